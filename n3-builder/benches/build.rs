@@ -0,0 +1,55 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use maplit::btreemap;
+
+use n3_builder::{ast, NodeRoot};
+
+const MODELS: &[(&str, &[u64])] = &[
+    ("LeNet5", &[1, 28, 28]),
+    ("Vgg", &[3, 32, 32]),
+    ("Deep", &[64]),
+];
+
+/// The number of the layers of `Deep`, which has many variables to be copied.
+const DEEP_LAYERS: usize = 256;
+
+fn new_root() -> NodeRoot {
+    let root = NodeRoot::new(Some("../n3-torch/ffi/python/n3"));
+    root.add_source("Deep".to_string(), deep_source());
+    root
+}
+
+fn deep_source() -> String {
+    let mut source = "node Deep:\n    0. Input = 64\n".to_string();
+    for i in 1..=DEEP_LAYERS {
+        source.push_str(&format!("    {}. Linear = 64\n", i));
+    }
+    source
+}
+
+fn input(dims: &[u64]) -> ast::Shapes {
+    let shape = ast::Shape(dims.iter().map(|&x| x.into()).collect());
+    ast::Shapes::new(btreemap! {"x".to_string() => Some(shape)})
+}
+
+fn bench_build(c: &mut Criterion) {
+    let mut group = c.benchmark_group("build");
+    for &(name, dims) in MODELS {
+        let input = input(dims);
+
+        // note: the sources are parsed on every iteration
+        group.bench_with_input(BenchmarkId::new("cold", name), &input, |b, input| {
+            b.iter(|| new_root().build_code(name, Some(input)).unwrap())
+        });
+
+        // note: the nodes are instantiated from the caches
+        let root = new_root();
+        root.build_code(name, Some(&input)).unwrap();
+        group.bench_with_input(BenchmarkId::new("cached", name), &input, |b, input| {
+            b.iter(|| root.build_code(name, Some(input)).unwrap())
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_build);
+criterion_main!(benches);
@@ -1,65 +1,132 @@
 use std::cell::RefCell;
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 use std::fs;
+use std::rc::Rc;
 
 use crate::context::{Build, CloneSafe};
 use crate::error::{Result, TensorNodeError};
 use crate::nodes::NodeRoot;
+use crate::suggest::suggest;
+use crate::variable::Bindings;
 
 pub struct NodeCache<T: Build> {
     paths: RefCell<HashMap<String, String>>,
-    caches_source: RefCell<HashMap<String, String>>,
-    caches: RefCell<HashMap<String, T::Output>>,
+    caches_source: RefCell<HashMap<String, T::Source>>,
+    /// The built nodes, which are immutable and shared by the instances
+    caches: RefCell<HashMap<String, Rc<T::Output>>>,
+    /// The nodes which have been built with each node
+    dependents: RefCell<HashMap<String, BTreeSet<String>>>,
+    building: RefCell<Vec<String>>,
 }
 
 impl<T: Build> NodeCache<T> {
-    pub fn new(caches: HashMap<String, String>) -> Self {
+    pub fn new(caches: HashMap<String, T::Source>) -> Self {
         Self {
             paths: RefCell::default(),
             caches_source: RefCell::new(caches),
             caches: RefCell::default(),
+            dependents: RefCell::default(),
+            building: RefCell::default(),
         }
     }
 
-    pub fn add_source(&self, name: String, source: String) {
+    pub fn add_source(&self, name: String, source: T::Source) -> BTreeSet<String> {
+        let invalidated = self.invalidate(&name);
         self.caches_source.borrow_mut().insert(name, source);
+        invalidated
     }
 
-    pub fn add_path(&self, name: String, path: String) {
+    pub fn remove_source(&self, name: &str) -> BTreeSet<String> {
+        let invalidated = self.invalidate(name);
+        self.caches_source.borrow_mut().remove(name);
+        invalidated
+    }
+
+    pub fn add_path(&self, name: String, path: String) -> BTreeSet<String> {
+        let invalidated = self.invalidate(&name);
         self.paths.borrow_mut().insert(name, path);
+        invalidated
+    }
+
+    /// Remove the built node and the nodes which depend on it.
+    /// Returns the names of the removed nodes.
+    pub fn invalidate(&self, name: &str) -> BTreeSet<String> {
+        let mut invalidated = BTreeSet::new();
+        let mut queue = vec![name.to_string()];
+        while let Some(name) = queue.pop() {
+            if invalidated.contains(&name) {
+                continue;
+            }
+            self.caches.borrow_mut().remove(&name);
+            if let Some(dependents) = self.dependents.borrow_mut().remove(&name) {
+                queue.extend(dependents);
+            }
+            invalidated.insert(name);
+        }
+        invalidated
+    }
+
+    pub fn get_path(&self, name: &str) -> Option<String> {
+        self.paths.borrow().get(name).cloned()
+    }
+
+    pub fn names(&self) -> BTreeSet<String> {
+        let mut names: BTreeSet<_> = self.paths.borrow().keys().cloned().collect();
+        names.extend(self.caches_source.borrow().keys().cloned());
+        names.extend(self.caches.borrow().keys().cloned());
+        names
     }
 
     pub fn get(&self, name: &str, root: &NodeRoot) -> Result<T::Output> {
-        if let Some(cache) = self.caches.borrow().get(name) {
-            let mut variables = vec![];
-            return Ok(cache.clone_safe(&root.seed, &mut variables));
+        if let Some(dependent) = self.building.borrow().last() {
+            self.dependents
+                .borrow_mut()
+                .entry(name.to_string())
+                .or_default()
+                .insert(dependent.clone());
         }
 
-        let path = self.paths.borrow_mut().remove(name);
-        if let Some(path) = path {
-            let source = fs::read_to_string(path)?;
-            return self.build_and_store(name, root, source);
+        let cache = self.caches.borrow().get(name).cloned();
+        if let Some(cache) = cache {
+            return Ok(Self::instantiate(&cache, root));
         }
 
-        let source = self.caches_source.borrow_mut().remove(name);
+        // note: the given sources take precedence over the files
+        // note: the sources are kept to rebuild the invalidated nodes
+        let source = self.caches_source.borrow().get(name).cloned();
         if let Some(source) = source {
             return self.build_and_store(name, root, source);
         }
 
+        let path = self.get_path(name);
+        if let Some(path) = path {
+            let source = fs::read_to_string(path)?;
+            return self.build_and_store(name, root, source.into());
+        }
+
         TensorNodeError::NoSuchNode {
             name: name.to_string(),
+            candidates: suggest(name, self.names()),
         }
         .into()
     }
 
-    fn build_and_store(&self, name: &str, root: &NodeRoot, source: String) -> Result<T::Output> {
+    fn build_and_store(&self, name: &str, root: &NodeRoot, source: T::Source) -> Result<T::Output> {
         // TODO: detect cycling
-        let result = T::build(root, name, source)?;
+        self.building.borrow_mut().push(name.to_string());
+        let result = T::build(root, name, source);
+        self.building.borrow_mut().pop();
+        let result = Rc::new(result?);
 
-        let mut variables = vec![];
-        let cloned = result.clone_safe(&root.seed, &mut variables);
+        self.caches
+            .borrow_mut()
+            .insert(name.to_string(), result.clone());
+        Ok(Self::instantiate(&result, root))
+    }
 
-        self.caches.borrow_mut().insert(name.to_string(), result);
-        Ok(cloned)
+    /// Copy the node with its own variables, leaving the cache untouched.
+    fn instantiate(cache: &T::Output, root: &NodeRoot) -> T::Output {
+        let mut variables = Bindings::default();
+        cache.clone_safe(&root.seed, &mut variables)
     }
 }
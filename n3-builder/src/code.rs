@@ -1,14 +1,20 @@
 use std::rc::Rc;
 
 use crate::error::Result;
-use crate::externs::PythonScripts;
+use crate::externs::{Backend, ExternScripts};
 use crate::nodes::NodeRoot;
 use crate::tensor::IRData;
 
 pub use n3_program::code::*;
 
 pub trait AddScripts {
-    fn add_scripts(&self, root: &NodeRoot, scripts: &mut PythonScripts) -> Result<()>;
+    /// Collect the implementations of the extern nodes, for the backend.
+    fn add_scripts(
+        &self,
+        root: &NodeRoot,
+        backend: Backend,
+        scripts: &mut ExternScripts,
+    ) -> Result<()>;
 }
 
 pub trait DataFromIR {
@@ -16,10 +22,15 @@ pub trait DataFromIR {
 }
 
 impl AddScripts for Code {
-    fn add_scripts(&self, root: &NodeRoot, scripts: &mut PythonScripts) -> Result<()> {
+    fn add_scripts(
+        &self,
+        root: &NodeRoot,
+        backend: Backend,
+        scripts: &mut ExternScripts,
+    ) -> Result<()> {
         match self {
-            Self::Node(node) => node.add_scripts(root, scripts),
-            Self::Extern(node) => node.add_scripts(root, scripts),
+            Self::Node(node) => node.add_scripts(root, backend, scripts),
+            Self::Extern(node) => node.add_scripts(root, backend, scripts),
         }
     }
 }
@@ -32,8 +43,8 @@ impl DataFromIR for CodeData {
                 .unwrap()
                 .into_inner()
                 .into_table(),
-            input: data.input,
-            output: data.output,
+            input: Rc::try_unwrap(data.input).unwrap_or_else(|x| (*x).clone()),
+            output: Rc::try_unwrap(data.output).unwrap_or_else(|x| (*x).clone()),
         }
     }
 }
@@ -2,12 +2,12 @@ use std::cell::RefCell;
 use std::collections::BTreeMap;
 use std::rc::Rc;
 
-use crate::ast;
 use crate::error::Result;
 use crate::graph::RefGraph;
 use crate::nodes::NodeRoot;
 use crate::seed::Seed;
 use crate::tensor::TensorNode;
+use crate::variable::Bindings;
 
 pub type NodeName = Vec<String>;
 type NodeNameRef<'a> = &'a [String];
@@ -46,14 +46,14 @@ impl<'a> Context<'a> {
         let mut parent = parent.to_vec();
         while !parent.is_empty() {
             if let Some(node) = self.children.get_mut(&parent).unwrap().get(name) {
-                let mut variables = vec![];
+                let mut variables = Bindings::default();
                 return Ok(node.clone_safe(&self.root.seed, &mut variables));
             }
             parent.pop();
         }
 
         if let Some(node) = self.uses.get(name) {
-            let mut variables = vec![];
+            let mut variables = Bindings::default();
             Ok(node.clone_safe(&self.root.seed, &mut variables))
         } else {
             Ok(self.root.get(name)?.into())
@@ -63,21 +63,23 @@ impl<'a> Context<'a> {
 
 pub trait Build: CloneSafe {
     type Output: CloneSafe;
+    /// The source to be built, which is read from the files as a text
+    type Source: Clone + From<String>;
 
-    fn build(root: &NodeRoot, name: &str, source: String) -> Result<Self::Output>
+    fn build(root: &NodeRoot, name: &str, source: Self::Source) -> Result<Self::Output>
     where
         Self: Sized;
 }
 
 pub trait CloneSafe {
-    fn clone_safe(&self, seed: &Seed, variables: &mut Vec<ast::RefVariable>) -> Self;
+    fn clone_safe(&self, seed: &Seed, variables: &mut Bindings) -> Self;
 }
 
 impl<T> CloneSafe for Rc<T>
 where
     T: CloneSafe,
 {
-    fn clone_safe(&self, seed: &Seed, variables: &mut Vec<ast::RefVariable>) -> Self {
+    fn clone_safe(&self, seed: &Seed, variables: &mut Bindings) -> Self {
         Rc::new((**self).clone_safe(seed, variables))
     }
 }
@@ -86,7 +88,7 @@ impl<T> CloneSafe for RefCell<T>
 where
     T: CloneSafe,
 {
-    fn clone_safe(&self, seed: &Seed, variables: &mut Vec<ast::RefVariable>) -> Self {
+    fn clone_safe(&self, seed: &Seed, variables: &mut Bindings) -> Self {
         RefCell::new(self.borrow().clone_safe(seed, variables))
     }
 }
@@ -95,7 +97,7 @@ impl<T> CloneSafe for BTreeMap<String, T>
 where
     T: CloneSafe,
 {
-    fn clone_safe(&self, seed: &Seed, variables: &mut Vec<ast::RefVariable>) -> Self {
+    fn clone_safe(&self, seed: &Seed, variables: &mut Bindings) -> Self {
         self.iter()
             .map(|(k, v)| (k.clone(), v.clone_safe(seed, variables)))
             .collect()
@@ -106,7 +108,7 @@ impl<T> CloneSafe for Vec<T>
 where
     T: CloneSafe,
 {
-    fn clone_safe(&self, seed: &Seed, variables: &mut Vec<ast::RefVariable>) -> Self {
+    fn clone_safe(&self, seed: &Seed, variables: &mut Bindings) -> Self {
         self.iter().map(|x| x.clone_safe(seed, variables)).collect()
     }
 }
@@ -115,7 +117,7 @@ impl<T> CloneSafe for Option<T>
 where
     T: CloneSafe,
 {
-    fn clone_safe(&self, seed: &Seed, variables: &mut Vec<ast::RefVariable>) -> Self {
+    fn clone_safe(&self, seed: &Seed, variables: &mut Bindings) -> Self {
         self.as_ref().map(|x| x.clone_safe(seed, variables))
     }
 }
@@ -124,7 +126,7 @@ impl<T> CloneSafe for Box<T>
 where
     T: CloneSafe,
 {
-    fn clone_safe(&self, seed: &Seed, variables: &mut Vec<ast::RefVariable>) -> Self {
+    fn clone_safe(&self, seed: &Seed, variables: &mut Bindings) -> Self {
         Box::new((**self).clone_safe(seed, variables))
     }
 }
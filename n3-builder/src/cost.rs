@@ -0,0 +1,189 @@
+use std::collections::BTreeMap;
+use std::iter::Sum;
+use std::ops::Add;
+
+use crate::ast;
+use crate::error::{Result, TensorNodeError};
+use crate::externs::ExternIR;
+use crate::graph::Graph;
+use crate::suggest::suggest;
+use crate::variable::BuildValue;
+
+/// The costs which can be declared in the `cost` section of the extern nodes.
+pub const COSTS: &[&str] = &["params", "flops", "memory"];
+
+/// The formulas of the declared costs, which refer to the variables of the extern node.
+pub type CostFormulas = BTreeMap<String, ast::Value>;
+
+/// The estimated cost of a node, for a sample.
+///
+/// Each cost is `None` if its formula is not resolved.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Cost {
+    /// The number of the parameters
+    pub params: Option<u64>,
+    /// The number of the floating point operations
+    pub flops: Option<u64>,
+    /// The number of the activation elements
+    pub memory: Option<u64>,
+}
+
+impl Default for Cost {
+    fn default() -> Self {
+        Self {
+            params: Some(0),
+            flops: Some(0),
+            memory: Some(0),
+        }
+    }
+}
+
+impl Add for Cost {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        let add = |lhs: Option<u64>, rhs: Option<u64>| Some(lhs? + rhs?);
+        Self {
+            params: add(self.params, rhs.params),
+            flops: add(self.flops, rhs.flops),
+            memory: add(self.memory, rhs.memory),
+        }
+    }
+}
+
+impl Sum for Cost {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::default(), Add::add)
+    }
+}
+
+impl<'a> From<&'a ExternIR> for Cost {
+    fn from(node: &'a ExternIR) -> Self {
+        let graph = node.data.graph.borrow();
+
+        // note: the undeclared costs are free
+        let get = |name| match node.cost.get(name) {
+            Some(value) => graph.rebind_value(value).build().unwrap_uint(),
+            None => Some(0),
+        };
+
+        Self {
+            params: get("params"),
+            flops: get("flops"),
+            memory: get("memory"),
+        }
+    }
+}
+
+/// Take the `cost` section, linking the formulas to the variables of the graph.
+///
+/// The formulas are kept apart from the variables, so that they are evaluated
+/// only on the analysis and are not passed to the runtime.
+pub(crate) fn build_cost(cost: &mut ast::Keywords, graph: &Graph) -> Result<CostFormulas> {
+    std::mem::take(cost)
+        .into_iter()
+        .map(|(name, value)| {
+            if !COSTS.contains(&name.as_str()) {
+                return TensorNodeError::NoSuchCost {
+                    candidates: suggest(&name, COSTS),
+                    name,
+                }
+                .into();
+            }
+            Ok((name, graph.link_value(&value)?))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::code::Code;
+    use crate::nodes::NodeRoot;
+
+    #[test]
+    fn test_cost_section() {
+        let root = NodeRoot::new(Some("../n3-torch/ffi/python/n3"));
+        let build = |name: &str, cost: &str| {
+            let model = format!(
+                "
+extern node {}:
+    let C: channels = dim
+
+    cost:
+{}
+
+    0. Input  = C
+    1. Output = C
+",
+                name, cost
+            );
+            root.add_source(name.to_string(), model);
+            root.inspect(name)
+        };
+
+        assert!(build("MyScale", "        set params = C\n        set flops = C").is_ok());
+
+        // the cost should not be passed to the runtime
+        let input = ast::Shapes::new(
+            Some(("x".to_string(), Some(ast::Shape(vec![4u64.into()]))))
+                .into_iter()
+                .collect(),
+        );
+        match root.build_code("MyScale", Some(&input)).unwrap() {
+            Code::Extern(code) => {
+                let names: Vec<_> = code.data.graph.variables.keys().collect();
+                assert_eq!(names, ["channels"]);
+            }
+            Code::Node(_) => unreachable!("MyScale is an extern node"),
+        }
+        assert_eq!(
+            build("MyFlops", "        set flop = C").err(),
+            Some(
+                TensorNodeError::NoSuchCost {
+                    name: "flop".to_string(),
+                    candidates: vec!["flops".to_string()].into_iter().collect(),
+                }
+                .into()
+            )
+        );
+
+        // the cost should be declared on the extern nodes only
+        let model = "
+node MyNode:
+    cost:
+        set params = 1
+
+    0. Input  = 1
+    1. Linear = 1
+";
+        root.add_source("MyNode".to_string(), model.to_string());
+        assert_eq!(
+            root.inspect("MyNode").err(),
+            Some(
+                TensorNodeError::UnexpectedCost {
+                    name: "MyNode".to_string(),
+                }
+                .into()
+            )
+        );
+    }
+
+    #[test]
+    fn test_sum() {
+        let cost = |params, flops, memory| Cost {
+            params,
+            flops,
+            memory,
+        };
+        assert_eq!(
+            vec![
+                cost(Some(1), Some(2), Some(3)),
+                cost(Some(4), None, Some(6))
+            ]
+            .into_iter()
+            .sum::<Cost>(),
+            cost(Some(5), None, Some(9))
+        );
+    }
+}
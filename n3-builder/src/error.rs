@@ -7,6 +7,7 @@ use n3_parser::error::ParseError;
 use n3_program::error::Error as CompactError;
 
 use crate::ast;
+use crate::externs::Backend;
 
 pub type Result<T> = std::result::Result<T, Error>;
 
@@ -17,6 +18,7 @@ pub enum Error {
     ExecBuildError(ExecBuildError),
     ExecError(ExecError),
     CompactError(CompactError),
+    LintError(LintError),
     ExternalError(ExternalError),
 }
 
@@ -38,6 +40,18 @@ pub enum ExecBuildError {
         expected: ast::LetNodeType,
         given: ast::LetNodeType,
     },
+    NoSuchPass {
+        name: String,
+        candidates: BTreeSet<String>,
+    },
+    NoSuchImplementation {
+        name: String,
+        backend: Backend,
+    },
+    NoSuchBackend {
+        name: String,
+        candidates: BTreeSet<String>,
+    },
 }
 
 #[derive(Debug, PartialEq)]
@@ -50,6 +64,7 @@ pub enum ExecError {
 pub enum TensorNodeError {
     NoSuchNode {
         name: String,
+        candidates: BTreeSet<String>,
     },
     MismatchedName {
         expected: String,
@@ -59,6 +74,13 @@ pub enum TensorNodeError {
         expected: ast::FinalNodeType,
         given: ast::FinalNodeType,
     },
+    UnexpectedCost {
+        name: String,
+    },
+    NoSuchCost {
+        name: String,
+        candidates: BTreeSet<String>,
+    },
 }
 
 #[derive(Debug, PartialEq)]
@@ -90,6 +112,11 @@ pub enum GraphError {
         expected: ast::LetType,
         given: Option<ast::LetType>,
     },
+    OutOfRange {
+        name: String,
+        value: Box<ast::Value>,
+        range: Box<ast::LetRange>,
+    },
 }
 
 #[derive(Debug, PartialEq)]
@@ -154,6 +181,7 @@ pub enum GraphCallError {
     MismatchedArgs {
         expected: &'static [&'static str],
         given: Vec<String>,
+        candidates: BTreeSet<String>,
     },
     MismatchedShapes {
         expected: usize,
@@ -175,6 +203,19 @@ pub enum LinkError {
         expected: ast::Shape,
         given: ast::Shape,
     },
+    UnsolvableDim {
+        name: String,
+        expected: ast::Value,
+        given: ast::Value,
+    },
+}
+
+#[derive(Debug, PartialEq)]
+pub enum LintError {
+    NoSuchLint {
+        name: String,
+        candidates: BTreeSet<String>,
+    },
 }
 
 #[derive(Debug)]
@@ -184,11 +225,233 @@ pub enum ExternalError {
     PatternError(PatternError),
 }
 
+impl Error {
+    /// Get the stable code of the error, which is explained by [`explain`].
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::ParseError(e) => e.code(),
+            Self::BuildError(e) => e.code(),
+            Self::ExecBuildError(e) => e.code(),
+            Self::ExecError(e) => e.code(),
+            Self::CompactError(e) => compact_error_code(e),
+            Self::LintError(e) => e.code(),
+            Self::ExternalError(e) => e.code(),
+        }
+    }
+}
+
+impl BuildError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::TensorNodeError(e) => e.code(),
+            Self::GraphError(e) => e.code(),
+            Self::GraphNodeError(e) => e.code(),
+            Self::GraphCallError(e) => e.code(),
+            Self::LinkError(e) => e.code(),
+        }
+    }
+}
+
+impl TensorNodeError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::NoSuchNode { .. } => "N3-E0101",
+            Self::MismatchedName { .. } => "N3-E0102",
+            Self::MismatchedType { .. } => "N3-E0103",
+            Self::UnexpectedCost { .. } => "N3-E0104",
+            Self::NoSuchCost { .. } => "N3-E0105",
+        }
+    }
+}
+
+impl GraphError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::NoSuchVariable { .. } => "N3-E0201",
+            Self::UnexpectedNodeVariable { .. } => "N3-E0202",
+            Self::UnparsableString { .. } => "N3-E0203",
+            Self::DuplicatedVariable { .. } => "N3-E0204",
+            Self::CycledVariables { .. } => "N3-E0205",
+            Self::EmptyValue { .. } => "N3-E0206",
+            Self::MismatchedType { .. } => "N3-E0207",
+            Self::OutOfRange { .. } => "N3-E0208",
+        }
+    }
+}
+
+impl GraphNodeError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::EmptyCalls => "N3-E0301",
+            Self::UnexpectedShapes => "N3-E0302",
+            Self::NoSuchInput { .. } => "N3-E0303",
+            Self::MismatchedId { .. } => "N3-E0304",
+            Self::MismatchedSize { .. } => "N3-E0305",
+            Self::MismatchedShapesExistence { .. } => "N3-E0306",
+        }
+    }
+}
+
+impl GraphCallError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::EmptyInputs => "N3-E0401",
+            Self::UnexpectedInputs => "N3-E0402",
+            Self::UnexpectedArgs => "N3-E0403",
+            Self::UnexpectedRepeat => "N3-E0404",
+            Self::GenericListInputShape { .. } => "N3-E0405",
+            Self::GenericShape { .. } => "N3-E0406",
+            Self::GenericShapes => "N3-E0407",
+            Self::MismatchedName { .. } => "N3-E0408",
+            Self::MismatchedSize { .. } => "N3-E0409",
+            Self::MismatchedInputsType { .. } => "N3-E0410",
+            Self::MismatchedRepeat { .. } => "N3-E0411",
+            Self::MismatchedAxis { .. } => "N3-E0412",
+            Self::MismatchedArgType { .. } => "N3-E0413",
+            Self::MismatchedArgs { .. } => "N3-E0414",
+            Self::MismatchedShapes { .. } => "N3-E0415",
+            Self::MismatchedShapeKeys { .. } => "N3-E0416",
+        }
+    }
+}
+
+impl LinkError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::MismatchedDim { .. } => "N3-E0501",
+            Self::MismatchedShape { .. } => "N3-E0502",
+            Self::UnsolvableDim { .. } => "N3-E0503",
+        }
+    }
+}
+
+impl ExecBuildError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::UnexpectedWiths => "N3-E0601",
+            Self::UnexpectedChildren => "N3-E0602",
+            Self::EmptyGraph => "N3-E0603",
+            Self::MismatchedNodeType { .. } => "N3-E0604",
+            Self::NoSuchPass { .. } => "N3-E0605",
+            Self::NoSuchImplementation { .. } => "N3-E0606",
+            Self::NoSuchBackend { .. } => "N3-E0607",
+        }
+    }
+}
+
+impl ExecError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::NoSuchDirectory { .. } => "N3-E0701",
+            Self::NotDirectory { .. } => "N3-E0702",
+        }
+    }
+}
+
+impl LintError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::NoSuchLint { .. } => "N3-E0801",
+        }
+    }
+}
+
+impl ExternalError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::IOError(_) => "N3-E0901",
+            Self::GlobError(_) => "N3-E0902",
+            Self::PatternError(_) => "N3-E0903",
+        }
+    }
+}
+
+fn compact_error_code(error: &CompactError) -> &'static str {
+    match error {
+        CompactError::MismatchedVersion { .. } => "N3-E0905",
+        CompactError::NotProgram | CompactError::CorruptedProgram => "N3-E0906",
+        CompactError::IOError(_) => "N3-E0901",
+        CompactError::BincodeError(_) | CompactError::JsonError(_) | CompactError::YamlError(_) => {
+            "N3-E0904"
+        }
+    }
+}
+
+/// The long-form explanations of the error codes, with the examples.
+pub static EXPLANATIONS: &[(&str, &str)] = &[
+    ("N3-E0101", include_str!("../errors/N3-E0101.md")),
+    ("N3-E0102", include_str!("../errors/N3-E0102.md")),
+    ("N3-E0103", include_str!("../errors/N3-E0103.md")),
+    ("N3-E0104", include_str!("../errors/N3-E0104.md")),
+    ("N3-E0105", include_str!("../errors/N3-E0105.md")),
+    ("N3-E0201", include_str!("../errors/N3-E0201.md")),
+    ("N3-E0202", include_str!("../errors/N3-E0202.md")),
+    ("N3-E0203", include_str!("../errors/N3-E0203.md")),
+    ("N3-E0204", include_str!("../errors/N3-E0204.md")),
+    ("N3-E0205", include_str!("../errors/N3-E0205.md")),
+    ("N3-E0206", include_str!("../errors/N3-E0206.md")),
+    ("N3-E0207", include_str!("../errors/N3-E0207.md")),
+    ("N3-E0208", include_str!("../errors/N3-E0208.md")),
+    ("N3-E0301", include_str!("../errors/N3-E0301.md")),
+    ("N3-E0302", include_str!("../errors/N3-E0302.md")),
+    ("N3-E0303", include_str!("../errors/N3-E0303.md")),
+    ("N3-E0304", include_str!("../errors/N3-E0304.md")),
+    ("N3-E0305", include_str!("../errors/N3-E0305.md")),
+    ("N3-E0306", include_str!("../errors/N3-E0306.md")),
+    ("N3-E0401", include_str!("../errors/N3-E0401.md")),
+    ("N3-E0402", include_str!("../errors/N3-E0402.md")),
+    ("N3-E0403", include_str!("../errors/N3-E0403.md")),
+    ("N3-E0404", include_str!("../errors/N3-E0404.md")),
+    ("N3-E0405", include_str!("../errors/N3-E0405.md")),
+    ("N3-E0406", include_str!("../errors/N3-E0406.md")),
+    ("N3-E0407", include_str!("../errors/N3-E0407.md")),
+    ("N3-E0408", include_str!("../errors/N3-E0408.md")),
+    ("N3-E0409", include_str!("../errors/N3-E0409.md")),
+    ("N3-E0410", include_str!("../errors/N3-E0410.md")),
+    ("N3-E0411", include_str!("../errors/N3-E0411.md")),
+    ("N3-E0412", include_str!("../errors/N3-E0412.md")),
+    ("N3-E0413", include_str!("../errors/N3-E0413.md")),
+    ("N3-E0414", include_str!("../errors/N3-E0414.md")),
+    ("N3-E0415", include_str!("../errors/N3-E0415.md")),
+    ("N3-E0416", include_str!("../errors/N3-E0416.md")),
+    ("N3-E0501", include_str!("../errors/N3-E0501.md")),
+    ("N3-E0502", include_str!("../errors/N3-E0502.md")),
+    ("N3-E0503", include_str!("../errors/N3-E0503.md")),
+    ("N3-E0601", include_str!("../errors/N3-E0601.md")),
+    ("N3-E0602", include_str!("../errors/N3-E0602.md")),
+    ("N3-E0603", include_str!("../errors/N3-E0603.md")),
+    ("N3-E0604", include_str!("../errors/N3-E0604.md")),
+    ("N3-E0605", include_str!("../errors/N3-E0605.md")),
+    ("N3-E0606", include_str!("../errors/N3-E0606.md")),
+    ("N3-E0607", include_str!("../errors/N3-E0607.md")),
+    ("N3-E0701", include_str!("../errors/N3-E0701.md")),
+    ("N3-E0702", include_str!("../errors/N3-E0702.md")),
+    ("N3-E0801", include_str!("../errors/N3-E0801.md")),
+    ("N3-E0901", include_str!("../errors/N3-E0901.md")),
+    ("N3-E0902", include_str!("../errors/N3-E0902.md")),
+    ("N3-E0903", include_str!("../errors/N3-E0903.md")),
+    ("N3-E0904", include_str!("../errors/N3-E0904.md")),
+    ("N3-E0905", include_str!("../errors/N3-E0905.md")),
+    ("N3-E0906", include_str!("../errors/N3-E0906.md")),
+];
+
+/// Get the long-form explanation of the error code.
+pub fn explain(code: &str) -> Option<&'static str> {
+    explanations().find(|(k, _)| *k == code).map(|(_, v)| *v)
+}
+
+/// Iterate the explanations, including the ones of the parser.
+pub fn explanations() -> impl Iterator<Item = &'static (&'static str, &'static str)> {
+    n3_parser::error::EXPLANATIONS.iter().chain(EXPLANATIONS)
+}
+
 impl PartialEq for Error {
     fn eq(&self, other: &Self) -> bool {
         match (self, other) {
             (Self::ParseError(a), Self::ParseError(b)) => a.eq(b),
             (Self::BuildError(a), Self::BuildError(b)) => a.eq(b),
+            (Self::ExecBuildError(a), Self::ExecBuildError(b)) => a.eq(b),
+            (Self::LintError(a), Self::LintError(b)) => a.eq(b),
             (Self::ExternalError(a), Self::ExternalError(b)) => a.eq(b),
             _ => false,
         }
@@ -218,6 +481,17 @@ impl PartialEq for LinkError {
                     expected: _,
                     given: _,
                 },
+            ) | (
+                Self::UnsolvableDim {
+                    name: _,
+                    expected: _,
+                    given: _,
+                },
+                Self::UnsolvableDim {
+                    name: _,
+                    expected: _,
+                    given: _,
+                },
             ),
         )
     }
@@ -265,6 +539,12 @@ impl From<CompactError> for Error {
     }
 }
 
+impl From<LintError> for Error {
+    fn from(error: LintError) -> Self {
+        Self::LintError(error)
+    }
+}
+
 impl<T> From<ExecBuildError> for Result<T> {
     fn from(error: ExecBuildError) -> Self {
         Err(Error::from(error))
@@ -277,6 +557,12 @@ impl<T> From<ExecError> for Result<T> {
     }
 }
 
+impl<T> From<LintError> for Result<T> {
+    fn from(error: LintError) -> Self {
+        Err(Error::from(error))
+    }
+}
+
 macro_rules! impl_into_build_error(
     ($t:ident) => {
         impl From<$t> for BuildError {
@@ -338,3 +624,42 @@ impl From<std::io::Error> for Error {
         Self::ExternalError(ExternalError::IOError(error))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_explanations() {
+        let codes: Vec<_> = EXPLANATIONS.iter().map(|(k, _)| *k).collect();
+        let mut sorted = codes.clone();
+        sorted.sort_unstable();
+        sorted.dedup();
+        assert_eq!(codes, sorted);
+
+        for (code, explanation) in EXPLANATIONS {
+            assert!(code.starts_with("N3-E0"), "{}", code);
+            assert!(explanation.ends_with('\n'), "{}", code);
+        }
+    }
+
+    #[test]
+    fn test_explain() {
+        let error: Error = GraphError::DuplicatedVariable {
+            name: "x".to_string(),
+        }
+        .into();
+        assert_eq!(error.code(), "N3-E0204");
+        assert!(explain(error.code()).is_some());
+
+        // the parser has its own codes
+        let error: Error = crate::Parser::default()
+            .parse_file("node MyNode:\n")
+            .unwrap_err()
+            .into();
+        assert_eq!(error.code(), "N3-E0001");
+        assert!(explain(error.code()).is_some());
+
+        assert_eq!(explain("N3-E9999"), None);
+    }
+}
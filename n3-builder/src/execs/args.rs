@@ -5,11 +5,16 @@ use super::program::Program;
 use super::root::ExecRoot;
 use super::var::Vars;
 use crate::error::Result;
+use crate::externs::Backend;
+use crate::nodes::NodeRoot;
+use crate::optimize::Pipeline;
 
 pub struct Args<'a> {
     pub(super) root: &'a mut ExecRoot,
     pub(super) ir: ExecIR,
     pub(super) args: Vars,
+    pub(super) pipeline: Pipeline,
+    pub(super) backend: Backend,
 }
 
 impl<'a> Deref for Args<'a> {
@@ -21,14 +26,41 @@ impl<'a> Deref for Args<'a> {
 }
 
 impl<'a> Args<'a> {
+    pub fn description(&self) -> Option<&str> {
+        self.ir.description.as_deref()
+    }
+
+    /// The optimization passes, which are applied when the program is built.
+    pub fn pipeline_mut(&mut self) -> &mut Pipeline {
+        &mut self.pipeline
+    }
+
+    pub fn root(&self) -> &NodeRoot {
+        self.root
+    }
+
+    /// The backend which the extern nodes should be implemented for.
+    pub fn set_backend(&mut self, backend: Backend) {
+        self.backend = backend;
+    }
+
     pub fn build_uncompacted(self) -> Result<Program> {
-        self.ir.build(&self.root)
+        let mut program = self.ir.build(&self.root, self.backend)?;
+        self.pipeline.run(&mut program.nodes);
+        Ok(program)
     }
 
-    pub fn build_with_env(self) -> Result<Vec<u8>> {
-        let mut program = self.ir.build(&self.root)?;
+    pub fn build_uncompacted_with_env(self) -> Result<Program> {
+        let mut program = self.ir.build(&self.root, self.backend)?;
+        self.pipeline.run(&mut program.nodes);
         self.root.attach_env(&mut program);
-        program.save_to_binary().map_err(|e| e.into())
+        Ok(program)
+    }
+
+    pub fn build_with_env(self) -> Result<Vec<u8>> {
+        self.build_uncompacted_with_env()?
+            .save_to_binary()
+            .map_err(|e| e.into())
     }
 
     pub fn build(self) -> Result<Vec<u8>> {
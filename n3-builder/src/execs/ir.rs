@@ -7,14 +7,16 @@ use crate::ast;
 use crate::code::AddScripts;
 use crate::context::CloneSafe;
 use crate::error::{ExecBuildError, GraphError, Result};
+use crate::externs::Backend;
 use crate::nodes::NodeRoot;
 use crate::seed::Seed;
 use crate::tensor::IRData;
-use crate::variable::Link;
+use crate::variable::{assert_in_range, Bindings, Link};
 
 #[derive(Debug)]
 pub struct ExecIR {
     pub data: IRData,
+    pub description: Option<String>,
     pub links: Vec<Vec<String>>,
 }
 
@@ -29,7 +31,8 @@ impl ExecIR {
         Vars::from(self.data.graph.borrow().variables().clone())
     }
 
-    pub fn build(self, root: &NodeRoot) -> Result<Program> {
+    /// Build the program, with the implementations of the given backend.
+    pub fn build(self, root: &NodeRoot, backend: Backend) -> Result<Program> {
         // prune graph
         let mut nodes = BTreeMap::new();
 
@@ -81,6 +84,9 @@ impl ExecIR {
                     nodes.insert(var_name, node);
                     None
                 } else {
+                    if let Err(e) = assert_in_range(&var_ref) {
+                        return Some(Err(e));
+                    }
                     drop(var_ref);
                     Some(Ok((var_name, var)))
                 }
@@ -110,26 +116,29 @@ impl ExecIR {
         // get extern scripts
         let mut scripts = BTreeMap::new();
         for node in nodes.values() {
-            node.add_scripts(root, &mut scripts)?;
+            node.add_scripts(root, backend, &mut scripts)?;
         }
 
         // add exec script
-        let script = root.get_extern(&self.data.name)?;
+        let script = root.get_extern(backend, &self.data.name)?;
         scripts.insert(PROGRAM_MAIN.to_string(), script);
 
         Ok(Program {
             env: None,
             graph,
             nodes,
+            links: self.links,
+            backend,
             scripts,
         })
     }
 }
 
 impl CloneSafe for ExecIR {
-    fn clone_safe(&self, seed: &Seed, variables: &mut Vec<ast::RefVariable>) -> Self {
+    fn clone_safe(&self, seed: &Seed, variables: &mut Bindings) -> Self {
         Self {
             data: self.data.clone_safe(seed, variables),
+            description: self.description.clone(),
             links: self.links.clone(),
         }
     }
@@ -8,13 +8,18 @@ pub use n3_program::execs::dirs;
 
 pub use self::args::Args;
 pub use self::ir::ExecIR;
-pub use self::program::Program;
+pub use self::program::{CompactedProgram, Program, ProgramFormat, PROGRAM_FORMATS};
 pub use self::root::{ExecRoot, ExecRootConfig};
 pub use self::var::{GlobalVars, Vars, QUERY_SPLIT_1};
 
 #[cfg(test)]
 mod tests {
+    use n3_program::compact::FORMAT_VERSION;
+    use n3_program::error::Error as CompactError;
+
     use super::*;
+    use crate::error::ExecBuildError;
+    use crate::{parse_backend, Backend, GraphFormat, RenderGraph};
 
     #[test]
     fn test_build_ic() {
@@ -32,6 +37,16 @@ mod tests {
 
         let program = args.build_uncompacted().unwrap();
 
+        // rendering the links
+        {
+            let graph = program.render_graph(GraphFormat::Dot);
+            assert_eq!(
+                program.links,
+                vec![vec!["data", "model", "loss", "optimizer"]]
+            );
+            assert!(graph.contains("    subgraph cluster_1 {\n        label=\"model: LeNet5\";\n"));
+        }
+
         // compacting & decompacting
         {
             let binary = program.save_to_binary().unwrap();
@@ -48,5 +63,199 @@ mod tests {
 
             assert_eq!(program, program_decompacted);
         }
+
+        // broken binaries
+        {
+            let binary = program.save_to_binary().unwrap();
+            let load = |binary: &[u8]| Program::load(binary).err();
+
+            assert_eq!(load(b"PK\x03\x04"), Some(CompactError::NotProgram));
+            assert_eq!(
+                load(&binary[..binary.len() - 1]),
+                Some(CompactError::CorruptedProgram)
+            );
+
+            let mut corrupted = binary.clone();
+            *corrupted.last_mut().unwrap() ^= 0xff;
+            assert_eq!(load(&corrupted), Some(CompactError::CorruptedProgram));
+
+            let mut outdated = binary;
+            outdated[4..8].copy_from_slice(&0u32.to_le_bytes());
+            assert_eq!(
+                load(&outdated),
+                Some(CompactError::MismatchedVersion {
+                    expected: FORMAT_VERSION,
+                    given: 0,
+                })
+            );
+        }
+
+        // the human-readable formats
+        for &format in &[ProgramFormat::Json, ProgramFormat::Yaml] {
+            let mut text = vec![];
+            program.save_as(&mut text, format).unwrap();
+            let program_decompacted = Program::load_as(&*text, format).unwrap();
+
+            assert_eq!(program, program_decompacted);
+        }
+    }
+
+    #[test]
+    fn test_program_hash() {
+        let envs = GlobalVars::default();
+        envs.set(dirs::N3_ROOT, "tests/data/").unwrap();
+        envs.set(dirs::N3_SOURCE_ROOT, "../n3-torch/ffi/python/n3")
+            .unwrap();
+        let mut root = ExecRoot::try_new(envs, Default::default()).unwrap();
+
+        let mut build = |epoch: &str| {
+            let args = root.get("DummyImageClassification").unwrap();
+            args.set("data", "Mnist").unwrap();
+            args.set("model", "LeNet5").unwrap();
+            args.set("epoch", epoch).unwrap();
+            args.set("batch size", "10").unwrap();
+            args.build_uncompacted_with_env().unwrap()
+        };
+
+        let program = build("1");
+        let hash = program.hash().unwrap();
+
+        // the seeds are not reset, so the ids are generated differently
+        assert_eq!(build("1").hash().unwrap(), hash);
+        assert_ne!(build("2").hash().unwrap(), hash);
+
+        // compacting & decompacting
+        let binary = program.save_to_binary().unwrap();
+        let program_decompacted = Program::load(&*binary).unwrap();
+        assert_eq!(program_decompacted.hash().unwrap(), hash);
+    }
+
+    #[test]
+    fn test_build_backend() {
+        let envs = GlobalVars::default();
+        envs.set(dirs::N3_ROOT, "tests/data/").unwrap();
+        envs.set(dirs::N3_SOURCE_ROOT, "../n3-torch/ffi/python/n3")
+            .unwrap();
+        let mut root = ExecRoot::try_new(envs, Default::default()).unwrap();
+
+        let build = |root: &mut ExecRoot, backend: Backend| {
+            let mut args = root.get("DummyImageClassification").unwrap();
+            args.set("data", "Mnist").unwrap();
+            args.set("model", "LeNet5").unwrap();
+            args.set("epoch", "1").unwrap();
+            args.set("batch size", "10").unwrap();
+            args.set_backend(backend);
+            args.build_uncompacted()
+        };
+
+        let program_torch = build(&mut root, Backend::Torch).unwrap();
+        assert_eq!(program_torch.backend, Backend::Torch);
+
+        // the nodes are not implemented yet
+        assert_eq!(
+            build(&mut root, Backend::NdArray).err(),
+            Some(
+                ExecBuildError::NoSuchImplementation {
+                    name: "Mnist".to_string(),
+                    backend: Backend::NdArray,
+                }
+                .into()
+            )
+        );
+
+        // implement the nodes natively, by their names
+        for name in root.get_extern_names(Backend::Torch) {
+            root.add_extern(Backend::NdArray, name.clone(), name);
+        }
+
+        let program = build(&mut root, Backend::NdArray).unwrap();
+        assert_eq!(program.backend, Backend::NdArray);
+        assert_eq!(
+            program.scripts.keys().collect::<Vec<_>>(),
+            program_torch.scripts.keys().collect::<Vec<_>>()
+        );
+        assert_eq!(program.scripts["Linear"].source, "Linear");
+        assert_ne!(program.hash().unwrap(), program_torch.hash().unwrap());
+
+        // compacting & decompacting
+        let binary = program.save_to_binary().unwrap();
+        let program_decompacted = Program::load(&*binary).unwrap();
+        assert_eq!(program_decompacted.backend, Backend::NdArray);
+
+        // the backends are parsed by their names
+        assert_eq!(parse_backend("ndarray").unwrap(), Backend::NdArray);
+        assert_eq!(
+            parse_backend("ndarry").err(),
+            Some(
+                ExecBuildError::NoSuchBackend {
+                    name: "ndarry".to_string(),
+                    candidates: vec!["ndarray".to_string()].into_iter().collect(),
+                }
+                .into()
+            )
+        );
+    }
+
+    #[test]
+    fn test_share_codes() {
+        let envs = GlobalVars::default();
+        envs.set(dirs::N3_ROOT, "tests/data/").unwrap();
+        envs.set(dirs::N3_SOURCE_ROOT, "../n3-torch/ffi/python/n3")
+            .unwrap();
+        let mut root = ExecRoot::try_new(envs, Default::default()).unwrap();
+
+        let mut build = |repeat: u64| {
+            let model = format!(
+                "
+node MyModel:
+    let R: repeat = int {}
+
+    let C: input channels = dim
+    let W: width = dim
+    let H: height = dim
+
+    with Conv2D:
+        set kernel size = 3
+        set padding = 1
+
+    node ConvBlock:
+        1. Conv2D
+        2. BatchNorm2D
+        3. Relu
+
+    0. Input            =  C, W, H
+    1. ConvBlock * R    = 16, W, H
+    2. ToLinear
+    3. Linear           = 10
+",
+                repeat
+            );
+            root.add_source("MyModel".to_string(), model);
+
+            let args = root.get("DummyImageClassification").unwrap();
+            args.set("data", "Mnist").unwrap();
+            args.set("model", "MyModel").unwrap();
+            args.set("epoch", "1").unwrap();
+            args.set("batch size", "10").unwrap();
+            let program = args.build_uncompacted().unwrap();
+
+            let binary = program.save_to_binary().unwrap();
+            let compacted = CompactedProgram::load(&*binary).unwrap();
+            assert_eq!(Program::load(&*binary).unwrap(), program);
+            (binary.len(), compacted.num_defs())
+        };
+
+        // the first block differs from the others, as it takes `C` channels
+        let (size_1, defs_1) = build(1);
+        let (size_2, defs_2) = build(2);
+        let (size_4, defs_4) = build(4);
+        let (size_8, defs_8) = build(8);
+        assert!(defs_1 < defs_2);
+        assert_eq!(defs_2, defs_4);
+        assert_eq!(defs_2, defs_8);
+
+        // the repeated blocks only add their own tables
+        assert!((size_8 - size_4) / 4 < size_2 - size_1);
+        assert_eq!((size_8 - size_4) / 4, (size_4 - size_2) / 2);
     }
 }
@@ -1 +1,2 @@
-pub use n3_program::execs::{Program, PROGRAM_MAIN};
+pub use n3_program::compact::Program as CompactedProgram;
+pub use n3_program::execs::{Program, ProgramFormat, PROGRAM_FORMATS, PROGRAM_MAIN};
@@ -7,6 +7,7 @@ use super::dirs::*;
 use super::program::Program;
 use super::var::GlobalVars;
 use crate::error::{ExecError, Result};
+use crate::externs::{Backend, BACKENDS};
 use crate::graph::ToValues;
 use crate::n3_std::trim_path;
 use crate::nodes::NodeRoot;
@@ -62,6 +63,8 @@ impl ExecRoot {
             root: self,
             ir,
             args,
+            pipeline: Default::default(),
+            backend: Default::default(),
         })
     }
 
@@ -95,10 +98,16 @@ impl ExecRoot {
             let path = path?;
             let name = trim_path(&path);
 
-            let extern_path = path.with_extension("py");
-            if extern_path.exists() {
-                let path_str = extern_path.display().to_string();
-                self.node_root.add_extern_path(name.clone(), path_str);
+            for backend in BACKENDS.iter().map(|x| x.parse::<Backend>().unwrap()) {
+                let extern_path = match backend.extension() {
+                    Some(extension) => path.with_extension(extension),
+                    None => continue,
+                };
+                if extern_path.exists() {
+                    let path_str = extern_path.display().to_string();
+                    self.node_root
+                        .add_extern_path(backend, name.clone(), path_str);
+                }
             }
 
             let path_str = path.display().to_string();
@@ -1,6 +1,8 @@
 use crate::ast;
 use crate::error::{GraphError, Result};
 use crate::graph::{ToValues, Values, Variables};
+use crate::suggest::suggest;
+use crate::variable::assert_in_range;
 
 use inflector::Inflector;
 
@@ -62,7 +64,7 @@ impl Vars {
         self.inner.get(name).ok_or_else(|| {
             GraphError::NoSuchVariable {
                 name: name.to_string(),
-                candidates: self.inner.keys().cloned().collect(),
+                candidates: suggest(name, self.inner.keys()),
             }
             .into()
         })
@@ -157,7 +159,7 @@ impl Vars {
     pub fn set(&self, name: &str, value: &str) -> Result<()> {
         let mut var = self.get(name)?.borrow_mut();
         var.value = Some(Self::encode(name, value.to_string(), var.ty.as_ref())?);
-        Ok(())
+        assert_in_range(&var)
     }
 
     pub fn set_as_value(&self, name: &str, value: impl Into<ast::Value>) -> Result<()> {
@@ -165,6 +167,14 @@ impl Vars {
 
         let mut var = self.get(name)?.borrow_mut();
 
+        // the enum variants are given as String
+        if let Some(ast::LetType::Enum(_)) = &var.ty {
+            if let ast::Value::String(value) = value {
+                var.value = Some(Self::encode(name, value, var.ty.as_ref())?);
+                return assert_in_range(&var);
+            }
+        }
+
         let expected = &var.ty;
         let given = &value.ty();
         if expected != given {
@@ -177,7 +187,7 @@ impl Vars {
         }
 
         var.value = Some(value);
-        Ok(())
+        assert_in_range(&var)
     }
 
     fn encode(name: &str, value: String, ty: Option<&ast::LetType>) -> Result<ast::Value> {
@@ -213,6 +223,13 @@ impl Vars {
                 Err(_) => unparsable_string(name, value, ty),
             },
             Some(ast::LetType::String) => Ok(ast::Value::String(value)),
+            Some(ast::LetType::Enum(variants)) => {
+                if variants.contains(&value) {
+                    Ok(ast::Value::String(value))
+                } else {
+                    unparsable_string(name, value, ty)
+                }
+            }
             Some(ast::LetType::Node(_)) => Ok(ast::Value::Node(value.to_pascal_case())),
             _ => unparsable_string(name, value, ty),
         }
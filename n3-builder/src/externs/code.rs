@@ -1,15 +1,21 @@
-use super::script::PythonScripts;
+use super::registry::Backend;
+use super::script::ExternScripts;
 use crate::code::AddScripts;
 use crate::error::Result;
 use crate::nodes::NodeRoot;
 
-pub use n3_program::externs::ExternCode;
+pub use n3_program::externs::{ExternCode, ExternCodeShapes};
 
 impl AddScripts for ExternCode {
-    fn add_scripts(&self, root: &NodeRoot, scripts: &mut PythonScripts) -> Result<()> {
+    fn add_scripts(
+        &self,
+        root: &NodeRoot,
+        backend: Backend,
+        scripts: &mut ExternScripts,
+    ) -> Result<()> {
         let name = &self.data.name;
         if !scripts.contains_key(name) {
-            let script = root.get_extern(name)?;
+            let script = root.get_extern(backend, name)?;
             scripts.insert(name.clone(), script);
         }
         Ok(())
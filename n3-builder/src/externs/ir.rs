@@ -1,18 +1,24 @@
-use super::code::ExternCode;
+use std::rc::Rc;
+
+use super::code::{ExternCode, ExternCodeShapes};
 use crate::ast;
 use crate::code::{CodeData, DataFromIR};
 use crate::context::CloneSafe;
+use crate::cost::CostFormulas;
 use crate::error::Result;
 use crate::graph::RefGraph;
 use crate::seed::Seed;
 use crate::tensor::IRData;
-use crate::variable::CloneValue;
+use crate::variable::{build_shapes, Bindings, CloneValue};
 
 #[derive(Debug, PartialEq)]
 pub struct ExternIR {
     pub ty: ast::ExternNodeType,
     pub data: IRData,
     pub shapes: ExternIRShapes,
+    /// The formulas of the costs, which are not a part of the program.
+    /// They are shared by the instances, and rebound to the graph on evaluation.
+    pub cost: Rc<CostFormulas>,
 }
 
 #[derive(Debug, PartialEq)]
@@ -46,6 +52,7 @@ impl ExternIR {
             ty,
             data: IRData::with_shapes(name, graph, input.as_ref(), output.as_ref()),
             shapes: ExternIRShapes { input, output },
+            cost: Default::default(),
         }
     }
 
@@ -58,26 +65,34 @@ impl ExternIR {
     }
 
     pub fn build(self) -> Result<ExternCode> {
+        self.data.graph.borrow().assert_in_range()?;
         Ok(ExternCode {
             ty: self.ty,
             data: CodeData::from_ir(self.data),
+            shapes: ExternCodeShapes {
+                input: self.shapes.input.as_ref().map(build_shapes),
+                output: self.shapes.output.as_ref().map(build_shapes),
+            },
         })
     }
 }
 
 impl CloneSafe for ExternIR {
-    fn clone_safe(&self, seed: &Seed, variables: &mut Vec<ast::RefVariable>) -> Self {
+    fn clone_safe(&self, seed: &Seed, variables: &mut Bindings) -> Self {
         // note: ordered (data -> shapes)
+        let data = self.data.clone_safe(seed, variables);
+        let shapes = self.shapes.clone_safe(seed, variables);
         Self {
             ty: self.ty,
-            data: self.data.clone_safe(seed, variables),
-            shapes: self.shapes.clone_safe(seed, variables),
+            data,
+            shapes,
+            cost: self.cost.clone(),
         }
     }
 }
 
 impl CloneSafe for ExternIRShapes {
-    fn clone_safe(&self, _: &Seed, variables: &mut Vec<ast::RefVariable>) -> Self {
+    fn clone_safe(&self, _: &Seed, variables: &mut Bindings) -> Self {
         Self {
             input: self.input.as_ref().map(|x| x.clone_value(variables)),
             output: self.output.as_ref().map(|x| x.clone_value(variables)),
@@ -1,7 +1,9 @@
 mod code;
 mod ir;
+mod registry;
 mod script;
 
 pub use self::code::ExternCode;
 pub use self::ir::{ExternIR, ExternIRShapes};
-pub use self::script::{PythonScript, PythonScripts};
+pub use self::registry::{parse_backend, Backend, ExternRegistry, BACKENDS};
+pub use self::script::{ExternScript, ExternScripts};
@@ -0,0 +1,66 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+use super::script::ExternScript;
+use crate::cache::NodeCache;
+use crate::error::{ExecBuildError, Result};
+use crate::n3_std;
+use crate::nodes::NodeRoot;
+use crate::suggest::suggest;
+
+pub use n3_program::externs::{Backend, BACKENDS};
+
+/// Parse the name of the backend, like `torch`.
+pub fn parse_backend(name: &str) -> Result<Backend> {
+    name.parse().or_else(|_| {
+        ExecBuildError::NoSuchBackend {
+            name: name.to_string(),
+            candidates: suggest(name, BACKENDS),
+        }
+        .into()
+    })
+}
+
+/// The implementations of the extern nodes, for each backend.
+pub struct ExternRegistry {
+    backends: BTreeMap<Backend, NodeCache<ExternScript>>,
+}
+
+impl ExternRegistry {
+    /// Load the std implementations, of the backends which are written in the files.
+    pub fn new(n3_source_root: &Path) -> Self {
+        Self {
+            backends: BACKENDS
+                .iter()
+                .map(|x| x.parse().unwrap())
+                .map(|backend: Backend| {
+                    let sources = backend
+                        .extension()
+                        .map(|x| n3_std::get_externs(n3_source_root, x))
+                        .unwrap_or_default();
+                    (backend, NodeCache::new(sources))
+                })
+                .collect(),
+        }
+    }
+
+    pub fn add_source(&self, backend: Backend, name: String, source: String) {
+        self.backends[&backend].add_source(name, source);
+    }
+
+    pub fn add_path(&self, backend: Backend, name: String, path: String) {
+        self.backends[&backend].add_path(name, path);
+    }
+
+    pub fn names(&self, backend: Backend) -> BTreeSet<String> {
+        self.backends[&backend].names()
+    }
+
+    pub fn contains(&self, backend: Backend, name: &str) -> bool {
+        self.names(backend).contains(name)
+    }
+
+    pub fn get(&self, backend: Backend, name: &str, root: &NodeRoot) -> Result<ExternScript> {
+        self.backends[&backend].get(name, root)
+    }
+}
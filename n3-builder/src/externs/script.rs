@@ -1,25 +1,26 @@
-use crate::ast;
 use crate::context::{Build, CloneSafe};
 use crate::error::Result;
 use crate::nodes::NodeRoot;
 use crate::seed::Seed;
+use crate::variable::Bindings;
 
-pub use n3_program::externs::{PythonScript, PythonScripts};
+pub use n3_program::externs::{ExternScript, ExternScripts};
 
-impl CloneSafe for PythonScript {
-    fn clone_safe(&self, _seed: &Seed, _variables: &mut Vec<ast::RefVariable>) -> Self {
+impl CloneSafe for ExternScript {
+    fn clone_safe(&self, _seed: &Seed, _variables: &mut Bindings) -> Self {
         self.clone()
     }
 }
 
-impl Build for PythonScript {
+impl Build for ExternScript {
     type Output = Self;
+    type Source = String;
 
     fn build(_root: &NodeRoot, name: &str, source: String) -> Result<Self::Output>
     where
         Self: Sized,
     {
-        Ok(PythonScript {
+        Ok(ExternScript {
             name: name.to_string(),
             source,
         })
@@ -1,10 +1,12 @@
 use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap};
 use std::rc::Rc;
 
 use crate::ast;
 use crate::context::CloneSafe;
 use crate::error::{GraphError, Result};
 use crate::seed::Seed;
+use crate::suggest::suggest;
 use crate::variable::*;
 
 pub use n3_program::graph::*;
@@ -55,19 +57,19 @@ impl Graph {
                         return GraphError::UnexpectedNodeVariable { name: k }.into();
                     }
                 }
-                Ok((
-                    k,
-                    ast::Variable {
-                        id: Some(id),
-                        id_old: Some(id),
-                        name: v.name,
-                        shortcut: v.shortcut,
-                        description: None,
-                        ty: Some(v.ty),
-                        value: v.value,
-                    }
-                    .into(),
-                ))
+                let value = cast_enum(&v.name, &v.ty, v.value)?;
+                let var = ast::Variable {
+                    id: Some(id),
+                    id_old: Some(id),
+                    name: v.name,
+                    shortcut: v.shortcut,
+                    description: v.description,
+                    ty: Some(v.ty),
+                    value,
+                    range: v.range,
+                };
+                assert_in_range(&var)?;
+                Ok((k, var.into()))
             })
             .collect::<Result<_>>()?;
 
@@ -115,11 +117,16 @@ impl Graph {
 
         for (name, v) in variables.into_iter() {
             if let Some(var) = self_variables.get(&name) {
-                var.borrow_mut().value = v;
+                let mut var = var.borrow_mut();
+                var.value = match &var.ty {
+                    Some(ty) => cast_enum(&var.name, ty, v)?,
+                    None => v,
+                };
+                assert_in_range(&var)?;
             } else {
                 return GraphError::NoSuchVariable {
+                    candidates: suggest(&name, self_variables.keys()),
                     name,
-                    candidates: self_variables.keys().cloned().collect(),
                 }
                 .into();
             }
@@ -131,18 +138,56 @@ impl Graph {
         self.variables.get(name).ok_or_else(|| {
             GraphError::NoSuchVariable {
                 name: name.to_string(),
-                candidates: self.variables.keys().cloned().collect(),
+                candidates: suggest(name, self.variables.keys()),
             }
             .into()
         })
     }
 
-    fn build(&mut self) -> Result<()> {
-        let shortcuts_map = self
-            .variables
+    /// Link the variables of the value to the graph, by their names or shortcuts.
+    pub fn link_value(&self, value: &ast::Value) -> Result<ast::Value> {
+        value.replace_to(&mut Vec::new(), &self.variables, &self.shortcuts_map())
+    }
+
+    /// Rebind the variables of the linked value to the graph, by their names.
+    ///
+    /// Unlike `link_value`, the values of the variables are not visited,
+    /// so that the value linked to the other instance can be reused.
+    pub fn rebind_value(&self, value: &ast::Value) -> ast::Value {
+        match value {
+            ast::Value::Variable(var) => match self.variables.get(&var.borrow().name) {
+                Some(new_var) => new_var.clone().into(),
+                None => var.clone().into(),
+            },
+            ast::Value::Expr(expr) => ast::Expr {
+                op: expr.op,
+                lhs: self.rebind_value(&expr.lhs),
+                rhs: expr.rhs.as_ref().map(|x| self.rebind_value(x)),
+            }
+            .into(),
+            ast::Value::List(list) => list
+                .iter()
+                .map(|x| self.rebind_value(x))
+                .collect::<Vec<_>>()
+                .into(),
+            ast::Value::Map(map) => map
+                .iter()
+                .map(|(k, v)| (k.clone(), v.as_ref().map(|x| self.rebind_value(x))))
+                .collect::<BTreeMap<_, _>>()
+                .into(),
+            _ => value.clone(),
+        }
+    }
+
+    fn shortcuts_map(&self) -> HashMap<String, String> {
+        self.variables
             .iter()
             .filter_map(|(k, v)| v.borrow().shortcut.as_ref().map(|s| (s.clone(), k.clone())))
-            .collect();
+            .collect()
+    }
+
+    fn build(&mut self) -> Result<()> {
+        let shortcuts_map = self.shortcuts_map();
 
         let variables = self
             .variables
@@ -197,7 +242,7 @@ impl Graph {
     pub fn unload_dims(&mut self) -> Values {
         self.variables
             .iter_mut()
-            .filter(|(_, v)| v.borrow().ty == Some(ast::LetType::Dim))
+            .filter(|(_, v)| v.borrow().is_dim())
             .map(|(k, v)| (k.clone(), v.borrow_mut().value.take()))
             .collect()
     }
@@ -213,6 +258,14 @@ impl Graph {
         }
     }
 
+    /// Check the ranges again, as the values may be resolved after they are applied.
+    pub fn assert_in_range(&self) -> Result<()> {
+        for var in self.variables.values() {
+            assert_in_range(&var.borrow())?;
+        }
+        Ok(())
+    }
+
     pub fn variables(&self) -> &Variables {
         &self.variables
     }
@@ -248,11 +301,11 @@ impl Estimable for Graph {
 }
 
 impl CloneSafe for Graph {
-    fn clone_safe(&self, seed: &Seed, variables: &mut Vec<ast::RefVariable>) -> Self {
+    fn clone_safe(&self, seed: &Seed, variables: &mut Bindings) -> Self {
         let id = seed.generate();
 
         // Step 1. get the copies
-        let mut self_variables: Variables = self
+        let self_variables: Variables = self
             .variables
             .iter()
             .map(|(k, v)| (k.clone(), v.detach(id)))
@@ -261,13 +314,12 @@ impl CloneSafe for Graph {
 
         // Step 2. register the copied variables
         for var in self_variables.values() {
-            variables.push(var.clone());
+            variables.bind(var);
         }
 
-        // Step 3. replace the olds into the news
-        for var in self_variables.values_mut() {
-            let new_var = var.borrow().value.clone_value(variables);
-            var.borrow_mut().value = new_var;
+        // Step 3. rebind the values of the olds into the news
+        for (old, new) in self.variables.values().zip(self_variables.values()) {
+            new.borrow_mut().value = old.borrow().value.clone_value(variables);
         }
 
         // Step 4. store
@@ -279,6 +331,51 @@ impl CloneSafe for Graph {
     }
 }
 
+/// Converts the bare names into the enum variants, testing whether they are valid.
+fn cast_enum(
+    name: &str,
+    ty: &ast::LetType,
+    value: Option<ast::Value>,
+) -> Result<Option<ast::Value>> {
+    let variants = match ty.unwrap_enum() {
+        Some(variants) => variants,
+        None => return Ok(value),
+    };
+
+    match &value {
+        Some(ast::Value::String(variant)) => {
+            if variants.contains(variant) {
+                Ok(value)
+            } else {
+                GraphError::UnparsableString {
+                    name: name.to_string(),
+                    value: variant.clone(),
+                    ty: Some(ty.clone()),
+                }
+                .into()
+            }
+        }
+        // not a variable, but a bare name of the variant
+        Some(ast::Value::Variable(var)) if is_bare_name(&var.borrow()) => {
+            let var_name = var.borrow().name.clone();
+            if variants.contains(&var_name) {
+                Ok(Some(ast::Value::String(var_name)))
+            } else {
+                GraphError::NoSuchVariable {
+                    candidates: suggest(&var_name, variants),
+                    name: var_name,
+                }
+                .into()
+            }
+        }
+        _ => Ok(value),
+    }
+}
+
+fn is_bare_name(var: &ast::Variable) -> bool {
+    var.ty.is_none() && var.value.is_none()
+}
+
 fn to_shortcuts(variables: &Variables) -> Variables {
     variables
         .values()
@@ -8,27 +8,35 @@ mod n3_std;
 mod cache;
 mod code;
 mod context;
-mod error;
+mod cost;
+pub mod error;
 mod execs;
 mod externs;
 mod graph;
+pub mod lint;
 mod nodes;
+pub mod optimize;
 mod seed;
+mod suggest;
 mod tensor;
 mod variable;
 
-pub use n3_parser::ast;
+pub use n3_parser::{ast, Document, FormatConfig, Parser, Position, TextEdit};
+pub use n3_program::compact::to_hex;
+pub use n3_program::{GraphFormat, RenderGraph, GRAPH_FORMATS};
 
 pub use self::code::{Code, CodeData, CodeType};
+pub use self::cost::{Cost, COSTS};
 pub use self::error::{Error, Result};
 pub use self::execs::{
-    dirs, Args, ExecRoot, ExecRootConfig, GlobalVars, Program, Vars, QUERY_SPLIT_1,
+    dirs, Args, CompactedProgram, ExecRoot, ExecRootConfig, GlobalVars, Program, ProgramFormat,
+    Vars, PROGRAM_FORMATS, QUERY_SPLIT_1,
 };
-pub use self::externs::{ExternCode, PythonScripts};
+pub use self::externs::{parse_backend, Backend, ExternCode, ExternScripts, BACKENDS};
 pub use self::graph::ToValues;
-pub use self::nodes::NodeCode;
-
-use n3_parser::Parser;
+pub use self::n3_std::trim_path;
+pub use self::nodes::{GraphInspection, NodeCode, NodeInspection, NodeRoot, NodeSummary};
+pub use self::suggest::{did_you_mean, suggest};
 
 #[cfg(test)]
 mod tests_recon {
@@ -58,4 +66,47 @@ mod tests_recon {
             recon(&source);
         }
     }
+
+    #[test]
+    fn test_all_externs_json() {
+        let parser = super::Parser::default();
+
+        let path = std::path::PathBuf::from("../n3-torch/ffi/python/n3");
+        for source in super::n3_std::get_sources(&path).values() {
+            let file = parser.parse_file(source).unwrap();
+            let json = super::ast::with_shared_variables(|| serde_json::to_string(&file)).unwrap();
+            let file_recon: super::ast::File =
+                super::ast::with_shared_variables(|| serde_json::from_str(&json)).unwrap();
+
+            assert_eq!(format!("{:?}", file), format!("{:?}", file_recon));
+        }
+    }
+
+    #[test]
+    fn test_all_externs_incremental() {
+        let parser = super::Parser::default();
+
+        let path = std::path::PathBuf::from("../n3-torch/ffi/python/n3");
+        for source in super::n3_std::get_sources(&path).values() {
+            let file = parser.parse_file(source).unwrap();
+            let document = super::Document::new(source.clone());
+
+            assert_eq!(
+                format!("{:?}", file),
+                format!("{:?}", document.file().unwrap())
+            );
+        }
+    }
+
+    #[test]
+    fn test_all_externs_format() {
+        let parser = super::Parser::default();
+        let config = super::FormatConfig::default();
+
+        let path = std::path::PathBuf::from("../n3-torch/ffi/python/n3");
+        for source in super::n3_std::get_sources(&path).values() {
+            let formatted = parser.format_file(source, &config).unwrap();
+            assert_eq!(formatted, parser.format_file(&formatted, &config).unwrap());
+        }
+    }
 }
@@ -0,0 +1,314 @@
+mod passes;
+
+use std::collections::BTreeMap;
+use std::ops::Range;
+
+use crate::ast;
+use crate::error::{LintError, Result};
+use crate::nodes::NodeRoot;
+use crate::suggest::suggest;
+use crate::tensor::TensorNode;
+
+pub use self::passes::LINTS;
+
+/// The level of the lint, which can be configured by the user.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Level {
+    Allow,
+    Warn,
+    Deny,
+}
+
+/// A lint, which is identified by the name. (e.g. `unused-let`)
+pub struct Lint {
+    pub name: &'static str,
+    pub level: Level,
+    pub description: &'static str,
+    check: fn(&LintContext, &mut Vec<Warning>),
+}
+
+/// The warning, which is not located yet.
+struct Warning {
+    /// The names of the node and its ancestors, from the root
+    path: Vec<String>,
+    target: Target,
+    message: String,
+}
+
+/// The declaration in the node, which is warned.
+enum Target {
+    Let { name: String, shortcut: bool },
+    With(String),
+    Node(String),
+    GraphNode(u64),
+}
+
+/// The node to be checked.
+///
+/// The dataflow is checked on the built node, where the variables and the inputs
+/// are resolved. The declarations which are consumed on build (e.g. `with` blocks)
+/// are checked on the parsed file.
+pub struct LintContext<'a> {
+    pub file: &'a ast::File,
+    node: &'a TensorNode,
+}
+
+/// The message of the lint, which is located on the source.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LintMessage {
+    pub lint: &'static str,
+    pub level: Level,
+    pub message: String,
+    pub span: Option<Span>,
+}
+
+/// A span of a line, by the 0-based line and character indices.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Span {
+    pub line: usize,
+    pub start: usize,
+    pub end: usize,
+}
+
+pub struct Linter {
+    levels: BTreeMap<&'static str, Level>,
+}
+
+impl Default for Linter {
+    fn default() -> Self {
+        Self {
+            levels: LINTS.iter().map(|x| (x.name, x.level)).collect(),
+        }
+    }
+}
+
+impl Linter {
+    pub fn set_level(&mut self, name: &str, level: Level) -> Result<()> {
+        match self.levels.get_mut(name) {
+            Some(value) => {
+                *value = level;
+                Ok(())
+            }
+            None => LintError::NoSuchLint {
+                name: name.to_string(),
+                candidates: suggest(name, self.levels.keys()),
+            }
+            .into(),
+        }
+    }
+
+    /// Build the node, and check the lints on its source.
+    /// The node should be added into the root before.
+    ///
+    /// The lints are checked only if the node is built successfully,
+    /// so that they catch the bugs which are legal.
+    pub fn check(&self, root: &NodeRoot, source: &str) -> Result<Vec<LintMessage>> {
+        let file = root.parser.parse_file(source)?;
+        let node = root.get_tensor_node(&file.node.name)?;
+
+        let ctx = LintContext {
+            file: &file,
+            node: &node,
+        };
+        let mut messages = vec![];
+        for lint in LINTS {
+            let level = self.levels[lint.name];
+            if level == Level::Allow {
+                continue;
+            }
+
+            let mut warnings = vec![];
+            (lint.check)(&ctx, &mut warnings);
+            messages.extend(warnings.into_iter().map(|x| LintMessage {
+                lint: lint.name,
+                level,
+                span: locate(source, &x),
+                message: x.message,
+            }));
+        }
+
+        messages.sort_by_key(|x| x.span.map(|x| (x.line, x.start)));
+        Ok(messages)
+    }
+}
+
+/// Find the span of the warning, with the indentation of the node blocks.
+fn locate(source: &str, warning: &Warning) -> Option<Span> {
+    let lines: Vec<_> = source.lines().collect();
+
+    // find the block of the node
+    let (mut start, mut end) = (0, lines.len());
+    for name in &warning.path {
+        let header = find_line(&lines, start..end, |code| {
+            parse_header(code, "node").as_ref() == Some(name)
+        })?;
+        let indent = indent_of(lines[header]);
+
+        start = header + 1;
+        end = (start..end)
+            .find(|&at| is_code(lines[at]) && indent_of(lines[at]) <= indent)
+            .unwrap_or(end);
+    }
+
+    // find the declaration in the body
+    let (line, subject) = match &warning.target {
+        Target::Let { name, shortcut } => {
+            let line = find_line(&lines, start..end, |code| {
+                parse_let(code).map(|(_, x)| x == name).unwrap_or_default()
+            })?;
+            let (s, name) = parse_let(lines[line].trim_start())?;
+            (line, if *shortcut { s? } else { name })
+        }
+        Target::With(name) => {
+            let line = find_line(&lines, start..end, |code| {
+                parse_header(code, "with").as_ref() == Some(name)
+            })?;
+            (line, name.as_str())
+        }
+        Target::Node(name) => {
+            let line = find_line(&lines, start..end, |code| {
+                parse_header(code, "node").as_ref() == Some(name)
+            })?;
+            (line, name.as_str())
+        }
+        Target::GraphNode(id) => {
+            let id = format!("{}.", id);
+            let line = find_line(&lines, start..end, |code| code.starts_with(&id))?;
+            (line, lines[line].trim())
+        }
+    };
+
+    let text = lines[line];
+    let indent = indent_of(text);
+    let at = text[indent..].find(subject).map(|at| at + indent)?;
+    let start = text[..at].chars().count();
+    Some(Span {
+        line,
+        start,
+        end: start + subject.chars().count(),
+    })
+}
+
+/// Find the line in the body of the block, by its code.
+fn find_line(lines: &[&str], rows: Range<usize>, f: impl Fn(&str) -> bool) -> Option<usize> {
+    let body = rows
+        .clone()
+        .find(|&at| is_code(lines[at]))
+        .map(|at| indent_of(lines[at]))?;
+    rows.into_iter()
+        .find(|&at| indent_of(lines[at]) == body && f(lines[at].trim()))
+}
+
+/// Parse `[extern|data|optim|exec] <keyword> <name>:`
+fn parse_header(code: &str, keyword: &str) -> Option<String> {
+    let mut words = code.strip_suffix(':')?.split_whitespace();
+    words.find(|&x| x == keyword)?;
+    Some(words.collect::<Vec<_>>().join(" "))
+}
+
+/// Parse `let [<shortcut>:] <name> = ...`
+fn parse_let(code: &str) -> Option<(Option<&str>, &str)> {
+    let lhs = code.strip_prefix("let ")?.split('=').next()?;
+    match lhs.find(':') {
+        Some(at) => Some((Some(lhs[..at].trim()), lhs[at + 1..].trim())),
+        None => Some((None, lhs.trim())),
+    }
+}
+
+fn indent_of(line: &str) -> usize {
+    line.len() - line.trim_start().len()
+}
+
+fn is_code(line: &str) -> bool {
+    let line = line.trim_start();
+    !line.is_empty() && !line.starts_with('#')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = "node MyNode:
+    let K: kernel size = int 5
+    let W: width = dim
+    let P: padding = int 1
+
+    with Conv2D:
+        set kernel size = K
+
+    with MaxPool2D:
+        set kernel size = 2
+
+    node MyConv:
+        let K: kernel size = int 3
+
+        1. Conv2D(K=K, S=2)
+        2. Relu
+
+    node MyRelu:
+        1. Relu
+
+    0. Input    = 3, W, W
+    1. MyConv   = 16, W/2, W/2
+    2. MyConv{x=$0} = 16, W/2, W/2
+    3. MyConv   = 8, W/2, W/2
+";
+
+    fn new_root() -> NodeRoot {
+        let root = NodeRoot::new(Some("../n3-torch/ffi/python/n3"));
+        root.add_source("MyNode".to_string(), SOURCE.to_string());
+        root
+    }
+
+    fn check(linter: &Linter) -> Vec<(&'static str, usize, usize)> {
+        linter
+            .check(&new_root(), SOURCE)
+            .unwrap()
+            .into_iter()
+            .map(|x| {
+                let span = x.span.unwrap();
+                (x.lint, span.line, span.start)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_lints() {
+        let linter = Linter::default();
+        assert_eq!(
+            check(&linter),
+            [
+                ("unused-let", 3, 11),
+                ("unused-with", 8, 9),
+                ("shadowed-shortcut", 12, 12),
+                ("unused-child", 17, 9),
+                ("unused-output", 21, 4),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_levels() {
+        let mut linter = Linter::default();
+        linter.set_level("unused-let", Level::Deny).unwrap();
+        linter.set_level("unused-output", Level::Allow).unwrap();
+        assert!(linter.set_level("unused-lets", Level::Allow).is_err());
+
+        let messages = linter.check(&new_root(), SOURCE).unwrap();
+        assert_eq!(messages.len(), 4);
+        assert_eq!(messages[0].level, Level::Deny);
+        assert_eq!(messages[1].level, Level::Warn);
+    }
+
+    #[test]
+    fn test_std() {
+        let root = new_root();
+        let linter = Linter::default();
+
+        let path = std::path::PathBuf::from("../n3-torch/ffi/python/n3");
+        for (name, source) in crate::n3_std::get_sources(&path) {
+            let messages = linter.check(&root, &source).unwrap();
+            assert_eq!(messages, [], "{}", name);
+        }
+    }
+}
@@ -0,0 +1,302 @@
+use std::cell::RefCell;
+use std::collections::{BTreeMap, BTreeSet};
+
+use super::{Level, Lint, LintContext, Target, Warning};
+use crate::ast::{self, visit, Visit};
+use crate::nodes::NodeIR;
+use crate::tensor::TensorNode;
+
+pub static LINTS: &[Lint] = &[
+    Lint {
+        name: "unused-let",
+        level: Level::Warn,
+        description: "A variable which is never used in the node and its children.",
+        check: unused_let,
+    },
+    Lint {
+        name: "unused-with",
+        level: Level::Warn,
+        description: "A `with` block for the node which is never called.",
+        check: unused_with,
+    },
+    Lint {
+        name: "unused-child",
+        level: Level::Warn,
+        description: "A child node which is never called.",
+        check: unused_child,
+    },
+    Lint {
+        name: "unused-output",
+        level: Level::Warn,
+        description: "An output of the graph node which is never consumed.",
+        check: unused_output,
+    },
+    Lint {
+        name: "shadowed-shortcut",
+        level: Level::Warn,
+        description: "A shortcut which shadows the one of the outer node.",
+        check: shadowed_shortcut,
+    },
+];
+
+fn unused_let(ctx: &LintContext, warnings: &mut Vec<Warning>) {
+    let used = referred_variables(ctx.node);
+    walk_ir(ctx, &mut |node, decl, path| {
+        // note: the withs are consumed on build, and are checked by `unused-with`
+        let mut refs = References::default();
+        for with in decl.withs.values() {
+            refs.visit_with(with);
+        }
+
+        let graph = node.data.graph.borrow();
+        for var in decl.graph.values() {
+            let is_used = var
+                .shortcut
+                .iter()
+                .chain(Some(&var.name))
+                .any(|x| refs.variables.contains(x))
+                || match graph.variables().get(&var.name) {
+                    Some(var) => used.contains(&(&**var as *const _)),
+                    None => true,
+                };
+            if !is_used {
+                warnings.push(Warning {
+                    path: path.to_vec(),
+                    target: Target::Let {
+                        name: var.name.clone(),
+                        shortcut: false,
+                    },
+                    message: format!("the variable `{}` is never used", &var.name),
+                });
+            }
+        }
+    });
+}
+
+fn unused_with(ctx: &LintContext, warnings: &mut Vec<Warning>) {
+    walk(&ctx.file.node, &mut vec![], &mut |node, path| {
+        let mut refs = References::default();
+        refs.visit_node(node);
+
+        for name in node.withs.keys() {
+            if !refs.calls.contains(name) {
+                warnings.push(Warning {
+                    path: path.to_vec(),
+                    target: Target::With(name.clone()),
+                    message: format!("the node `{}` is never called in this node", name),
+                });
+            }
+        }
+    });
+}
+
+fn unused_child(ctx: &LintContext, warnings: &mut Vec<Warning>) {
+    walk(&ctx.file.node, &mut vec![], &mut |node, path| {
+        for name in node.children.keys() {
+            // note: the child itself is not a caller
+            let mut refs = References::default();
+            for graph_node in node.tensor_graph.values() {
+                refs.visit_graph_node(graph_node);
+            }
+            for child in node.children.values().filter(|x| &x.name != name) {
+                refs.visit_node(child);
+            }
+
+            if !refs.calls.contains(name) {
+                warnings.push(Warning {
+                    path: path.to_vec(),
+                    target: Target::Node(name.clone()),
+                    message: format!("the child node `{}` is never called", name),
+                });
+            }
+        }
+    });
+}
+
+fn unused_output(ctx: &LintContext, warnings: &mut Vec<Warning>) {
+    walk_ir(ctx, &mut |node, _, path| {
+        let mut consumed = BTreeSet::new();
+        for graph_node in node.tensor_graph.iter() {
+            // note: the built outs refer to the next id of the graph node
+            // note: the other calls of the same line take the previous call
+            let id = graph_node.get_id();
+            consumed.extend(
+                graph_node
+                    .get_inputs()
+                    .values()
+                    .filter_map(|x| x.id)
+                    .map(|x| x - 1)
+                    .filter(|&x| x != id),
+            );
+        }
+
+        // note: the last output is the output of the node
+        consumed.extend(node.tensor_graph.last().map(|x| x.get_id()));
+
+        let ids: BTreeSet<_> = node.tensor_graph.iter().map(|x| x.get_id()).collect();
+        for id in ids {
+            if !consumed.contains(&id) {
+                warnings.push(Warning {
+                    path: path.to_vec(),
+                    target: Target::GraphNode(id),
+                    message: format!("the output of the graph node `{}` is never consumed", id),
+                });
+            }
+        }
+    });
+}
+
+fn shadowed_shortcut(ctx: &LintContext, warnings: &mut Vec<Warning>) {
+    fn walk_shortcuts(
+        node: &ast::Node,
+        path: &mut Vec<String>,
+        outer: &BTreeMap<String, (String, String)>,
+        warnings: &mut Vec<Warning>,
+    ) {
+        path.push(node.name.clone());
+
+        let mut shortcuts = outer.clone();
+        for var in node.graph.values() {
+            if let Some(shortcut) = &var.shortcut {
+                if let Some((outer_name, outer_node)) = outer.get(shortcut) {
+                    warnings.push(Warning {
+                        path: path.clone(),
+                        target: Target::Let {
+                            name: var.name.clone(),
+                            shortcut: true,
+                        },
+                        message: format!(
+                            "the shortcut `{}` shadows the variable `{}` of the node `{}`",
+                            shortcut, outer_name, outer_node,
+                        ),
+                    });
+                }
+                shortcuts.insert(shortcut.clone(), (var.name.clone(), node.name.clone()));
+            }
+        }
+
+        for child in node.children.values() {
+            walk_shortcuts(child, path, &shortcuts, warnings);
+        }
+        path.pop();
+    }
+
+    walk_shortcuts(&ctx.file.node, &mut vec![], &Default::default(), warnings);
+}
+
+/// Call the function with each node and its path.
+fn walk<F>(node: &ast::Node, path: &mut Vec<String>, f: &mut F)
+where
+    F: FnMut(&ast::Node, &[String]),
+{
+    path.push(node.name.clone());
+    f(node, path);
+    for child in node.children.values() {
+        walk(child, path, f);
+    }
+    path.pop();
+}
+
+/// Call the function with each built node which is declared in the file,
+/// with its declaration and path. The instances of the same node are visited once.
+///
+/// The extern and exec nodes are not visited, as their variables are used outside.
+fn walk_ir<F>(ctx: &LintContext, f: &mut F)
+where
+    F: FnMut(&NodeIR, &ast::Node, &[String]),
+{
+    fn walk_node<'a, F>(
+        node: &NodeIR,
+        decls: &[&'a ast::Node],
+        visited: &mut BTreeSet<Vec<String>>,
+        f: &mut F,
+    ) where
+        F: FnMut(&NodeIR, &ast::Node, &[String]),
+    {
+        let path: Vec<_> = decls.iter().map(|x| x.name.clone()).collect();
+        if !visited.insert(path.clone()) {
+            return;
+        }
+        f(node, decls[decls.len() - 1], &path);
+
+        for child in node.tensor_graph.iter() {
+            if let TensorNode::Node(child) = child {
+                // note: the children of the ancestors can be called
+                let name = &child.data.name;
+                if let Some(at) = decls.iter().rposition(|x| x.children.contains_key(name)) {
+                    let decl: &'a ast::Node = &decls[at].children[name];
+                    let mut decls = decls[..=at].to_vec();
+                    decls.push(decl);
+                    walk_node(child, &decls, visited, f);
+                }
+            }
+        }
+    }
+
+    if let TensorNode::Node(node) = ctx.node {
+        if let ast::LetNodeType::Default = node.ty {
+            walk_node(node, &[&ctx.file.node], &mut BTreeSet::new(), f);
+        }
+    }
+}
+
+/// Collect the variables which are referred to by the values of the built node.
+fn referred_variables(node: &TensorNode) -> BTreeSet<*const RefCell<ast::Variable>> {
+    #[derive(Default)]
+    struct Referred(BTreeSet<*const RefCell<ast::Variable>>);
+
+    impl Visit for Referred {
+        fn visit_variable(&mut self, var: &ast::RefVariable) {
+            self.0.insert(&**var);
+        }
+    }
+
+    fn visit_node(refs: &mut Referred, node: &TensorNode) {
+        // note: the exec nodes cannot be called by the other nodes
+        if let TensorNode::Exec(_) = node {
+            return;
+        }
+
+        for var in node.get_graph().borrow().variables().values() {
+            if let Some(value) = &var.borrow().value {
+                refs.visit_value(value);
+            }
+        }
+        for shapes in node
+            .get_input_shapes()
+            .into_iter()
+            .chain(node.get_output_shapes())
+        {
+            refs.visit_shapes(shapes);
+        }
+        if let TensorNode::Node(node) = node {
+            if let Some(repeat) = &node.repeat {
+                refs.visit_value(repeat);
+            }
+            for child in node.tensor_graph.iter() {
+                visit_node(refs, child);
+            }
+        }
+    }
+
+    let mut refs = Referred::default();
+    visit_node(&mut refs, node);
+    refs.0
+}
+
+#[derive(Default)]
+struct References {
+    variables: BTreeSet<String>,
+    calls: BTreeSet<String>,
+}
+
+impl Visit for References {
+    fn visit_graph_call(&mut self, call: &ast::GraphCall) {
+        self.calls.insert(call.name.clone());
+        visit::visit_graph_call(self, call)
+    }
+
+    fn visit_variable(&mut self, var: &ast::RefVariable) {
+        self.variables.insert(var.borrow().name.clone());
+    }
+}
@@ -1,22 +1,34 @@
 use std::collections::HashMap;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use glob::glob;
 use inflector::Inflector;
 
+#[cfg(test)]
 pub fn get_sources(root: &Path) -> HashMap<String, String> {
     get_files(root, "n3")
 }
 
-pub fn get_externs(root: &Path) -> HashMap<String, String> {
-    get_files(root, "py")
+pub fn get_externs(root: &Path, extension: &'static str) -> HashMap<String, String> {
+    get_files(root, extension)
+}
+
+pub fn get_source_paths(root: &Path) -> HashMap<String, PathBuf> {
+    get_paths(root, "n3")
 }
 
 fn get_files(root: &Path, extension: &'static str) -> HashMap<String, String> {
+    get_paths(root, extension)
+        .into_iter()
+        .map(|(k, p)| (k, load_source(&p)))
+        .collect()
+}
+
+fn get_paths(root: &Path, extension: &'static str) -> HashMap<String, PathBuf> {
     let result: HashMap<_, _> = glob(&format!("{}/std/**/*.{}", root.display(), extension))
         .unwrap()
         .filter_map(|e| e.ok())
-        .map(|p| (trim_path(&p), load_source(&p)))
+        .map(|p| (trim_path(&p), p))
         .collect();
 
     if result.is_empty() {
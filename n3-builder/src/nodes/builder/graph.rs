@@ -1,3 +1,5 @@
+use std::rc::Rc;
+
 use super::node::{ExternTensorGraphCondition, NodeEntry};
 use crate::ast;
 use crate::error::{GraphCallError, Result};
@@ -11,7 +13,7 @@ pub mod builtins {
     pub const INPUTS: &[&str] = &[INPUT_NAME];
     pub(super) const INPUT_NAME: &str = "AssertShape";
 
-    pub(super) const NODE__Transform: &str = "Transform";
+    pub const NODE__Transform: &str = "Transform";
     pub(super) const NODE__ToLinear: &str = "ToLinear";
     pub(super) const NODE__Concat: &str = "Concat";
 }
@@ -46,10 +48,18 @@ impl<'a, 'b, 'c> GraphNodeBuilder<InputNode> for GraphNodeEntry<'a, 'b, 'c> {
         }
         .test()?;
 
+        // store the shapes to be asserted on runtime, only if they have the dynamic dims
+        let graph = match node.shapes.as_ref().filter(|x| has_dynamic_dims(x)) {
+            Some(shapes) => {
+                make_graph_with_one_var(self.root, "output shapes", Some(shapes_to_value(shapes)))
+            }
+            None => make_empty_graph(self.root),
+        };
+
         let ir = ExternIR::new_first(
             ast::ExternNodeType::Default,
             INPUT_NAME.to_string(),
-            make_empty_graph(&self.root).into(),
+            graph.into(),
             None,
             node.shapes,
         );
@@ -191,13 +201,14 @@ fn build_extern(
             id,
             name,
             graph: graph.into(),
-            input: io_input,
-            output: io_output,
+            input: io_input.into(),
+            output: io_output.into(),
         },
         shapes: ExternIRShapes {
             input: Some(input),
             output: Some(output),
         },
+        cost: Default::default(),
     })
 }
 
@@ -260,18 +271,7 @@ fn build_transform(
     }
 
     // Step 4. store variables
-    let graph = make_graph_with_one_var(
-        &root,
-        "output shapes",
-        Some(ast::Value::Map(
-            outputs
-                .0
-                .borrow()
-                .iter()
-                .map(|(k, v)| (k.clone(), v.as_ref().map(|x| x.0.clone().into())))
-                .collect(),
-        )),
-    );
+    let graph = make_graph_with_one_var(root, "output shapes", Some(shapes_to_value(&outputs)));
 
     // Step 5. store
     let io_inputs: Vec<_> = inputs.0.borrow().keys().cloned().collect();
@@ -325,7 +325,7 @@ impl<'a, 'b, 'c> GraphNodeBuilder<Concat> for GraphNodeEntry<'a, 'b, 'c> {
         let mut args = call.args.unwrap();
 
         // Step 1. get the axis
-        let axis = args.remove("axis");
+        let axis = args.shift_remove("axis");
         let axis = root.graph.borrow().replace_to(axis)?.unwrap();
         let axis = axis.build();
 
@@ -425,17 +425,26 @@ impl<'a, 'b, 'c> GraphNodeBuilder<Concat> for GraphNodeEntry<'a, 'b, 'c> {
             .collect();
         let outputs = ast::Shapes::new(outputs);
 
-        let io_inputs = io_inputs.into_iter().map(|x| x.name).collect();
         let io_outputs = vec!["x".to_string()];
-        let ir = build_extern(
+        let mut ir = build_extern(
             ast::ExternNodeType::Default,
             id,
             root,
             call.name,
             graph,
-            (inputs, io_inputs),
+            (inputs, vec![]),
             (outputs, io_outputs),
         )?;
+
+        // note: the inputs are keyed by their indices, as they may share the same names
+        //       (e.g. `Concat[x$0, x$1]`), which would be merged into one if keyed by names
+        ir.data.input = Rc::new(
+            io_inputs
+                .into_iter()
+                .enumerate()
+                .map(|(i, x)| (i.to_string(), x))
+                .collect(),
+        );
         root.tensor_graph.push(ir.into());
         Ok(())
     }
@@ -492,6 +501,40 @@ fn make_graph_with_one_var(root: &NodeEntry, name: &str, value: Option<ast::Valu
     Graph::with_one_var(&root.ctx.root.seed, name, value)
 }
 
+fn shapes_to_value(shapes: &ast::Shapes) -> ast::Value {
+    ast::Value::Map(
+        shapes
+            .0
+            .borrow()
+            .iter()
+            .map(|(k, v)| (k.clone(), v.as_ref().map(|x| x.0.clone().into())))
+            .collect(),
+    )
+}
+
+fn has_dynamic_dims(shapes: &ast::Shapes) -> bool {
+    fn is_dynamic(value: &ast::Value) -> bool {
+        match value {
+            ast::Value::Variable(var) => {
+                let var = var.borrow();
+                var.ty == Some(ast::LetType::DynamicDim)
+                    || var.value.as_ref().is_some_and(is_dynamic)
+            }
+            ast::Value::Expr(expr) => {
+                is_dynamic(&expr.lhs) || expr.rhs.as_ref().is_some_and(is_dynamic)
+            }
+            _ => false,
+        }
+    }
+
+    shapes
+        .0
+        .borrow()
+        .values()
+        .flatten()
+        .any(|shape| shape.0.iter().any(is_dynamic))
+}
+
 fn unwrap_dict(inputs: ast::GraphInputs) -> Result<ast::Outs> {
     let given = inputs.ty();
     inputs.unwrap_dict().ok_or_else(|| {
@@ -4,10 +4,12 @@ use super::super::ir::NodeIR;
 use super::graph::GraphNodeEntry;
 use crate::ast;
 use crate::context::{Context, NodeName};
-use crate::error::{ExecBuildError, GraphCallError, GraphNodeError, Result};
+use crate::cost::{build_cost, CostFormulas};
+use crate::error::{ExecBuildError, GraphCallError, GraphNodeError, Result, TensorNodeError};
 use crate::execs::ExecIR;
 use crate::externs::ExternIR;
 use crate::graph::{Graph, RefGraph};
+use crate::suggest::suggest;
 use crate::tensor::{IRData, TensorGraph, TensorNode};
 
 pub trait ASTBuild<'a> {
@@ -175,6 +177,12 @@ impl<'a> ASTBuild<'a> for ast::File {
         if self.node.ty.is_extern() {
             return Ok(ExternFile(self).build(ctx, ())?.into());
         }
+        if !self.node.cost.is_empty() {
+            return TensorNodeError::UnexpectedCost {
+                name: self.node.name,
+            }
+            .into();
+        }
         if self.node.ty.is_exec() {
             return Ok(ExecFile(self).build(ctx, ())?.into());
         }
@@ -225,14 +233,16 @@ struct ExternNodeEntry<'a, 'b> {
 
     input: Option<ast::Shapes>,
     output: Option<ast::Shapes>,
+    cost: CostFormulas,
 }
 impl<'a, 'b> ExternNodeEntry<'a, 'b> {
-    fn new(inner: NodeEntry<'a, 'b>, ty: ast::ExternNodeType) -> Self {
+    fn new(inner: NodeEntry<'a, 'b>, ty: ast::ExternNodeType, cost: CostFormulas) -> Self {
         Self {
             inner,
             ty,
             input: None,
             output: None,
+            cost,
         }
     }
 
@@ -277,13 +287,14 @@ impl<'a, 'b> ExternNodeEntry<'a, 'b> {
     fn build(mut self) -> NodeIR {
         let name = self.inner.name.pop().unwrap();
 
-        let extern_node = ExternIR::new_first(
+        let mut extern_node = ExternIR::new_first(
             self.ty,
             name.clone(),
             self.inner.graph,
             self.input,
             self.output,
         );
+        extern_node.cost = self.cost.into();
         let graph = extern_node.data.graph.clone();
 
         let tensor_graph = TensorGraph::new_one(extern_node.into());
@@ -308,11 +319,12 @@ impl<'a> ASTBuild<'a> for ExternFile {
 
         let ty = node.ty.unwrap_extern();
 
-        // Step 1. make a graph
-        let graph = Graph::try_with_variables(ctx.root.seed.generate(), node.graph, false)?.into();
+        // Step 1. make a graph, and link the cost to it
+        let graph = Graph::try_with_variables(ctx.root.seed.generate(), node.graph, false)?;
+        let cost = build_cost(&mut node.cost, &graph)?;
 
-        let entry = NodeEntry::new(vec![node.name], graph, ctx);
-        let mut entry = ExternNodeEntry::new(entry, ty);
+        let entry = NodeEntry::new(vec![node.name], graph.into(), ctx);
+        let mut entry = ExternNodeEntry::new(entry, ty, cost);
 
         // Step 2. hint variables with tensor graph
         entry.hint_variables(&mut node.tensor_graph)?;
@@ -426,9 +438,21 @@ impl<'a> ExternTensorGraphCondition<'a> {
             };
 
             if given != expected {
+                // note: suggest the expected args for the unexpected ones
+                let missing: Vec<_> = expected
+                    .iter()
+                    .filter(|x| !given.iter().any(|y| y == *x))
+                    .collect();
+                let candidates = given
+                    .iter()
+                    .filter(|x| !expected.contains(&x.as_str()))
+                    .flat_map(|x| suggest(x, &missing))
+                    .collect();
+
                 return GraphCallError::MismatchedArgs {
                     expected,
                     given: given.into_iter().cloned().collect(),
+                    candidates,
                 }
                 .into();
             }
@@ -448,6 +472,7 @@ impl<'a> ExternTensorGraphCondition<'a> {
 
 struct ExecNodeEntry {
     name: String,
+    description: Option<String>,
     graph: RefGraph,
     links: Vec<Vec<String>>,
 }
@@ -455,11 +480,13 @@ struct ExecNodeEntry {
 impl ExecNodeEntry {
     fn try_new(
         name: String,
+        description: Option<String>,
         graph: RefGraph,
         tensor_graph: BTreeMap<u64, ast::GraphNode>,
     ) -> Result<Self> {
         Ok(Self {
             name,
+            description,
             graph,
             links: ExecNodeEntry::get_links(tensor_graph)?,
         })
@@ -508,6 +535,7 @@ impl ExecNodeEntry {
     fn build(self) -> ExecIR {
         ExecIR {
             data: IRData::with_no_shapes(self.name, self.graph),
+            description: self.description,
             links: self.links,
         }
     }
@@ -534,7 +562,7 @@ impl<'a> ASTBuild<'a> for ExecFile {
         // Step 1. make a graph
         let graph = Graph::try_with_variables(ctx.root.seed.generate(), node.graph, true)?.into();
 
-        let entry = ExecNodeEntry::try_new(node.name, graph, node.tensor_graph)?;
+        let entry = ExecNodeEntry::try_new(node.name, node.description, graph, node.tensor_graph)?;
 
         // Step 2. store
         Ok(entry.build())
@@ -1,14 +1,19 @@
 use super::root::NodeRoot;
 use crate::code::AddScripts;
 use crate::error::Result;
-use crate::externs::PythonScripts;
+use crate::externs::{Backend, ExternScripts};
 
 pub use n3_program::nodes::NodeCode;
 
 impl AddScripts for NodeCode {
-    fn add_scripts(&self, root: &NodeRoot, scripts: &mut PythonScripts) -> Result<()> {
+    fn add_scripts(
+        &self,
+        root: &NodeRoot,
+        backend: Backend,
+        scripts: &mut ExternScripts,
+    ) -> Result<()> {
         for node in &self.tensor_graph {
-            node.add_scripts(root, scripts)?;
+            node.add_scripts(root, backend, scripts)?;
         }
         Ok(())
     }
@@ -0,0 +1,60 @@
+use crate::ast;
+use crate::tensor::TensorNode;
+
+/// The built information of a node, which is used by the tooling.
+#[derive(Debug)]
+pub struct NodeInspection {
+    pub name: String,
+    pub variables: Vec<ast::RefVariable>,
+    pub graph: Vec<GraphInspection>,
+}
+
+/// The inferred shapes of a node, which is called by a line of the tensor graph.
+#[derive(Debug)]
+pub struct GraphInspection {
+    pub id: u64,
+    pub name: String,
+    pub input: Option<ast::Shapes>,
+    pub output: Option<ast::Shapes>,
+}
+
+impl<'a> From<&'a TensorNode> for NodeInspection {
+    fn from(node: &'a TensorNode) -> Self {
+        let graph = match node {
+            TensorNode::Node(node) => node.tensor_graph.iter().map(Into::into).collect(),
+            TensorNode::Extern(_) => vec![node.into()],
+            TensorNode::Exec(_) => vec![],
+        };
+
+        Self {
+            name: node.name().to_string(),
+            variables: node
+                .get_graph()
+                .borrow()
+                .variables()
+                .values()
+                .cloned()
+                .collect(),
+            graph,
+        }
+    }
+}
+
+impl<'a> From<&'a TensorNode> for GraphInspection {
+    fn from(node: &'a TensorNode) -> Self {
+        let (input, output) = match node {
+            TensorNode::Exec(_) => (None, None),
+            _ => (
+                node.get_input_shapes().cloned(),
+                node.get_output_shapes().cloned(),
+            ),
+        };
+
+        Self {
+            id: node.get_id(),
+            name: node.name().to_string(),
+            input,
+            output,
+        }
+    }
+}
@@ -6,8 +6,8 @@ use crate::context::{Build, CloneSafe};
 use crate::error::{GraphCallError, Result};
 use crate::graph::Graph;
 use crate::seed::Seed;
-use crate::tensor::{IRData, TensorGraph, TensorNode};
-use crate::variable::{BuildValue, CloneValue, Link};
+use crate::tensor::{IRData, NodeSource, TensorGraph, TensorNode};
+use crate::variable::{Bindings, BuildValue, CloneValue, Link};
 
 #[derive(Debug, PartialEq)]
 pub struct NodeIR {
@@ -26,8 +26,9 @@ impl NodeIR {
         self.tensor_graph.get_output_shapes()
     }
 
-    pub fn build(mut self, root: &NodeRoot) -> Result<Code> {
-        if let Some(repeat) = &self.repeat {
+    /// Repeat the tensor graph by the `repeat` value, so that it is called only once.
+    pub fn expand(&mut self, root: &NodeRoot) -> Result<()> {
+        if let Some(repeat) = self.repeat.take() {
             let repeat = repeat.build();
             let repeat = repeat
                 .unwrap_uint()
@@ -65,7 +66,7 @@ impl NodeIR {
                     for _ in 0..(repeat - 1) {
                         for node in self.tensor_graph.iter() {
                             let mut node = {
-                                let mut variables = vec![];
+                                let mut variables = Bindings::default();
                                 node.clone_safe(&root.seed, &mut variables)
                             };
 
@@ -97,6 +98,11 @@ impl NodeIR {
                 }
             }
         }
+        Ok(())
+    }
+
+    pub fn build(mut self, root: &NodeRoot) -> Result<Code> {
+        self.expand(root)?;
 
         // extern node
         if let ast::LetNodeType::Extern(_) = self.ty {
@@ -119,6 +125,7 @@ impl NodeIR {
         }
 
         let tensor_graph = self.tensor_graph.build(root)?;
+        self.data.graph.borrow().assert_in_range()?;
 
         Ok(NodeCode {
             data: CodeData::from_ir(self.data),
@@ -130,14 +137,15 @@ impl NodeIR {
 
 impl Build for NodeIR {
     type Output = TensorNode;
+    type Source = NodeSource;
 
-    fn build(root: &NodeRoot, name: &str, source: String) -> Result<Self::Output> {
+    fn build(root: &NodeRoot, name: &str, source: NodeSource) -> Result<Self::Output> {
         TensorNode::build(root, name, source)
     }
 }
 
 impl CloneSafe for NodeIR {
-    fn clone_safe(&self, seed: &Seed, variables: &mut Vec<ast::RefVariable>) -> Self {
+    fn clone_safe(&self, seed: &Seed, variables: &mut Bindings) -> Self {
         match self.ty {
             // extern node wrapper
             // note: ordered (extern_node -> graph(copy) -> data -> repeat)
@@ -1,12 +1,16 @@
 mod builder;
 mod code;
+mod inspect;
 mod ir;
 mod root;
+mod summary;
 
 pub use self::builder::{builtins, ASTBuild};
 pub use self::code::NodeCode;
+pub use self::inspect::{GraphInspection, NodeInspection};
 pub use self::ir::NodeIR;
 pub use self::root::NodeRoot;
+pub use self::summary::NodeSummary;
 
 #[cfg(test)]
 mod tests {
@@ -19,10 +23,14 @@ mod tests {
 
     use super::super::*;
     use super::*;
-    use crate::error::TensorNodeError;
+    use crate::error::{
+        BuildError, ExecBuildError, GraphCallError, GraphError, LinkError, TensorNodeError,
+    };
     use crate::externs::ExternIR;
     use crate::graph::Graph;
     use crate::tensor::IRData;
+    use crate::variable::{BuildValue, Link};
+    use crate::{GraphFormat, RenderGraph};
 
     fn new_root() -> NodeRoot {
         NodeRoot::new(Some("../n3-torch/ffi/python/n3"))
@@ -44,8 +52,10 @@ mod tests {
                         ast::NodeLet {
                             name: k.to_string(),
                             shortcut: None,
+                            description: None,
                             ty,
                             value: Some(v),
+                            range: None,
                         },
                     )
                 })
@@ -56,17 +66,18 @@ mod tests {
                 id: ix,
                 name: "Linear".to_string(),
                 graph: graph.into(),
-                input: btreemap! {
+                input: Rc::new(btreemap! {
                     "x".to_string() => ast::Out::new(ix, "x".to_string()),
-                },
-                output: btreemap! {
+                }),
+                output: Rc::new(btreemap! {
                     "x".to_string() => ast::Out::new(ox, "x".to_string()),
-                },
+                }),
             };
             ExternIR {
                 ty: ast::ExternNodeType::Default,
                 shapes: (&data).into(),
                 data,
+                cost: Default::default(),
             }
         }
         let graph_1 = make_graph((1, 32), (2, 64));
@@ -88,16 +99,207 @@ mod tests {
     fn test_unexpected_extern_node() {
         let root = new_root();
         assert_eq!(
-            root.get_extern("FOO").err(),
+            root.get_extern(Backend::Torch, "FOO").err(),
             Some(
                 TensorNodeError::NoSuchNode {
                     name: "FOO".to_string(),
+                    candidates: Default::default(),
+                }
+                .into()
+            )
+        );
+
+        // the node exists, but is not implemented for the backend
+        assert_eq!(
+            root.get_extern(Backend::NdArray, "Relu").err(),
+            Some(
+                ExecBuildError::NoSuchImplementation {
+                    name: "Relu".to_string(),
+                    backend: Backend::NdArray,
+                }
+                .into()
+            )
+        );
+
+        root.add_extern(Backend::NdArray, "Relu".to_string(), "Relu".to_string());
+        assert_eq!(
+            root.get_extern(Backend::NdArray, "Relu").unwrap().source,
+            "Relu"
+        );
+    }
+
+    #[test]
+    fn test_enum_variables() {
+        let root = new_root();
+        let ir = root.get("CrossEntropy").unwrap();
+        let graph = ir.data.graph.borrow();
+
+        // default value
+        let reduction = graph.get("reduction").unwrap().clone();
+        assert_eq!(reduction.borrow().value, Some("mean".to_string().into()));
+
+        // the bare names should be converted into the variants
+        let apply = |value: &str| {
+            let value = ast::Variable::with_name(value.to_string()).into();
+            let values = vec![("reduction".to_string(), Some(value))];
+            graph.apply(values.into_iter().collect(), false)
+        };
+        apply("sum").unwrap();
+        assert_eq!(reduction.borrow().value, Some("sum".to_string().into()));
+
+        // the other names are not the variants, but the unknown variables
+        assert_eq!(
+            apply("man").err(),
+            Some(
+                GraphError::NoSuchVariable {
+                    name: "man".to_string(),
+                    candidates: vec!["mean".to_string()].into_iter().collect(),
+                }
+                .into()
+            )
+        );
+
+        // the strings should be one of the variants
+        let values = vec![("reduction".to_string(), Some("max".to_string().into()))];
+        assert_eq!(
+            graph.apply(values.into_iter().collect(), false).err(),
+            Some(
+                GraphError::UnparsableString {
+                    name: "reduction".to_string(),
+                    value: "max".to_string(),
+                    ty: Some(ast::LetType::Enum(vec![
+                        "mean".to_string(),
+                        "sum".to_string(),
+                        "none".to_string(),
+                    ])),
                 }
                 .into()
             )
         );
     }
 
+    #[test]
+    fn test_range_variables() {
+        let root = new_root();
+        let ir = root.get("Dropout").unwrap();
+        let graph = ir.data.graph.borrow();
+
+        let apply = |value: f64| {
+            let values = vec![("P".to_string(), Some(value.into()))];
+            graph.apply(values.into_iter().collect(), true)
+        };
+        apply(1.0).unwrap();
+
+        assert_eq!(
+            apply(1.5).err(),
+            Some(
+                GraphError::OutOfRange {
+                    name: "probability".to_string(),
+                    value: Box::new(1.5.into()),
+                    range: Box::new(ast::LetRange {
+                        min: Some(ast::LetBound {
+                            value: 0u64.into(),
+                            inclusive: true,
+                        }),
+                        max: Some(ast::LetBound {
+                            value: 1u64.into(),
+                            inclusive: true,
+                        }),
+                    }),
+                }
+                .into()
+            )
+        );
+    }
+
+    #[test]
+    fn test_range_variables_resolved() {
+        let model = "
+node MyNode:
+    let W: width = dim
+
+    with MaxPool2D:
+        set kernel size = 5 - W
+
+    0. Input = 1, W, W
+    1. MaxPool2D
+";
+        let root = new_root();
+        root.add_source("MyNode".to_string(), model.to_string());
+
+        // the kernel size is checked again when the width is resolved
+        let input = |w: u64| {
+            ast::Shapes::new(
+                btreemap! { "x".to_string() => Some(ast::Shape(vec![1u64.into(), w.into(), w.into()])) },
+            )
+        };
+        root.build_code("MyNode", Some(&input(4))).unwrap();
+        assert!(matches!(
+            root.build_code("MyNode", Some(&input(6))),
+            Err(Error::BuildError(BuildError::GraphError(
+                GraphError::OutOfRange { .. }
+            ))),
+        ));
+    }
+
+    #[test]
+    fn test_doc_comments() {
+        let model = "
+## My own node.
+node MyNode:
+    ## The input dimension
+    ## which is given by the user.
+    let Ic: input dimension = int 32
+    let Oc: output dimension = int 10  ## not a doc comment
+
+    ## The inner node.
+    node MyRelu:
+        1. Relu
+
+    0. Input = Ic
+    1. Linear + MyRelu = Oc
+";
+        let root = new_root();
+        let file = root.parser.parse_file(model).unwrap();
+        let node = &file.node;
+        assert_eq!(node.description.as_deref(), Some("My own node."));
+        assert_eq!(
+            node.graph["input dimension"].description.as_deref(),
+            Some("The input dimension\nwhich is given by the user."),
+        );
+        assert_eq!(node.graph["output dimension"].description, None);
+        assert_eq!(
+            node.children["MyRelu"].description.as_deref(),
+            Some("The inner node."),
+        );
+
+        // the doc comments should be preserved on formatting
+        let file_recon = root.parser.parse_file(&format!("{:?}", &file)).unwrap();
+        assert_eq!(format!("{:?}", &file), format!("{:?}", &file_recon));
+
+        // the doc comments should be stored into the variables
+        root.add_source("MyNode".to_string(), model.to_string());
+        let ir = root.get("MyNode").unwrap();
+        let graph = ir.data.graph.borrow();
+        assert_eq!(
+            graph.get("input dimension").unwrap().borrow().description,
+            node.graph["input dimension"].description,
+        );
+    }
+
+    #[test]
+    fn test_declaration_order() {
+        let root = new_root();
+        let ir = root.get_exec("ImageClassification").unwrap();
+        let graph = ir.data.graph.borrow();
+
+        let names: Vec<_> = graph.variables().keys().map(|x| x.as_str()).collect();
+        assert_eq!(
+            names,
+            ["data", "model", "loss", "optimizer", "epoch", "batch size"],
+        );
+    }
+
     #[test]
     fn test_build_process() {
         let model = "
@@ -181,13 +383,187 @@ node MyNode:
         assert_eq!(node_n7.data.output["x"].id, Some(6));
     }
 
+    #[test]
+    fn test_build_infer_input_dims() {
+        let model = "
+node MyNode:
+    let W: width = dim
+    let H: height = dim
+
+    with MaxPool2D:
+        set kernel size = 2
+        set stride = 2
+
+    0. Input        = 3, W, H
+    1. MaxPool2D    = 3, 7, 7
+";
+        let root = new_root();
+        root.add_source("MyNode".to_string(), model.to_string());
+        let ir = root.get("MyNode").unwrap();
+        let input = ir.get_input_shapes().unwrap().0.borrow()["x"]
+            .clone()
+            .unwrap();
+        ir.build(&root).unwrap();
+
+        // (W + M) / S = 7  -->  W = 14
+        assert_eq!(input.0[1].build(), 14u64.into());
+        assert_eq!(input.0[2].build(), 14u64.into());
+
+        // 2 * W = 7  -->  W = 3.5
+        let model = "
+node MyOddNode:
+    let W: width = dim
+
+    0. Input    = 3, 2 * W
+";
+        root.add_source("MyOddNode".to_string(), model.to_string());
+        let input = ast::Shapes::new(btreemap! {
+            "x".to_string() => Some(ast::Shape(vec![3u64.into(), 7u64.into()])),
+        });
+        assert_eq!(
+            root.build_code("MyOddNode", Some(&input)).err(),
+            Some(
+                LinkError::UnsolvableDim {
+                    name: "width".to_string(),
+                    expected: 7u64.into(),
+                    given: ast::Value::UInt(0),
+                }
+                .into()
+            )
+        );
+    }
+
+    #[test]
+    fn test_build_dynamic_dims() {
+        let model = "
+node MyNode:
+    let T: time = dim dynamic
+
+    0. Input        =  3, T, 28
+    1. Conv2D(K=3)  = 16, T, 28
+";
+        let root = new_root();
+        root.add_source("MyNode".to_string(), model.to_string());
+        let ir = root.get("MyNode").unwrap();
+        let input = ir.get_input_shapes().unwrap();
+
+        // the concrete sizes should not be linked to the dynamic dims
+        let given = ast::Shapes::new(btreemap! {
+            "x".to_string() => Some(ast::Shape(vec![3u64.into(), 40u64.into(), 28u64.into()])),
+        });
+        given.link_to(input).unwrap();
+
+        let time = input.0.borrow()["x"].as_ref().unwrap().0[1].clone();
+        let node = ir.build(&root).unwrap();
+        assert!(matches!(time.build(), ast::Value::Dim(_)));
+
+        // the shapes should be asserted on runtime
+        let input_node = node.as_node().tensor_graph[0].as_extern();
+        assert_eq!(input_node.data.name, "AssertShape");
+        assert!(input_node
+            .data
+            .graph
+            .variables
+            .contains_key("output shapes"));
+
+        // the static shapes are not asserted
+        let input = ast::Shapes::new(btreemap! {
+            "x".to_string() => Some(ast::Shape(vec![1u64.into(), 28u64.into(), 28u64.into()])),
+        });
+        let node = root.build_code("LeNet5", Some(&input)).unwrap();
+        let input_node = node.as_node().tensor_graph[0].as_extern();
+        assert_eq!(input_node.data.name, "AssertShape");
+        assert!(input_node.data.graph.variables.is_empty());
+    }
+
+    #[test]
+    fn test_summarize() {
+        let root = new_root();
+        let shape = |dims: &[u64]| Some(ast::Shape(dims.iter().map(|&x| x.into()).collect()));
+        let input = |dims: &[u64]| ast::Shapes::new(btreemap! {"x".to_string() => shape(dims)});
+        let output = |node: &NodeSummary| node.output.as_ref().unwrap().0.borrow()["x"].clone();
+
+        let summary = root
+            .summarize("LeNet5", Some(&input(&[1, 28, 28])))
+            .unwrap();
+        assert_eq!(summary.cost.params, Some(3_274_634));
+        assert_eq!(output(&summary), shape(&[10]));
+
+        let conv = &summary.children[2].children[0];
+        assert_eq!(conv.name, "Conv2D");
+        assert_eq!(conv.cost.params, Some(51_264));
+        assert_eq!(conv.cost.flops, Some(5_017_600));
+        assert_eq!(conv.cost.memory, Some(64 * 7 * 7));
+        assert_eq!(output(conv), shape(&[64, 7, 7]));
+
+        // the repeated nodes should be linked to each other
+        let summary = root.summarize("Vgg", Some(&input(&[3, 32, 32]))).unwrap();
+        let block = &summary.children[3].children[0];
+        assert_eq!(block.repeat, Some(2));
+        assert_eq!(block.children.len(), 6);
+        assert_eq!(block.children[0].cost.params, Some(295_168));
+        assert_eq!(block.children[3].cost.params, Some(590_080));
+        assert_eq!(summary.cost.params, Some(29_719_306));
+
+        let given = ast::Shapes::new(btreemap! {"y".to_string() => shape(&[3])});
+        assert_eq!(
+            root.summarize("LeNet5", Some(&given)).err(),
+            Some(
+                GraphCallError::MismatchedShapeKeys {
+                    expected: vec!["x".to_string()],
+                    given: vec!["y".to_string()],
+                }
+                .into()
+            )
+        );
+    }
+
+    #[test]
+    fn test_render_graph() {
+        let root = new_root();
+        let shape = |dims: &[u64]| Some(ast::Shape(dims.iter().map(|&x| x.into()).collect()));
+        let input = ast::Shapes::new(btreemap! {"x".to_string() => shape(&[1, 28, 28])});
+
+        let code = root.build_code("LeNet5", Some(&input)).unwrap();
+        let graph = code.render_graph(GraphFormat::Mermaid);
+        assert!(graph.contains("    subgraph c0 [\"MyConv\"]\n"));
+        assert!(graph.contains("        n2[\"Conv2D<br/>[32, 14, 14]\"]\n"));
+        assert!(graph.contains("    n9 --> n10\n"));
+
+        // the inputs of Concat should be drawn from each origin
+        let model = "
+node MyNode:
+    0. Input          = 32
+    1. Linear         = 64
+    2. Concat[$0, $1] (axis=-1)
+";
+        root.add_source("MyNode".to_string(), model.to_string());
+        let graph = root
+            .build_code("MyNode", None)
+            .unwrap()
+            .render_graph(GraphFormat::Dot);
+        assert!(graph.contains("    n3 [label=\"Concat\\n[96]\"];\n"));
+        assert!(graph.contains("    n1 -> n3 [label=\"0\"];\n    n2 -> n3 [label=\"1\"];\n"));
+    }
+
     #[test]
     fn test_build_concat() {
         let model = fs::read_to_string("tests/data/nodes/__user__/sample/test-cat.n3").unwrap();
         let root = new_root();
         root.add_source("TestCat".to_string(), model);
         let ir = root.get("TestCat").unwrap();
-        ir.build(&root).unwrap();
+        let code = ir.build(&root).unwrap();
+
+        // the inputs share the same name "x", so they are keyed by their indices
+        let concat = &code.as_node().tensor_graph[2];
+        let inputs: Vec<_> = concat.data().input.iter().collect();
+        assert_eq!(
+            inputs,
+            [
+                (&"0".to_string(), &ast::Out::new(1, "x".to_string())),
+                (&"1".to_string(), &ast::Out::new(2, "x".to_string())),
+            ]
+        );
     }
 
     #[test]
@@ -209,4 +585,166 @@ node MyNode:
         let ir = root.get("MyNode").unwrap();
         ir.build(&root).unwrap();
     }
+
+    #[test]
+    fn test_inspect() {
+        let root = new_root();
+        assert!(root.get_names().contains("LeNet5"));
+        assert!(root.get_source_path("LeNet5").is_some());
+
+        let node = root.inspect("LeNet5").unwrap();
+        assert_eq!(node.name, "LeNet5");
+        assert!(node
+            .variables
+            .iter()
+            .any(|x| x.borrow().name == "kernel size"));
+
+        let graph: Vec<_> = node.graph.iter().map(|x| (x.id, x.name.as_str())).collect();
+        assert_eq!(
+            graph,
+            [
+                (0, "AssertShape"),
+                (1, "MyConv"),
+                (2, "MyConv"),
+                (3, "ToLinear"),
+                (4, "Linear"),
+                (4, "Relu"),
+                (4, "Dropout"),
+                (5, "Linear"),
+            ]
+        );
+
+        // the shapes are inferred
+        let output = node.graph[3].output.as_ref().unwrap();
+        assert!(output.0.borrow()["x"].is_some());
+    }
+
+    #[test]
+    fn test_invalidate() {
+        let inner = |dim: u64| {
+            format!(
+                "
+node MyInner:
+    0. Input = 8
+    1. Linear = {}
+",
+                dim
+            )
+        };
+        let outer = "
+node MyOuter:
+    0. Input = 8
+    1. MyInner
+    2. Relu
+";
+
+        let root = new_root();
+        root.add_source("MyInner".to_string(), inner(4));
+        root.add_source("MyOuter".to_string(), outer.to_string());
+        root.inspect("MyOuter").unwrap();
+        root.inspect("LeNet5").unwrap();
+
+        // only the dependent nodes are invalidated
+        let invalidated = root.add_source("MyInner".to_string(), inner(16));
+        let invalidated: Vec<_> = invalidated.iter().map(|x| x.as_str()).collect();
+        assert_eq!(invalidated, ["MyInner", "MyOuter"]);
+
+        // the dependent nodes are rebuilt with the new source
+        let node = root.inspect("MyOuter").unwrap();
+        let output = node.graph[1].output.as_ref().unwrap();
+        assert_eq!(
+            format!("{:?}", output.0.borrow()["x"]),
+            "Some(output channels=16, )"
+        );
+
+        // the removed node cannot be found anymore
+        let invalidated = root.remove_source("MyInner");
+        assert_eq!(invalidated.len(), 2);
+        assert!(root.inspect("MyOuter").is_err());
+    }
+
+    #[test]
+    fn test_instantiate() {
+        let model = "
+node MyNode:
+    let K: kernel size = int 3
+
+    0. Input = 8
+    1. Linear = 4
+";
+        let root = new_root();
+        root.add_source("MyNode".to_string(), model.to_string());
+
+        let kernel_size = |ir: &NodeIR| ir.data.graph.borrow().get("kernel size").unwrap().clone();
+        let ir = root.get("MyNode").unwrap();
+        kernel_size(&ir).borrow_mut().value = Some(5u64.into());
+
+        // each instance has its own variables, and the cache is untouched
+        let ir_new = root.get("MyNode").unwrap();
+        assert_eq!(kernel_size(&ir).borrow().value, Some(5u64.into()));
+        assert_eq!(kernel_size(&ir_new).borrow().value, Some(3u64.into()));
+        assert_ne!(ir.data.graph.borrow().id, ir_new.data.graph.borrow().id);
+    }
+
+    #[test]
+    fn test_suggestions() {
+        let build = |name: &str, model: &str| {
+            let root = new_root();
+            root.add_source(name.to_string(), model.to_string());
+            root.get(name).err().unwrap()
+        };
+        let candidates = |x: &[&str]| x.iter().map(|x| x.to_string()).collect();
+
+        // node names
+        let model = "
+node MyNode:
+    0. Input    = 32
+    1. Linaer   = 64
+";
+        assert_eq!(
+            build("MyNode", model),
+            TensorNodeError::NoSuchNode {
+                name: "Linaer".to_string(),
+                candidates: candidates(&["Linear"]),
+            }
+            .into()
+        );
+
+        // with/set keys
+        let model = "
+node MyNode:
+    with Linear:
+        set bias_ = no
+
+    0. Input    = 32
+    1. Linear   = 64
+";
+        assert_eq!(
+            build("MyNode", model),
+            GraphError::NoSuchVariable {
+                name: "bias_".to_string(),
+                candidates: candidates(&["bias"]),
+            }
+            .into()
+        );
+
+        // call arguments
+        let model = "
+node MyNode:
+    let axis = int -1
+
+    0. Input                    = 32
+    1. Linear                   = 64
+    2. Concat[$0, $1] (axs=axis)
+";
+        assert_eq!(
+            build("MyNode", model),
+            GraphCallError::MismatchedArgs {
+                expected: &["axis"],
+                given: vec!["axs".to_string()],
+                candidates: candidates(&["axis"]),
+            }
+            .into()
+        );
+    }
 }
@@ -1,19 +1,25 @@
 use std::cell::UnsafeCell;
+use std::collections::BTreeSet;
 use std::path::PathBuf;
 
+use super::inspect::NodeInspection;
 use super::ir::NodeIR;
+use super::summary::NodeSummary;
+use crate::ast;
 use crate::cache::NodeCache;
-use crate::error::Result;
-use crate::execs::{ExecIR, GlobalVars};
-use crate::externs::PythonScript;
+use crate::code::Code;
+use crate::error::{ExecBuildError, GraphCallError, Result};
+use crate::execs::{ExecIR, GlobalVars, Program};
+use crate::externs::{Backend, ExternRegistry, ExternScript};
 use crate::n3_std;
 use crate::seed::Seed;
 use crate::tensor::TensorNode;
+use crate::variable::Link;
 
 pub struct NodeRoot {
     pub(crate) seed: Seed,
     sources: NodeCache<TensorNode>,
-    externs: NodeCache<PythonScript>,
+    externs: ExternRegistry,
     pub(crate) parser: crate::Parser,
     _thread_unsafe: UnsafeCell<()>,
 }
@@ -24,38 +30,146 @@ impl NodeRoot {
             .map(PathBuf::from)
             .unwrap_or_else(GlobalVars::get_n3_source_root);
 
-        Self {
+        let root = Self {
             seed: Seed::default(),
-            sources: NodeCache::new(n3_std::get_sources(&n3_source_root)),
-            externs: NodeCache::new(n3_std::get_externs(&n3_source_root)),
+            sources: NodeCache::new(Default::default()),
+            externs: ExternRegistry::new(&n3_source_root),
             parser: crate::Parser::default(),
             _thread_unsafe: UnsafeCell::new(()),
+        };
+
+        for (name, path) in n3_std::get_source_paths(&n3_source_root) {
+            root.add_source_path(name, path.display().to_string());
         }
+        root
     }
 }
 
 impl NodeRoot {
-    pub fn add_source(&self, name: String, source: String) {
-        self.sources.add_source(name, source);
+    /// Add the source, and invalidate the nodes which depend on it.
+    /// Returns the names of the invalidated nodes.
+    pub fn add_source(&self, name: String, source: String) -> BTreeSet<String> {
+        self.sources.add_source(name, source.into())
+    }
+
+    /// Add the parsed source, so that the node is built without parsing again.
+    /// Returns the names of the invalidated nodes.
+    pub fn add_file(&self, name: String, file: ast::File) -> BTreeSet<String> {
+        self.sources.add_source(name, file.into())
+    }
+
+    /// Remove the source, so that the node is loaded from the file again.
+    /// Returns the names of the invalidated nodes.
+    pub fn remove_source(&self, name: &str) -> BTreeSet<String> {
+        self.sources.remove_source(name)
+    }
+
+    pub fn add_source_path(&self, name: String, path: String) -> BTreeSet<String> {
+        self.sources.add_path(name, path)
+    }
+
+    /// Add the implementation of the extern node, for the backend.
+    pub fn add_extern(&self, backend: Backend, name: String, source: String) {
+        self.externs.add_source(backend, name, source);
+    }
+
+    pub fn add_extern_path(&self, backend: Backend, name: String, path: String) {
+        self.externs.add_path(backend, name, path);
+    }
+
+    /// Get the names of the extern nodes which are implemented for the backend.
+    pub fn get_extern_names(&self, backend: Backend) -> BTreeSet<String> {
+        self.externs.names(backend)
+    }
+
+    pub fn get_source_path(&self, name: &str) -> Option<PathBuf> {
+        self.sources.get_path(name).map(PathBuf::from)
     }
 
-    pub fn add_source_path(&self, name: String, path: String) {
-        self.sources.add_path(name, path);
+    pub fn get_names(&self) -> BTreeSet<String> {
+        self.sources.names()
     }
 
-    pub fn add_extern_path(&self, name: String, path: String) {
-        self.externs.add_path(name, path);
+    /// Build the node, and collect the variables and the inferred shapes.
+    pub fn inspect(&self, name: &str) -> Result<NodeInspection> {
+        let node = self.sources.get(name, self)?;
+        Ok((&node).into())
+    }
+
+    /// Build the node with the given input shapes,
+    /// and summarize the resolved shapes and costs of each graph node.
+    pub fn summarize(&self, name: &str, input: Option<&ast::Shapes>) -> Result<NodeSummary> {
+        let mut node = TensorNode::from(self.get_with_input(name, input)?);
+        let summary = NodeSummary::new(self, &mut node, None)?;
+        node.unwrap_node()?.build(self)?;
+        Ok(summary)
+    }
+
+    /// Build the node with the given input shapes.
+    pub fn build_code(&self, name: &str, input: Option<&ast::Shapes>) -> Result<Code> {
+        self.get_with_input(name, input)?.build(self)
+    }
+
+    /// Build the exec node with the given arguments for the backend, without the env.
+    pub fn build_exec(
+        &self,
+        name: &str,
+        args: &[(&str, &str)],
+        backend: Backend,
+    ) -> Result<Program> {
+        let ir = self.get_exec(name)?;
+        let vars = ir.args();
+        for (name, value) in args {
+            vars.set(name, value)?;
+        }
+        ir.build(self, backend)
+    }
+
+    pub fn is_exec(&self, name: &str) -> Result<bool> {
+        Ok(matches!(self.sources.get(name, self)?, TensorNode::Exec(_)))
+    }
+
+    pub(crate) fn get_tensor_node(&self, name: &str) -> Result<TensorNode> {
+        self.sources.get(name, self)
     }
 
     pub(crate) fn get(&self, name: &str) -> Result<NodeIR> {
         self.sources.get(name, self)?.unwrap_node()
     }
 
+    fn get_with_input(&self, name: &str, input: Option<&ast::Shapes>) -> Result<NodeIR> {
+        let ir = self.get(name)?;
+        if let Some(given) = input {
+            let expected = ir.get_input_shapes();
+            let expected_keys: Vec<_> = expected
+                .map(|x| x.0.borrow().keys().cloned().collect())
+                .unwrap_or_default();
+            let given_keys: Vec<_> = given.0.borrow().keys().cloned().collect();
+            if given_keys.iter().any(|x| !expected_keys.contains(x)) {
+                return GraphCallError::MismatchedShapeKeys {
+                    expected: expected_keys,
+                    given: given_keys,
+                }
+                .into();
+            }
+            Some(given).link_to(&expected)?;
+        }
+        Ok(ir)
+    }
+
     pub(crate) fn get_exec(&self, name: &str) -> Result<ExecIR> {
         self.sources.get(name, self)?.unwrap_exec()
     }
 
-    pub(crate) fn get_extern(&self, name: &str) -> Result<PythonScript> {
-        self.externs.get(name, self)
+    pub(crate) fn get_extern(&self, backend: Backend, name: &str) -> Result<ExternScript> {
+        // note: the node exists, but is not implemented for the backend
+        if !self.externs.contains(backend, name) && self.sources.names().contains(name) {
+            return ExecBuildError::NoSuchImplementation {
+                name: name.to_string(),
+                backend,
+            }
+            .into();
+        }
+        self.externs.get(backend, name, self)
     }
 }
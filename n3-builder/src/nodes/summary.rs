@@ -0,0 +1,96 @@
+use super::root::NodeRoot;
+use crate::ast;
+use crate::cost::Cost;
+use crate::error::Result;
+use crate::tensor::TensorNode;
+use crate::variable::{build_shapes, BuildValue};
+
+/// The resolved shapes and costs of a node, which is called by a line of the tensor graph.
+#[derive(Debug)]
+pub struct NodeSummary {
+    pub id: u64,
+    pub name: String,
+    pub input: Option<ast::Shapes>,
+    pub output: Option<ast::Shapes>,
+    /// The public variables, by their shortcuts if exist
+    pub variables: Vec<(String, ast::Value)>,
+    pub repeat: Option<u64>,
+    /// The estimated cost, including the children
+    pub cost: Cost,
+    pub children: Vec<NodeSummary>,
+}
+
+impl NodeSummary {
+    /// Summarize the node, expanding the repeated graphs.
+    /// The input shapes are inherited from the last node if not given.
+    pub(super) fn new(
+        root: &NodeRoot,
+        node: &mut TensorNode,
+        last: Option<&ast::Shapes>,
+    ) -> Result<Self> {
+        let input = node.get_input_shapes().or(last).map(build_shapes);
+        let output = node
+            .get_output_shapes()
+            .map(build_shapes)
+            .or_else(|| input.clone());
+
+        let mut repeat = None;
+        let mut children = vec![];
+        let cost = match node {
+            TensorNode::Node(node) => {
+                repeat = node.repeat.as_ref().and_then(|x| x.build().unwrap_uint());
+                node.expand(root)?;
+
+                match node.ty {
+                    ast::LetNodeType::Extern(_) => node.tensor_graph.iter().map(cost_of).sum(),
+                    ast::LetNodeType::Default => {
+                        let mut last = input.clone();
+                        for child in node.tensor_graph.iter_mut() {
+                            let child = Self::new(root, child, last.as_ref())?;
+                            last = child.output.clone();
+                            children.push(child);
+                        }
+                        children.iter().map(|x| x.cost).sum()
+                    }
+                }
+            }
+            TensorNode::Extern(_) | TensorNode::Exec(_) => cost_of(node),
+        };
+
+        // note: the variables of the builtin nodes are internal
+        let variables = match node {
+            TensorNode::Node(_) => node
+                .get_graph()
+                .borrow()
+                .variables()
+                .values()
+                .filter(|x| !x.borrow().name.starts_with('_'))
+                .map(|x| {
+                    let var = x.borrow();
+                    let name = var.shortcut.as_ref().unwrap_or(&var.name);
+                    (name.clone(), x.build())
+                })
+                .collect(),
+            _ => vec![],
+        };
+
+        Ok(Self {
+            id: node.get_id(),
+            name: node.name().to_string(),
+            input,
+            output,
+            variables,
+            repeat,
+            cost,
+            children,
+        })
+    }
+}
+
+fn cost_of(node: &TensorNode) -> Cost {
+    match node {
+        TensorNode::Extern(node) => node.into(),
+        // note: the costs are declared on the extern nodes only
+        TensorNode::Node(_) | TensorNode::Exec(_) => Cost::default(),
+    }
+}
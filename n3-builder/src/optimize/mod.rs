@@ -0,0 +1,341 @@
+mod passes;
+
+use std::collections::BTreeMap;
+
+use crate::ast;
+use crate::code::{Code, Codes};
+use crate::error::{ExecBuildError, Result};
+use crate::nodes::NodeCode;
+use crate::suggest::suggest;
+
+pub use self::passes::PASSES;
+
+/// An optimization pass on the built codes, which is identified by the name. (e.g. `fold-constants`)
+///
+/// The passes should keep the outputs of the codes.
+pub struct Pass {
+    pub name: &'static str,
+    pub description: &'static str,
+    run: fn(&mut Codes),
+}
+
+/// The passes to be applied before the codes are compacted.
+pub struct Pipeline {
+    enabled: BTreeMap<&'static str, bool>,
+}
+
+impl Default for Pipeline {
+    fn default() -> Self {
+        Self {
+            enabled: PASSES.iter().map(|x| (x.name, true)).collect(),
+        }
+    }
+}
+
+impl Pipeline {
+    pub fn set_enabled(&mut self, name: &str, enabled: bool) -> Result<()> {
+        match self.enabled.get_mut(name) {
+            Some(value) => {
+                *value = enabled;
+                Ok(())
+            }
+            None => ExecBuildError::NoSuchPass {
+                name: name.to_string(),
+                candidates: suggest(name, self.enabled.keys()),
+            }
+            .into(),
+        }
+    }
+
+    pub fn run(&self, nodes: &mut Codes) {
+        for pass in PASSES {
+            if self.enabled[pass.name] {
+                (pass.run)(nodes);
+            }
+        }
+    }
+}
+
+/// Visit the code and its descendants, from the root.
+fn visit_codes(code: &Code, f: &mut impl FnMut(&Code)) {
+    f(code);
+    if let Code::Node(node) = code {
+        for child in &node.tensor_graph {
+            visit_codes(child, f);
+        }
+    }
+}
+
+/// Visit the node and its descendant nodes, from the leaves.
+fn visit_nodes_mut(code: &mut Code, f: &mut impl FnMut(&mut NodeCode)) {
+    if let Code::Node(node) = code {
+        for child in node.tensor_graph.iter_mut() {
+            visit_nodes_mut(child, f);
+        }
+        f(node);
+    }
+}
+
+/// Visit the values of the code and its descendants, including the nested ones.
+///
+/// The values of the referred variables are not visited.
+fn visit_values(code: &Code, f: &mut impl FnMut(&ast::Value)) {
+    visit_codes(code, &mut |code| {
+        for var in code.data().graph.variables.values() {
+            if let Some(value) = &var.borrow().value {
+                walk_value(value, f);
+            }
+        }
+        if let Code::Extern(node) = code {
+            for shapes in node.shapes.input.iter().chain(&node.shapes.output) {
+                for shape in shapes.0.borrow().values().flatten() {
+                    for dim in &shape.0 {
+                        walk_value(dim, f);
+                    }
+                }
+            }
+        }
+    });
+}
+
+fn walk_value(value: &ast::Value, f: &mut impl FnMut(&ast::Value)) {
+    f(value);
+    match value {
+        ast::Value::Expr(expr) => {
+            walk_value(&expr.lhs, f);
+            if let Some(rhs) = &expr.rhs {
+                walk_value(rhs, f);
+            }
+        }
+        ast::Value::List(list) => list.iter().for_each(|x| walk_value(x, f)),
+        ast::Value::Map(map) => map.values().flatten().for_each(|x| walk_value(x, f)),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::ToValues;
+    use crate::nodes::NodeRoot;
+    use crate::variable::BuildValue;
+
+    const SOURCE: &str = "node MyNode:
+    let K: kernel size = int 3
+
+    with Conv2D:
+        set kernel size = K
+
+    node MyRelu:
+        1. Relu
+
+    0. Input            = 3, 8, 8
+    1. Conv2D           = 4, 8, 8
+    2. Transform        = 4, 8, 8
+    3. Relu{x=$1}
+    4. MyRelu{x=$2}     = 4, 8, 8
+    5. Transform        = 256
+";
+
+    type Tensors = BTreeMap<String, String>;
+
+    fn new_root() -> NodeRoot {
+        let root = NodeRoot::new(Some("../n3-torch/ffi/python/n3"));
+        root.add_source("MyNode".to_string(), SOURCE.to_string());
+        root
+    }
+
+    /// The nodes to be optimized, with their input shapes.
+    const NODES: &[(&str, [u64; 3])] = &[
+        ("MyNode", [3, 8, 8]),
+        ("LeNet5", [1, 28, 28]),
+        ("Vgg", [3, 32, 32]),
+    ];
+
+    fn build_code(root: &NodeRoot, name: &str) -> Code {
+        let (_, dims) = NODES.iter().find(|(x, _)| *x == name).unwrap();
+        let shape = ast::Shape(dims.iter().map(|&x| x.into()).collect());
+        let input = ast::Shapes::new(Some(("x".to_string(), Some(shape))).into_iter().collect());
+
+        root.build_code(name, Some(&input)).unwrap()
+    }
+
+    fn build(root: &NodeRoot, name: &str, pipeline: &Pipeline) -> Code {
+        let mut nodes = Codes::new();
+        nodes.insert(name.to_string(), build_code(root, name));
+        pipeline.run(&mut nodes);
+        nodes.remove(name).unwrap()
+    }
+
+    fn pipeline_with(passes: &[&str]) -> Pipeline {
+        let mut pipeline = Pipeline::default();
+        for pass in PASSES {
+            pipeline
+                .set_enabled(pass.name, passes.contains(&pass.name))
+                .unwrap();
+        }
+        pipeline
+    }
+
+    /// Evaluate the code symbolically, following the dataflow of `NodeExecutable`.
+    fn eval(code: &Code, input: Tensors) -> Tensors {
+        match code {
+            Code::Node(node) => {
+                let mut outputs: BTreeMap<_, _> = input
+                    .into_iter()
+                    .map(|(k, x)| (ast::Out::new(1, k), x))
+                    .collect();
+
+                let mut x = BTreeMap::new();
+                for child in &node.tensor_graph {
+                    let data = child.data();
+                    let input = data
+                        .input
+                        .iter()
+                        .map(|(k, n)| (k.clone(), outputs[n].clone()))
+                        .collect();
+                    let y = eval(child, input);
+
+                    x = data
+                        .output
+                        .iter()
+                        .map(|(k, n)| (n.clone(), y[k].clone()))
+                        .collect();
+                    outputs.extend(x.clone());
+                }
+                x.into_iter().map(|(k, x)| (k.name, x)).collect()
+            }
+            Code::Extern(node) => {
+                // note: reshaping into the same shape does nothing
+                if node.data.name == "Transform" && node.shapes.input == node.shapes.output {
+                    return input;
+                }
+
+                let args: Vec<_> = input
+                    .iter()
+                    .map(|(k, x)| format!("{}={}", k, x))
+                    .chain(
+                        node.data
+                            .graph
+                            .to_values()
+                            .into_iter()
+                            .map(|(k, v)| format!("{}={:?}", k, v.map(|x| x.build()))),
+                    )
+                    .collect();
+                node.data
+                    .output
+                    .keys()
+                    .map(|k| {
+                        let x = format!("{}({})[{}]", &node.data.name, args.join(", "), k);
+                        (k.clone(), x)
+                    })
+                    .collect()
+            }
+        }
+    }
+
+    fn assert_same_outputs(name: &str, passes: &[&str]) {
+        let root = new_root();
+        let code = build(&root, name, &pipeline_with(&[]));
+        let optimized = build(&root, name, &pipeline_with(passes));
+
+        let input: Tensors = vec![("x".to_string(), "x".to_string())]
+            .into_iter()
+            .collect();
+        assert_eq!(
+            eval(&code, input.clone()),
+            eval(&optimized, input),
+            "{} with {:?}",
+            name,
+            passes,
+        );
+    }
+
+    #[test]
+    fn test_each_pass() {
+        for (name, _) in NODES {
+            for pass in PASSES {
+                assert_same_outputs(name, &[pass.name]);
+            }
+            assert_same_outputs(name, &PASSES.iter().map(|x| x.name).collect::<Vec<_>>());
+        }
+    }
+
+    #[test]
+    fn test_optimize() {
+        let root = new_root();
+        let code = build(&root, "MyNode", &Pipeline::default());
+        let node = code.as_node();
+
+        let names: Vec<_> = node.tensor_graph.iter().map(|x| &x.data().name).collect();
+        assert_eq!(
+            names,
+            ["AssertShape", "Conv2D", "Relu", "Relu", "Transform"]
+        );
+
+        // the constants are folded
+        let conv = node.tensor_graph[1].as_extern();
+        let padding = conv.data.graph.variables["padding"].borrow();
+        assert_eq!(padding.value, Some(ast::Value::Int(1)));
+
+        // the extern nodes keep their outputs, even on the dead branch
+        let relu_dead = node.tensor_graph[2].as_extern();
+        assert_eq!(relu_dead.data.output.len(), 1);
+
+        // the flattened node is connected to the identity transform's input
+        let relu = node.tensor_graph[3].as_extern();
+        assert_eq!(relu.data.input["x"], conv.data.output["x"]);
+    }
+
+    #[test]
+    fn test_extern_outputs() {
+        let root = new_root();
+        root.add_source(
+            "MyPair".to_string(),
+            "extern node MyPair:
+    0. Input:
+        x = 4, 8, 8
+        y = 4, 8, 8
+    1. Output:
+        x = 4, 8, 8
+        y = 4, 8, 8
+"
+            .to_string(),
+        );
+        root.add_source(
+            "MyPairNode".to_string(),
+            "node MyPairNode:
+    0. Input:
+        x = 4, 8, 8
+        y = 4, 8, 8
+    1. MyPair
+    2. Conv2D{x=$1} = 4, 8, 8
+    3. Transform    = 256
+"
+            .to_string(),
+        );
+
+        let mut nodes = Codes::new();
+        nodes.insert(
+            "MyPairNode".to_string(),
+            root.build_code("MyPairNode", None).unwrap(),
+        );
+        Pipeline::default().run(&mut nodes);
+        let node = nodes["MyPairNode"].as_node();
+
+        // the extern node still returns all of its outputs, though `y` is never consumed
+        let pair = node.tensor_graph[1].as_extern();
+        assert_eq!(pair.data.name, "MyPair");
+        assert_eq!(pair.data.output.keys().collect::<Vec<_>>(), ["x", "y"]);
+    }
+
+    #[test]
+    fn test_disabled() {
+        let root = new_root();
+        let code = build_code(&root, "MyNode");
+        assert_eq!(build(&root, "MyNode", &pipeline_with(&[])), code);
+
+        let mut pipeline = Pipeline::default();
+        assert!(pipeline.set_enabled("fold-constant", false).is_err());
+    }
+}
@@ -0,0 +1,252 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use super::{visit_codes, visit_nodes_mut, visit_values, Pass};
+use crate::ast;
+use crate::code::{Code, CodeData, Codes};
+use crate::nodes::builtins::NODE__Transform;
+use crate::variable::BuildValue;
+
+/// The passes, which are applied in order.
+pub static PASSES: &[Pass] = &[
+    Pass {
+        name: "fold-constants",
+        description: "Evaluate the values which do not depend on the runtime.",
+        run: fold_constants,
+    },
+    Pass {
+        name: "drop-identity-transforms",
+        description: "Remove the `Transform`s which keep the shapes.",
+        run: drop_identity_transforms,
+    },
+    Pass {
+        name: "flatten-nodes",
+        description: "Replace the nodes which have only one child into the child.",
+        run: flatten_nodes,
+    },
+    Pass {
+        name: "eliminate-unused-outputs",
+        description: "Remove the outputs of the child nodes which are never consumed.",
+        run: eliminate_unused_outputs,
+    },
+];
+
+fn fold_constants(nodes: &mut Codes) {
+    for code in nodes.values() {
+        visit_codes(code, &mut |code| {
+            for var in code.data().graph.variables.values() {
+                let value = var
+                    .borrow()
+                    .value
+                    .as_ref()
+                    .filter(|x| !is_constant(x))
+                    .map(|x| x.build());
+
+                if let Some(value) = value.filter(is_constant) {
+                    var.borrow_mut().value = Some(value);
+                }
+            }
+        });
+    }
+}
+
+fn drop_identity_transforms(nodes: &mut Codes) {
+    for code in nodes.values_mut() {
+        visit_nodes_mut(code, &mut |node| {
+            let graph = &mut node.tensor_graph;
+
+            // note: the last child returns the outputs of the node
+            let mut index = 0;
+            while index + 1 < graph.len() {
+                let (head, tail) = graph.split_at_mut(index + 1);
+                let code = &head[index];
+                if is_identity_transform(code) && bypass(code.data(), tail) {
+                    graph.remove(index);
+                } else {
+                    index += 1;
+                }
+            }
+        });
+    }
+}
+
+fn flatten_nodes(nodes: &mut Codes) {
+    // note: the tables of the flattened nodes are removed
+    let mut referenced = BTreeSet::new();
+    for code in nodes.values() {
+        visit_values(code, &mut |value| {
+            if let ast::Value::Variable(var) = value {
+                referenced.extend(var.borrow().id);
+            }
+        });
+    }
+
+    for code in nodes.values_mut() {
+        visit_nodes_mut(code, &mut |node| {
+            for child in node.tensor_graph.iter_mut() {
+                flatten(child, &referenced);
+            }
+        });
+    }
+}
+
+fn eliminate_unused_outputs(nodes: &mut Codes) {
+    for code in nodes.values_mut() {
+        visit_nodes_mut(code, &mut |node| {
+            let mut used = BTreeSet::new();
+
+            // note: the last child returns the outputs of the node
+            let mut children = node.tensor_graph.iter_mut().rev();
+            if let Some(last) = children.next() {
+                collect_used_outs(last, &mut used);
+            }
+            for child in children {
+                // note: the extern nodes return all of their outputs
+                if let Code::Node(child) = child {
+                    child.data.output.retain(|_, out| used.contains(out));
+                }
+                collect_used_outs(child, &mut used);
+            }
+        });
+    }
+}
+
+fn is_constant(value: &ast::Value) -> bool {
+    match value {
+        ast::Value::Bool(_)
+        | ast::Value::UInt(_)
+        | ast::Value::Int(_)
+        | ast::Value::Real(_)
+        | ast::Value::String(_) => true,
+        ast::Value::List(x) => x.iter().all(is_constant),
+        ast::Value::Map(x) => x.values().flatten().all(is_constant),
+        _ => false,
+    }
+}
+
+fn is_identity_transform(code: &Code) -> bool {
+    let node = match code {
+        Code::Extern(node) if node.data.name == NODE__Transform => node,
+        _ => return false,
+    };
+
+    let (input, output) = match (&node.shapes.input, &node.shapes.output) {
+        (Some(input), Some(output)) => (input.0.borrow(), output.0.borrow()),
+        _ => return false,
+    };
+    input.len() == output.len()
+        && input.iter().all(|(name, input)| {
+            match (input, output.get(name)) {
+                (Some(input), Some(Some(output))) => {
+                    input.0.len() == output.0.len()
+                        && input
+                            .0
+                            .iter()
+                            .zip(&output.0)
+                            .all(|(x, y)| is_constant(x) && x == y)
+                }
+                _ => false,
+            }
+        })
+}
+
+/// Connect the following nodes into the inputs of the node, instead of its outputs.
+///
+/// It fails if any input of the node would be overwritten before it is consumed.
+fn bypass(data: &CodeData, tail: &mut [Code]) -> bool {
+    // the outputs which still hold the values of the node
+    let mut aliases = BTreeMap::new();
+    for (name, output) in &data.output {
+        let input = match data.input.get(name) {
+            Some(input) => input,
+            None => return false,
+        };
+        aliases.insert(output, input);
+    }
+    // the inputs which are not overwritten yet
+    let mut sources: BTreeSet<_> = aliases.values().copied().collect();
+
+    let mut dims = BTreeSet::new();
+    for code in tail.iter() {
+        collect_dims(code, &mut dims);
+    }
+    if aliases.keys().any(|x| dims.contains(*x)) {
+        return false;
+    }
+
+    let mut edits = vec![];
+    for (index, code) in tail.iter().enumerate() {
+        let data = code.data();
+        for (name, out) in &data.input {
+            if let Some(&source) = aliases.get(out) {
+                if !sources.contains(source) {
+                    return false;
+                }
+                edits.push((index, name.clone(), source.clone()));
+            }
+        }
+        for out in data.output.values() {
+            aliases.remove(out);
+            sources.remove(out);
+        }
+    }
+
+    for (index, name, source) in edits {
+        tail[index].data_mut().input.insert(name, source);
+    }
+    true
+}
+
+/// Replace the node into its only child, connecting the child to the outside directly.
+fn flatten(code: &mut Code, referenced: &BTreeSet<u64>) {
+    let node = match code {
+        Code::Node(node)
+            if node.tensor_graph.len() == 1 && !referenced.contains(&node.data.graph.id) =>
+        {
+            node
+        }
+        _ => return,
+    };
+    let child = node.tensor_graph[0].data();
+
+    // the node passes the inputs as `$1`, and returns the outputs by their names
+    let names: BTreeSet<_> = child.output.values().map(|x| &x.name).collect();
+    if names.len() != child.output.len() || node.data.output.keys().any(|x| !names.contains(x)) {
+        return;
+    }
+
+    let input = child
+        .input
+        .iter()
+        .map(|(name, out)| match out.id {
+            Some(1) => Some((name.clone(), node.data.input.get(&out.name)?.clone())),
+            _ => None,
+        })
+        .collect::<Option<ast::Outs>>();
+    let output = child
+        .output
+        .iter()
+        .map(|(name, out)| Some((name.clone(), node.data.output.get(&out.name)?.clone())))
+        .collect::<Option<ast::Outs>>();
+
+    if let (Some(input), Some(output)) = (input, output) {
+        let mut child = node.tensor_graph.pop().unwrap();
+        let data = child.data_mut();
+        data.input = input;
+        data.output = output;
+        *code = child;
+    }
+}
+
+/// Collect the outs which are consumed by the node, including the dynamic dims.
+fn collect_used_outs(code: &Code, used: &mut BTreeSet<ast::Out>) {
+    used.extend(code.data().input.values().cloned());
+    collect_dims(code, used);
+}
+
+fn collect_dims(code: &Code, outs: &mut BTreeSet<ast::Out>) {
+    visit_values(code, &mut |value| {
+        if let ast::Value::Dim(dim) = value {
+            outs.insert(dim.out.clone());
+        }
+    });
+}
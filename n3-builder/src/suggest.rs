@@ -0,0 +1,114 @@
+use std::collections::BTreeSet;
+
+/// Find the most similar candidates to the given name.
+///
+/// The names are compared regardless of the case, spaces, underscores and hyphens,
+/// so that `kernel_size`, `kernel-size` and `Kernel Size` are the same.
+pub fn suggest<I, S>(name: &str, candidates: I) -> BTreeSet<String>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let name = normalize(name);
+    // note: allow a typo per 3 characters, like rustc
+    let max_distance = (name.len() / 3).max(1);
+
+    let mut best = max_distance;
+    let mut suggestions = BTreeSet::new();
+    for candidate in candidates {
+        let candidate = candidate.as_ref();
+        let distance = distance(&name, &normalize(candidate));
+        if distance > best {
+            continue;
+        }
+        if distance < best {
+            best = distance;
+            suggestions.clear();
+        }
+        suggestions.insert(candidate.to_string());
+    }
+    suggestions
+}
+
+/// Make a hint message, like "did you mean `Conv2D`?".
+pub fn did_you_mean(candidates: &BTreeSet<String>) -> Option<String> {
+    let candidates: Vec<_> = candidates.iter().map(|x| format!("`{}`", x)).collect();
+    match candidates.as_slice() {
+        [] => None,
+        [candidate] => Some(format!("did you mean {}?", candidate)),
+        [candidates @ .., last] => Some(format!(
+            "did you mean {} or {}?",
+            candidates.join(", "),
+            last
+        )),
+    }
+}
+
+fn normalize(name: &str) -> Vec<char> {
+    name.chars()
+        .filter(|x| !matches!(x, ' ' | '_' | '-'))
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// The optimal string alignment distance, which counts a transposition as a typo.
+fn distance(a: &[char], b: &[char]) -> usize {
+    let mut d = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in d.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, value) in d[0].iter_mut().enumerate() {
+        *value = j;
+    }
+
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = (a[i - 1] != b[j - 1]) as usize;
+            d[i][j] = (d[i - 1][j] + 1)
+                .min(d[i][j - 1] + 1)
+                .min(d[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                d[i][j] = d[i][j].min(d[i - 2][j - 2] + 1);
+            }
+        }
+    }
+    d[a.len()][b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn suggest_str(name: &str, candidates: &[&str]) -> Vec<String> {
+        suggest(name, candidates).into_iter().collect()
+    }
+
+    #[test]
+    fn test_suggest() {
+        let nodes = ["Conv1D", "Conv2D", "ConvTranspose2D", "Linear", "Relu"];
+        assert_eq!(suggest_str("Conv2d", &nodes), ["Conv2D"]);
+        assert_eq!(suggest_str("Conv3D", &nodes), ["Conv1D", "Conv2D"]);
+        assert_eq!(suggest_str("Lineer", &nodes), ["Linear"]);
+        assert_eq!(suggest_str("Softmax", &nodes), [] as [&str; 0]);
+
+        let variables = ["kernel size", "padding", "stride"];
+        assert_eq!(suggest_str("kernel_size", &variables), ["kernel size"]);
+        assert_eq!(suggest_str("kernal-size", &variables), ["kernel size"]);
+        assert_eq!(suggest_str("strides", &variables), ["stride"]);
+        assert_eq!(suggest_str("stirde", &variables), ["stride"]);
+    }
+
+    #[test]
+    fn test_did_you_mean() {
+        let candidates = |x: &[&str]| x.iter().map(|x| x.to_string()).collect();
+        assert_eq!(did_you_mean(&candidates(&[])), None);
+        assert_eq!(
+            did_you_mean(&candidates(&["Conv2D"])).unwrap(),
+            "did you mean `Conv2D`?"
+        );
+        assert_eq!(
+            did_you_mean(&candidates(&["Conv1D", "Conv2D", "Conv3D"])).unwrap(),
+            "did you mean `Conv1D`, `Conv2D` or `Conv3D`?"
+        );
+    }
+}
@@ -1,6 +1,8 @@
 use std::ops::{Deref, DerefMut};
+use std::rc::Rc;
 
-use crate::ast;
+use crate::ast::fold::DeepCopy;
+use crate::ast::{self, Fold};
 use crate::code::Code;
 use crate::context::{Build, CloneSafe, Context};
 use crate::error::{Result, TensorNodeError};
@@ -9,6 +11,7 @@ use crate::externs::ExternIR;
 use crate::graph::{RefGraph, Values};
 use crate::nodes::{builtins, ASTBuild, NodeIR, NodeRoot};
 use crate::seed::Seed;
+use crate::variable::Bindings;
 
 #[derive(Default, Debug, PartialEq)]
 pub struct TensorGraph(Vec<TensorNode>);
@@ -39,8 +42,9 @@ pub struct IRData {
     pub id: u64,
     pub name: String,
     pub graph: RefGraph,
-    pub input: ast::Outs,
-    pub output: ast::Outs,
+    /// The IO of the node, which are shared by the instances until modified
+    pub input: Rc<ast::Outs>,
+    pub output: Rc<ast::Outs>,
 }
 
 impl PartialEq for IRData {
@@ -185,11 +189,11 @@ impl TensorNode {
     }
 
     pub fn get_inputs_mut(&mut self) -> &mut ast::Outs {
-        &mut self.get_data_mut().input
+        Rc::make_mut(&mut self.get_data_mut().input)
     }
 
     pub fn get_outputs_mut(&mut self) -> &mut ast::Outs {
-        &mut self.get_data_mut().output
+        Rc::make_mut(&mut self.get_data_mut().output)
     }
 
     pub fn get_input_shapes(&self) -> Option<&ast::Shapes> {
@@ -266,13 +270,13 @@ fn exec_node_cannot_have_shapes() -> ! {
 }
 
 impl CloneSafe for TensorGraph {
-    fn clone_safe(&self, seed: &Seed, variables: &mut Vec<ast::RefVariable>) -> Self {
+    fn clone_safe(&self, seed: &Seed, variables: &mut Bindings) -> Self {
         Self(self.0.clone_safe(seed, variables))
     }
 }
 
 impl CloneSafe for TensorNode {
-    fn clone_safe(&self, seed: &Seed, variables: &mut Vec<ast::RefVariable>) -> Self {
+    fn clone_safe(&self, seed: &Seed, variables: &mut Bindings) -> Self {
         match self {
             Self::Node(node) => node.clone_safe(seed, variables).into(),
             Self::Extern(node) => node.clone_safe(seed, variables).into(),
@@ -282,7 +286,7 @@ impl CloneSafe for TensorNode {
 }
 
 impl CloneSafe for IRData {
-    fn clone_safe(&self, seed: &Seed, variables: &mut Vec<ast::RefVariable>) -> Self {
+    fn clone_safe(&self, seed: &Seed, variables: &mut Bindings) -> Self {
         Self {
             id: self.id,
             name: self.name.clone(),
@@ -293,11 +297,43 @@ impl CloneSafe for IRData {
     }
 }
 
+/// The source of a node, which may be parsed in advance (e.g. by the editors).
+pub enum NodeSource {
+    Text(String),
+    File(Box<ast::File>),
+}
+
+impl From<String> for NodeSource {
+    fn from(source: String) -> Self {
+        Self::Text(source)
+    }
+}
+
+impl From<ast::File> for NodeSource {
+    fn from(file: ast::File) -> Self {
+        Self::File(Box::new(file))
+    }
+}
+
+impl Clone for NodeSource {
+    fn clone(&self) -> Self {
+        match self {
+            Self::Text(source) => Self::Text(source.clone()),
+            // note: the variables should not be shared by the builds
+            Self::File(file) => Self::File(Box::new(DeepCopy.fold_file(*file.clone()))),
+        }
+    }
+}
+
 impl Build for TensorNode {
     type Output = Self;
+    type Source = NodeSource;
 
-    fn build(root: &NodeRoot, name: &str, source: String) -> Result<Self::Output> {
-        let file = root.parser.parse_file(&source)?;
+    fn build(root: &NodeRoot, name: &str, source: NodeSource) -> Result<Self::Output> {
+        let file = match source {
+            NodeSource::Text(source) => root.parser.parse_file(&source)?,
+            NodeSource::File(file) => *file,
+        };
 
         // test name
         if file.node.name != name {
@@ -333,8 +369,8 @@ impl IRData {
             id: 0,
             name,
             graph,
-            input: shapes_to_outs(1, input),
-            output: shapes_to_outs(1, output),
+            input: shapes_to_outs(1, input).into(),
+            output: shapes_to_outs(1, output).into(),
         }
     }
 
@@ -4,11 +4,35 @@ use std::collections::{BTreeMap, HashMap};
 use super::error::{GraphError, LinkError, Result};
 use super::graph::Variables;
 use crate::ast;
+use crate::suggest::suggest;
 
 pub use n3_program::variable::*;
 
 pub trait CloneValue {
-    fn clone_value(&self, variables: &[ast::RefVariable]) -> Self;
+    fn clone_value(&self, variables: &Bindings) -> Self;
+}
+
+/// The variables copied on instantiating a node, indexed by their older ids and names.
+#[derive(Default)]
+pub struct Bindings(HashMap<Option<u64>, HashMap<String, ast::RefVariable>>);
+
+impl Bindings {
+    pub fn bind(&mut self, var: &ast::RefVariable) {
+        let var_ref = var.borrow();
+
+        // note: the first one takes precedence
+        self.0
+            .entry(var_ref.id_old)
+            .or_default()
+            .entry(var_ref.name.clone())
+            .or_insert_with(|| var.clone());
+    }
+
+    /// Get the copy of the older variable, which is referred to 'id'.
+    pub fn get(&self, var: &ast::Variable) -> Option<&ast::RefVariable> {
+        // note: the names are not copied on lookups
+        self.0.get(&var.id)?.get(&var.name)
+    }
 }
 
 pub trait Detach {
@@ -46,25 +70,19 @@ pub(crate) trait Hint {
         Self: Sized;
 }
 
-#[allow(clippy::suspicious_operation_groupings)]
 impl CloneValue for ast::RefVariable {
-    fn clone_value(&self, variables: &[ast::RefVariable]) -> Self {
-        let this = self.borrow();
-        for var in variables {
-            let var_ref = var.borrow();
-
-            // The older 'this' variable should be referred to 'id'.
-            // The newer 'var_ref' variable should be referred to 'id_old'.
-            if var_ref.name == this.name && var_ref.id_old == this.id {
-                return var.clone();
-            }
+    fn clone_value(&self, variables: &Bindings) -> Self {
+        // The older 'self' variable should be referred to 'id'.
+        // The newer variables are indexed by 'id_old'.
+        match variables.get(&self.borrow()) {
+            Some(var) => var.clone(),
+            None => self.clone(),
         }
-        self.clone()
     }
 }
 
 impl CloneValue for ast::Value {
-    fn clone_value(&self, variables: &[ast::RefVariable]) -> Self {
+    fn clone_value(&self, variables: &Bindings) -> Self {
         match self {
             Self::Variable(value) => Self::Variable(value.clone_value(variables)),
             Self::Expr(value) => Self::Expr(value.clone_value(variables).into()),
@@ -76,7 +94,7 @@ impl CloneValue for ast::Value {
 }
 
 impl CloneValue for ast::Expr {
-    fn clone_value(&self, variables: &[ast::RefVariable]) -> Self {
+    fn clone_value(&self, variables: &Bindings) -> Self {
         Self {
             op: self.op,
             lhs: self.lhs.clone_value(variables),
@@ -86,13 +104,13 @@ impl CloneValue for ast::Expr {
 }
 
 impl CloneValue for ast::Shapes {
-    fn clone_value(&self, variables: &[ast::RefVariable]) -> Self {
+    fn clone_value(&self, variables: &Bindings) -> Self {
         Self(self.0.clone_value(variables))
     }
 }
 
 impl CloneValue for ast::Shape {
-    fn clone_value(&self, variables: &[ast::RefVariable]) -> Self {
+    fn clone_value(&self, variables: &Bindings) -> Self {
         Self(self.0.clone_value(variables))
     }
 }
@@ -102,7 +120,7 @@ where
     K: Clone + Ord,
     V: CloneValue,
 {
-    fn clone_value(&self, variables: &[ast::RefVariable]) -> Self {
+    fn clone_value(&self, variables: &Bindings) -> Self {
         self.iter()
             .map(|(k, v)| (k.clone(), v.clone_value(variables)))
             .collect()
@@ -113,7 +131,7 @@ impl<T> CloneValue for Vec<T>
 where
     T: CloneValue,
 {
-    fn clone_value(&self, variables: &[ast::RefVariable]) -> Self {
+    fn clone_value(&self, variables: &Bindings) -> Self {
         self.iter().map(|x| x.clone_value(variables)).collect()
     }
 }
@@ -122,7 +140,7 @@ impl<T> CloneValue for RefCell<T>
 where
     T: CloneValue,
 {
-    fn clone_value(&self, variables: &[ast::RefVariable]) -> Self {
+    fn clone_value(&self, variables: &Bindings) -> Self {
         Self::new(self.borrow().clone_value(variables))
     }
 }
@@ -131,12 +149,13 @@ impl<T> CloneValue for Option<T>
 where
     T: CloneValue,
 {
-    fn clone_value(&self, variables: &[ast::RefVariable]) -> Self {
+    fn clone_value(&self, variables: &Bindings) -> Self {
         self.as_ref().map(|x| x.clone_value(variables))
     }
 }
 
 impl Detach for ast::RefVariable {
+    /// Copy the variable into the graph of 'id', leaving its value to be rebound.
     fn detach(&self, id: u64) -> Self {
         let this = self.borrow();
         let cloned = ast::Variable {
@@ -146,7 +165,8 @@ impl Detach for ast::RefVariable {
             shortcut: this.shortcut.clone(),
             description: this.description.clone(),
             ty: this.ty.clone(),
-            value: this.value.clone(),
+            value: None,
+            range: this.range.clone(),
         };
         cloned.into()
     }
@@ -185,6 +205,15 @@ where
     }
 }
 
+impl<K, V> Estimable for ast::IndexMap<K, V>
+where
+    V: Estimable,
+{
+    fn is_estimable(&self) -> bool {
+        self.values().all(|x| x.is_estimable())
+    }
+}
+
 impl<T> Estimable for Vec<T>
 where
     T: Estimable,
@@ -344,7 +373,7 @@ impl Hint for ast::RefVariable {
             // hint in-place
             Some(output) => {
                 let mut output_ref = output.borrow_mut();
-                if output_ref.ty == Some(ast::LetType::Dim) && is_root {
+                if output_ref.is_dim() && is_root {
                     output_ref.value = Some(
                         ast::OutDim {
                             out: out.clone(),
@@ -357,7 +386,7 @@ impl Hint for ast::RefVariable {
             }
             None => GraphError::NoSuchVariable {
                 name: name.clone(),
-                candidates: shortcuts.keys().cloned().collect(),
+                candidates: suggest(name, shortcuts.keys()),
             }
             .into(),
         }
@@ -472,9 +501,28 @@ impl Link for ast::Shapes {
                         for (last_dim, new_dim) in last_output.0.iter().zip(new_input.0.iter()) {
                             if !last_dim.is_hint() {
                                 // replace
-                                if new_dim.is_hint() {
-                                    let new_dim = new_dim.get_hint().unwrap();
-                                    new_dim.borrow_mut().value = Some(last_dim.clone());
+                                if let Some(new_dim) =
+                                    new_dim.get_hint().filter(|_| new_dim.is_hint())
+                                {
+                                    // the dynamic dims are asserted on runtime
+                                    let mut new_dim = new_dim.borrow_mut();
+                                    if new_dim.ty != Some(ast::LetType::DynamicDim) {
+                                        new_dim.value = Some(last_dim.clone());
+                                    }
+                                }
+                                // solve the unknown dim
+                                else if let Some((var, value)) = new_dim.solve(last_dim) {
+                                    match value {
+                                        Some(value) => var.borrow_mut().value = Some(value),
+                                        None => {
+                                            return LinkError::UnsolvableDim {
+                                                name: var.borrow().name.clone(),
+                                                expected: last_dim.clone(),
+                                                given: new_dim.clone(),
+                                            }
+                                            .into()
+                                        }
+                                    }
                                 }
                                 // test value
                                 else {
@@ -518,8 +566,8 @@ where
 pub fn assert_equal(last_dim: ast::Value, new_dim: ast::Value) -> Result<()> {
     if last_dim != new_dim {
         LinkError::MismatchedDim {
-            expected: last_dim,
-            given: new_dim,
+            expected: last_dim.normalize().unwrap_or(last_dim),
+            given: new_dim.normalize().unwrap_or(new_dim),
         }
         .into()
     } else {
@@ -527,6 +575,22 @@ pub fn assert_equal(last_dim: ast::Value, new_dim: ast::Value) -> Result<()> {
     }
 }
 
+pub fn assert_in_range(var: &ast::Variable) -> Result<()> {
+    if let (Some(range), Some(value)) = (&var.range, &var.value) {
+        // the unresolved values are checked again when the graph is built
+        let value = value.build();
+        if range.contains(&value) == Some(false) {
+            return GraphError::OutOfRange {
+                name: var.name.clone(),
+                value: Box::new(value),
+                range: Box::new(range.clone()),
+            }
+            .into();
+        }
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::super::*;
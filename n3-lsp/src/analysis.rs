@@ -0,0 +1,848 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write;
+use std::fs;
+use std::ops::Deref;
+
+use lsp_types::{
+    CompletionItem, CompletionItemKind, Diagnostic, DiagnosticSeverity, Documentation, Location,
+    NumberOrString, Position, Range, TextDocumentContentChangeEvent, Url,
+};
+
+use n3_builder::error::{BuildError, GraphCallError, GraphError, TensorNodeError};
+use n3_builder::lint::{Level, Linter};
+use n3_builder::{
+    ast, did_you_mean, dirs, Document, Error, ExecRoot, ExecRootConfig, GlobalVars, NodeInspection,
+    NodeRoot, Parser, TextEdit,
+};
+
+use crate::text::{self, Span};
+
+/// The open documents, which are analyzed with the builder.
+///
+/// The built nodes are kept in the root, and only the nodes which depend on
+/// the edited documents are built again.
+pub struct Analysis {
+    root: Root,
+    parser: Parser,
+    docs: BTreeMap<Url, Document>,
+    /// The names of the nodes, which are added into the root
+    names: BTreeMap<Url, String>,
+}
+
+/// The root of the user nodes if exists, or of the standard nodes.
+enum Root {
+    Exec(ExecRoot),
+    Node(NodeRoot),
+}
+
+impl Deref for Root {
+    type Target = NodeRoot;
+
+    fn deref(&self) -> &Self::Target {
+        match self {
+            Self::Exec(root) => root,
+            Self::Node(root) => root,
+        }
+    }
+}
+
+impl Analysis {
+    pub fn new(env: GlobalVars) -> Self {
+        let config = ExecRootConfig {
+            create_root_dir: Some(false),
+        };
+        let root = match ExecRoot::try_new(env.clone(), config) {
+            Ok(root) => Root::Exec(root),
+            Err(_) => {
+                let n3_source_root = env.get_string(dirs::N3_SOURCE_ROOT).ok();
+                Root::Node(NodeRoot::new(n3_source_root.as_deref()))
+            }
+        };
+
+        Self {
+            root,
+            parser: Parser::default(),
+            docs: Default::default(),
+            names: Default::default(),
+        }
+    }
+
+    /// Open the document.
+    /// Returns the open documents which should be analyzed again.
+    pub fn open(&mut self, uri: Url, source: String) -> Vec<Url> {
+        self.docs.insert(uri.clone(), Document::new(source));
+        self.update(&uri)
+    }
+
+    /// Apply the changes to the document.
+    /// Returns the open documents which should be analyzed again.
+    pub fn change(&mut self, uri: &Url, changes: Vec<TextDocumentContentChangeEvent>) -> Vec<Url> {
+        let doc = match self.docs.get_mut(uri) {
+            Some(doc) => doc,
+            None => return vec![],
+        };
+        for change in changes {
+            match change.range {
+                Some(range) => {
+                    let start = from_position(doc.text(), range.start);
+                    let end = from_position(doc.text(), range.end);
+                    doc.apply_edits(&[TextEdit {
+                        start,
+                        end,
+                        text: change.text,
+                    }]);
+                }
+                None => {
+                    doc.set_text(change.text);
+                }
+            }
+        }
+        self.update(uri)
+    }
+
+    /// Close the document, so that the node is loaded from the file again.
+    /// Returns the open documents which should be analyzed again.
+    pub fn close(&mut self, uri: &Url) -> Vec<Url> {
+        self.docs.remove(uri);
+        match self.names.remove(uri) {
+            Some(name) => {
+                let invalidated = self.root.remove_source(&name);
+                self.find_docs(&invalidated)
+            }
+            None => vec![],
+        }
+    }
+
+    /// Add the document into the root, and invalidate the dependent nodes.
+    fn update(&mut self, uri: &Url) -> Vec<Url> {
+        let doc = &self.docs[uri];
+
+        // note: the last valid source is kept while the document is not parsable
+        let mut invalidated = BTreeSet::new();
+        if let Ok(file) = doc.file() {
+            let name = file.node.name.clone();
+            match self.names.insert(uri.clone(), name.clone()) {
+                Some(old) if old != name => invalidated.extend(self.root.remove_source(&old)),
+                _ => {}
+            }
+            invalidated.extend(self.root.add_file(name, file));
+        }
+
+        let mut docs = self.find_docs(&invalidated);
+        if !docs.contains(uri) {
+            docs.insert(0, uri.clone());
+        }
+        docs
+    }
+
+    /// Find the open documents of the nodes.
+    fn find_docs(&self, names: &BTreeSet<String>) -> Vec<Url> {
+        self.names
+            .iter()
+            .filter(|(uri, name)| names.contains(*name) && self.docs.contains_key(uri))
+            .map(|(uri, _)| uri.clone())
+            .collect()
+    }
+
+    fn get_doc(&self, uri: &Url) -> Option<&str> {
+        self.docs.get(uri).map(|x| x.text())
+    }
+
+    /// Find the source of the node, from the open documents or the files.
+    fn find_source(&self, root: &NodeRoot, name: &str) -> Option<(Url, String)> {
+        for (uri, doc) in &self.docs {
+            if text::find_node_defs(doc.text())
+                .first()
+                .map(|(x, _)| x == name)
+                .unwrap_or_default()
+            {
+                return Some((uri.clone(), doc.text().to_string()));
+            }
+        }
+
+        let path = root.get_source_path(name)?;
+        let source = fs::read_to_string(&path).ok()?;
+        let uri = Url::from_file_path(fs::canonicalize(&path).ok()?).ok()?;
+        Some((uri, source))
+    }
+
+    pub fn diagnostics(&self, uri: &Url) -> Vec<Diagnostic> {
+        let doc = match self.docs.get(uri) {
+            Some(doc) => doc,
+            None => return vec![],
+        };
+        let source = doc.text();
+
+        let file = match doc.file() {
+            Ok(file) => file,
+            Err(e) => {
+                let line = e.location.row().saturating_sub(1);
+                let column = text::to_utf16(source, line, e.location.column().saturating_sub(1));
+                let position = Position::new(line as u32, column as u32);
+                return vec![Diagnostic {
+                    code: Some(NumberOrString::String(e.code().to_string())),
+                    ..new_diagnostic(Range::new(position, position), e.error.to_string())
+                }];
+            }
+        };
+
+        match self.root.inspect(&file.node.name) {
+            Ok(_) => self.lints(source),
+            Err(e) => {
+                let range = get_error_subject(&e)
+                    .and_then(|x| find_word(source, x))
+                    .map(|x| to_range(source, x))
+                    .unwrap_or_default();
+                let mut message = format!("{:?}", e);
+                if let Some(hint) = get_error_candidates(&e).and_then(did_you_mean) {
+                    message = format!("{}\n{}", message, hint);
+                }
+                vec![Diagnostic {
+                    code: Some(NumberOrString::String(e.code().to_string())),
+                    ..new_diagnostic(range, message)
+                }]
+            }
+        }
+    }
+
+    fn lints(&self, source: &str) -> Vec<Diagnostic> {
+        let messages = Linter::default()
+            .check(&self.root, source)
+            .unwrap_or_default();
+        messages
+            .into_iter()
+            .map(|x| {
+                let range = x
+                    .span
+                    .map(|x| {
+                        let span = Span {
+                            line: x.line,
+                            start: x.start,
+                            end: x.end,
+                        };
+                        to_range(source, span)
+                    })
+                    .unwrap_or_default();
+                let severity = match x.level {
+                    Level::Deny => DiagnosticSeverity::ERROR,
+                    _ => DiagnosticSeverity::WARNING,
+                };
+                Diagnostic {
+                    severity: Some(severity),
+                    code: Some(NumberOrString::String(x.lint.to_string())),
+                    ..new_diagnostic(range, x.message)
+                }
+            })
+            .collect()
+    }
+
+    pub fn hover(&self, uri: &Url, position: Position) -> Option<(String, Range)> {
+        let source = self.get_doc(uri)?;
+        let n3_builder::Position { line, column } = from_position(source, position);
+        let root = &self.root;
+
+        let mut contents = String::new();
+        let mut range = None;
+
+        for (symbol, span) in text::symbols_at(source, line, column) {
+            let found = if let Some(owner) = text::find_args_owner(source, line, column) {
+                self.inspect(root, source, &owner.name)
+                    .and_then(|node| find_variable(&node, &symbol))
+                    .map(|var| fmt_variable(&mut contents, &var.borrow(), None))
+            } else if let Some((_, node_source)) = self.find_node(root, uri, &symbol) {
+                self.parser
+                    .parse_file(&node_source)
+                    .ok()
+                    .map(|file| fmt_node(&mut contents, &symbol, &file))
+            } else if let Some(def) = text::find_let_def(source, &symbol, line) {
+                let decl = source.lines().nth(def.span.line).unwrap_or_default().trim();
+                let var = self
+                    .inspect(root, source, &get_node_name(source, def.span.line)?)
+                    .and_then(|node| find_variable(&node, &def.name));
+                match var {
+                    Some(var) => fmt_variable(&mut contents, &var.borrow(), Some(decl)),
+                    None => fmt_declaration(&mut contents, decl),
+                }
+                Some(())
+            } else {
+                None
+            };
+
+            if found.is_some() {
+                range = Some(to_range(source, span));
+                break;
+            }
+        }
+
+        // the inferred shapes of the graph line
+        if let Some(id) = text::graph_line_id(source, line) {
+            let node = self.inspect(root, source, &get_node_name(source, line)?);
+            if let Some(node) = node {
+                fmt_graph_line(&mut contents, &node, id);
+            }
+        }
+
+        if contents.is_empty() {
+            None
+        } else {
+            let range = range.unwrap_or_else(|| {
+                let end = source
+                    .lines()
+                    .nth(line)
+                    .unwrap_or_default()
+                    .encode_utf16()
+                    .count();
+                Range::new(
+                    Position::new(line as u32, 0),
+                    Position::new(line as u32, end as u32),
+                )
+            });
+            Some((contents, range))
+        }
+    }
+
+    pub fn definition(&self, uri: &Url, position: Position) -> Option<Location> {
+        let source = self.get_doc(uri)?;
+        let n3_builder::Position { line, column } = from_position(source, position);
+        let root = &self.root;
+
+        for (symbol, _) in text::symbols_at(source, line, column) {
+            // the arguments of the other node
+            if let Some(owner) = text::find_args_owner(source, line, column) {
+                if let Some((uri, source)) = self.find_node(root, uri, &owner.name) {
+                    let owner_line = text::find_node_defs(&source)
+                        .into_iter()
+                        .find(|(x, _)| x == &owner.name)
+                        .map(|(_, span)| span.line)
+                        .unwrap_or_default();
+                    if let Some(def) = text::find_let_defs(&source).into_iter().find(|x| {
+                        x.span.line > owner_line
+                            && (x.name == symbol || x.shortcut.as_deref() == Some(&symbol))
+                    }) {
+                        return Some(Location::new(uri, to_range(&source, def.span)));
+                    }
+                }
+                continue;
+            }
+
+            // the child nodes
+            if let Some((_, span)) = text::find_node_defs(source)
+                .into_iter()
+                .find(|(x, _)| x == &symbol)
+            {
+                return Some(Location::new(uri.clone(), to_range(source, span)));
+            }
+
+            // the user and std nodes
+            if let Some((uri, source)) = self.find_source(root, &symbol) {
+                if let Some((_, span)) = text::find_node_defs(&source)
+                    .into_iter()
+                    .find(|(x, _)| x == &symbol)
+                {
+                    return Some(Location::new(uri, to_range(&source, span)));
+                }
+            }
+
+            // the variables
+            if let Some(def) = text::find_let_def(source, &symbol, line) {
+                return Some(Location::new(uri.clone(), to_range(source, def.span)));
+            }
+        }
+        None
+    }
+
+    pub fn completion(&self, uri: &Url, position: Position) -> Vec<CompletionItem> {
+        let source = match self.get_doc(uri) {
+            Some(source) => source,
+            None => return vec![],
+        };
+        let n3_builder::Position { line, column } = from_position(source, position);
+        let root = &self.root;
+
+        // the arguments of the node
+        if let Some(owner) = text::find_args_owner(source, line, column) {
+            return match self.inspect(root, source, &owner.name) {
+                Some(node) => node
+                    .variables
+                    .iter()
+                    .map(|var| {
+                        let var = var.borrow();
+                        let label = match &var.shortcut {
+                            Some(shortcut) if owner.shortcut => shortcut.clone(),
+                            _ => var.name.clone(),
+                        };
+                        CompletionItem {
+                            label,
+                            kind: Some(CompletionItemKind::FIELD),
+                            detail: var.ty.as_ref().map(|ty| format!("{}: {:?}", &var.name, ty)),
+                            documentation: var.description.clone().map(Documentation::String),
+                            ..Default::default()
+                        }
+                    })
+                    .collect(),
+                None => vec![],
+            };
+        }
+
+        // the node names
+        let mut names: BTreeSet<_> = root.get_names();
+        names.extend(text::find_node_defs(source).into_iter().map(|(x, _)| x));
+        names.insert("Input".to_string());
+        names
+            .into_iter()
+            .map(|name| CompletionItem {
+                label: name,
+                kind: Some(CompletionItemKind::CLASS),
+                ..Default::default()
+            })
+            .collect()
+    }
+
+    /// Find the source of the node, including the child nodes of the document.
+    fn find_node(&self, root: &NodeRoot, uri: &Url, name: &str) -> Option<(Url, String)> {
+        let source = self.get_doc(uri)?;
+        if text::find_node_defs(source).iter().any(|(x, _)| x == name) {
+            return Some((uri.clone(), source.to_string()));
+        }
+        self.find_source(root, name)
+    }
+
+    /// Build the node, including the child nodes of the document.
+    fn inspect(&self, root: &NodeRoot, source: &str, name: &str) -> Option<NodeInspection> {
+        if let Ok(node) = root.inspect(name) {
+            return Some(node);
+        }
+
+        // the child nodes cannot be built alone, so they are parsed only
+        let file = self.parser.parse_file(source).ok()?;
+        let node = find_child(&file.node, name)?;
+        Some(NodeInspection {
+            name: name.to_string(),
+            variables: node
+                .graph
+                .values()
+                .map(|x| {
+                    ast::Variable {
+                        name: x.name.clone(),
+                        shortcut: x.shortcut.clone(),
+                        description: x.description.clone(),
+                        ty: Some(x.ty.clone()),
+                        value: x.value.clone(),
+                        range: x.range.clone(),
+                        ..Default::default()
+                    }
+                    .into()
+                })
+                .collect(),
+            graph: vec![],
+        })
+    }
+}
+
+fn new_diagnostic(range: Range, message: String) -> Diagnostic {
+    Diagnostic {
+        severity: Some(DiagnosticSeverity::ERROR),
+        source: Some("n3".to_string()),
+        ..Diagnostic::new_simple(range, message)
+    }
+}
+
+/// Convert the UTF-16 position of LSP into the character position of the source.
+fn from_position(source: &str, position: Position) -> n3_builder::Position {
+    let line = position.line as usize;
+    n3_builder::Position {
+        line,
+        column: text::from_utf16(source, line, position.character as usize),
+    }
+}
+
+/// Convert the character span of the source into the UTF-16 range of LSP.
+fn to_range(source: &str, span: Span) -> Range {
+    let line = span.line;
+    Range::new(
+        Position::new(line as u32, text::to_utf16(source, line, span.start) as u32),
+        Position::new(line as u32, text::to_utf16(source, line, span.end) as u32),
+    )
+}
+
+/// Get the name which the error is caused by.
+fn get_error_subject(error: &Error) -> Option<&str> {
+    match error {
+        Error::BuildError(BuildError::TensorNodeError(e)) => match e {
+            TensorNodeError::NoSuchNode { name, .. } => Some(name),
+            TensorNodeError::MismatchedName { given, .. } => Some(given),
+            _ => None,
+        },
+        Error::BuildError(BuildError::GraphError(e)) => match e {
+            GraphError::NoSuchVariable { name, .. }
+            | GraphError::UnexpectedNodeVariable { name }
+            | GraphError::UnparsableString { name, .. }
+            | GraphError::DuplicatedVariable { name }
+            | GraphError::EmptyValue { name, .. }
+            | GraphError::MismatchedType { name, .. }
+            | GraphError::OutOfRange { name, .. } => Some(name),
+            GraphError::CycledVariables { names } => names.iter().next().map(|x| x.as_str()),
+        },
+        Error::BuildError(BuildError::GraphCallError(e)) => match e {
+            GraphCallError::MismatchedName { given, .. } => Some(given),
+            GraphCallError::GenericShape { name } => Some(name),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Get the names which are similar to the subject of the error.
+fn get_error_candidates(error: &Error) -> Option<&BTreeSet<String>> {
+    match error {
+        Error::BuildError(BuildError::TensorNodeError(TensorNodeError::NoSuchNode {
+            candidates,
+            ..
+        }))
+        | Error::BuildError(BuildError::TensorNodeError(TensorNodeError::NoSuchCost {
+            candidates,
+            ..
+        }))
+        | Error::BuildError(BuildError::GraphError(GraphError::NoSuchVariable {
+            candidates,
+            ..
+        }))
+        | Error::BuildError(BuildError::GraphCallError(GraphCallError::MismatchedArgs {
+            candidates,
+            ..
+        })) => Some(candidates),
+        _ => None,
+    }
+}
+
+/// Find the first occurrence of the word.
+fn find_word(source: &str, word: &str) -> Option<Span> {
+    source.lines().enumerate().find_map(|(line, text)| {
+        text.match_indices(word)
+            .find(|&(at, _)| {
+                let is_boundary = |c: Option<char>| match c {
+                    Some(c) => !(c.is_alphanumeric() || c == '_'),
+                    None => true,
+                };
+                is_boundary(text[..at].chars().last())
+                    && is_boundary(text[at + word.len()..].chars().next())
+            })
+            .map(|(at, _)| {
+                let start = text[..at].chars().count();
+                Span {
+                    line,
+                    start,
+                    end: start + word.chars().count(),
+                }
+            })
+    })
+}
+
+/// Get the name of the node, which owns the line.
+fn get_node_name(source: &str, line: usize) -> Option<String> {
+    let lines: Vec<_> = source.lines().collect();
+    let indent = text::indent_of(lines.get(line)?);
+
+    text::find_node_defs(source)
+        .into_iter()
+        .rev()
+        .filter(|(_, span)| span.line <= line)
+        .find(|(_, span)| span.line == line || text::indent_of(lines[span.line]) < indent)
+        .map(|(x, _)| x)
+}
+
+fn find_child<'a>(node: &'a ast::Node, name: &str) -> Option<&'a ast::Node> {
+    if node.name == name {
+        return Some(node);
+    }
+    node.children.values().find_map(|x| find_child(x, name))
+}
+
+fn find_variable(node: &NodeInspection, name: &str) -> Option<ast::RefVariable> {
+    node.variables
+        .iter()
+        .find(|x| {
+            let x = x.borrow();
+            x.name == name || x.shortcut.as_deref() == Some(name)
+        })
+        .cloned()
+}
+
+fn fmt_declaration(buf: &mut String, decl: &str) {
+    writeln!(buf, "```n3\n{}\n```", decl).unwrap();
+}
+
+fn fmt_variable(buf: &mut String, var: &ast::Variable, decl: Option<&str>) {
+    match decl {
+        Some(decl) => fmt_declaration(buf, decl),
+        None => {
+            let shortcut = var
+                .shortcut
+                .as_ref()
+                .map(|x| format!("{}: ", x))
+                .unwrap_or_default();
+            fmt_declaration(buf, &format!("let {}{}", shortcut, &var.name));
+        }
+    }
+    if let Some(description) = &var.description {
+        writeln!(buf, "{}\n", description).unwrap();
+    }
+    if let Some(ty) = &var.ty {
+        writeln!(buf, "* type: `{:?}`", ty).unwrap();
+    }
+    if let Some(value) = &var.value {
+        let value = value.normalize().unwrap_or_else(|| value.clone());
+        writeln!(buf, "* value: `{:?}`", value).unwrap();
+    }
+    if let Some(range) = &var.range {
+        writeln!(buf, "* range: `{:?}`", range).unwrap();
+    }
+}
+
+fn fmt_node(buf: &mut String, name: &str, file: &ast::File) {
+    let node = find_child(&file.node, name).unwrap_or(&file.node);
+
+    fmt_declaration(buf, &format!("{:?}node {}", node.ty, &node.name));
+    if let Some(description) = &node.description {
+        writeln!(buf, "{}\n", description).unwrap();
+    }
+    for var in node.graph.values() {
+        let shortcut = var
+            .shortcut
+            .as_ref()
+            .map(|x| format!("{}: ", x))
+            .unwrap_or_default();
+        writeln!(buf, "* `{}{}`: `{:?}`", shortcut, &var.name, &var.ty).unwrap();
+    }
+}
+
+fn fmt_graph_line(buf: &mut String, node: &NodeInspection, id: u64) {
+    let fmt_shapes = |shapes: Option<&ast::Shapes>| match shapes {
+        Some(shapes) => shapes
+            .0
+            .borrow()
+            .iter()
+            .map(|(name, shape)| match shape {
+                Some(shape) => {
+                    let dims: Vec<_> = shape
+                        .0
+                        .iter()
+                        .map(|x| format!("{:?}", x.normalize().unwrap_or_else(|| x.clone())))
+                        .collect();
+                    format!("{}: [{}]", name, dims.join(", "))
+                }
+                None => format!("{}: *", name),
+            })
+            .collect::<Vec<_>>()
+            .join(", "),
+        None => "*".to_string(),
+    };
+
+    let mut is_first = true;
+    for call in node.graph.iter().filter(|x| x.id == id) {
+        if is_first {
+            if !buf.is_empty() {
+                writeln!(buf, "\n---\n").unwrap();
+            }
+            is_first = false;
+        }
+        writeln!(
+            buf,
+            "* `{}`: `{}` → `{}`",
+            &call.name,
+            fmt_shapes(call.input.as_ref()),
+            fmt_shapes(call.output.as_ref()),
+        )
+        .unwrap();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = "node MyNode:
+    let K: kernel size = int 5
+
+    with Conv2D:
+        set kernel size = K
+
+    node MyConv:
+        1. Conv2D(S=2)
+        2. Relu
+
+    0. Input        = 3, 32, 32
+    1. MyConv       = 16, 16, 16
+    2. ToLinear
+    3. Linear       = 10
+";
+
+    fn new_analysis() -> (Analysis, Url) {
+        let env = GlobalVars::default();
+        env.set(dirs::N3_ROOT, "../n3-builder/tests/data/").unwrap();
+        env.set(dirs::N3_SOURCE_ROOT, "../n3-torch/ffi/python/n3")
+            .unwrap();
+
+        let mut analysis = Analysis::new(env);
+        let uri = Url::parse("file:///tmp/my_node.n3").unwrap();
+        analysis.open(uri.clone(), SOURCE.to_string());
+        (analysis, uri)
+    }
+
+    #[test]
+    fn test_diagnostics() {
+        let (mut analysis, uri) = new_analysis();
+        assert_eq!(analysis.diagnostics(&uri), []);
+
+        // parse error
+        analysis.open(uri.clone(), "node MyNode\n".to_string());
+        let diagnostics = analysis.diagnostics(&uri);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].range.start.line, 0);
+
+        // build error
+        let source = SOURCE.replace("3. Linear", "3. Lineer");
+        analysis.open(uri.clone(), source);
+        let diagnostics = analysis.diagnostics(&uri);
+        assert_eq!(diagnostics.len(), 1);
+        let range = Range::new(Position::new(13, 7), Position::new(13, 13));
+        assert_eq!(diagnostics[0].range, range);
+        assert!(diagnostics[0].message.ends_with("did you mean `Linear`?"));
+        let code = NumberOrString::String("N3-E0101".to_string());
+        assert_eq!(diagnostics[0].code, Some(code));
+
+        // lint warning
+        let source = SOURCE.replace("int 5\n", "int 5\n    let P: padding = int 1\n");
+        analysis.open(uri.clone(), source);
+        let diagnostics = analysis.diagnostics(&uri);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].severity, Some(DiagnosticSeverity::WARNING));
+        let range = Range::new(Position::new(2, 11), Position::new(2, 18));
+        assert_eq!(diagnostics[0].range, range);
+    }
+
+    #[test]
+    fn test_change() {
+        let (mut analysis, uri) = new_analysis();
+
+        let outer = Url::parse("file:///tmp/my_outer.n3").unwrap();
+        let source = "node MyOuter:\n    0. Input = 3, 32, 32\n    1. MyNode\n";
+        assert_eq!(
+            analysis.open(outer.clone(), source.to_string()),
+            [outer.clone()]
+        );
+        assert_eq!(analysis.diagnostics(&outer), []);
+
+        // the dependent documents are analyzed again
+        let change = TextDocumentContentChangeEvent {
+            range: Some(Range::new(Position::new(13, 7), Position::new(13, 13))),
+            range_length: None,
+            text: "Lineer".to_string(),
+        };
+        let uris = analysis.change(&uri, vec![change]);
+        assert_eq!(uris, [uri.clone(), outer.clone()]);
+        assert_eq!(
+            analysis.get_doc(&uri).unwrap(),
+            SOURCE.replace("3. Linear", "3. Lineer")
+        );
+        assert_eq!(analysis.diagnostics(&uri).len(), 1);
+        assert_eq!(analysis.diagnostics(&outer).len(), 1);
+
+        // the node is loaded from the file after closing
+        assert_eq!(analysis.close(&uri), [outer.clone()]);
+        assert_eq!(analysis.diagnostics(&outer).len(), 1);
+    }
+
+    #[test]
+    fn test_change_utf16() {
+        let (mut analysis, uri) = new_analysis();
+        let source = SOURCE.replace("int 5\n", "int 5 # 😀 x\n");
+        analysis.open(uri.clone(), source.clone());
+
+        // the emoji takes 2 columns, as a surrogate pair
+        let change = TextDocumentContentChangeEvent {
+            range: Some(Range::new(Position::new(1, 35), Position::new(1, 35))),
+            range_length: None,
+            text: "!".to_string(),
+        };
+        analysis.change(&uri, vec![change]);
+        assert_eq!(analysis.get_doc(&uri).unwrap(), source.replace("😀", "😀!"));
+        assert_eq!(analysis.diagnostics(&uri), []);
+    }
+
+    #[test]
+    fn test_hover() {
+        let (analysis, uri) = new_analysis();
+
+        // variables
+        let (contents, _) = analysis.hover(&uri, Position::new(1, 16)).unwrap();
+        assert!(contents.contains("let K: kernel size = int 5"));
+        assert!(contents.contains("* value: `5`"));
+
+        // arguments
+        let (contents, range) = analysis.hover(&uri, Position::new(4, 16)).unwrap();
+        assert!(contents.contains("let K: kernel size"));
+        assert_eq!(range.start, Position::new(4, 12));
+
+        // nodes and shapes
+        let (contents, _) = analysis.hover(&uri, Position::new(13, 8)).unwrap();
+        assert!(contents.contains("extern node Linear"));
+        assert!(contents.contains("* `Linear`: `x: [4096]` → `x: [10]`"));
+    }
+
+    #[test]
+    fn test_definition() {
+        let (analysis, uri) = new_analysis();
+
+        // child nodes
+        let location = analysis.definition(&uri, Position::new(11, 8)).unwrap();
+        assert_eq!(location.uri, uri);
+        assert_eq!(location.range.start, Position::new(6, 9));
+
+        // std nodes
+        let location = analysis.definition(&uri, Position::new(13, 8)).unwrap();
+        assert!(location.uri.path().ends_with("linear.n3"));
+
+        // variables
+        let location = analysis.definition(&uri, Position::new(4, 26)).unwrap();
+        assert_eq!(location.range.start, Position::new(1, 11));
+
+        // the arguments of the std nodes
+        let location = analysis.definition(&uri, Position::new(4, 16)).unwrap();
+        assert!(location.uri.path().ends_with("conv2d.n3"));
+        assert_eq!(location.range.start, Position::new(1, 11));
+
+        let location = analysis.definition(&uri, Position::new(7, 18)).unwrap();
+        assert!(location.uri.path().ends_with("conv2d.n3"));
+        assert_eq!(location.range.start, Position::new(4, 11));
+    }
+
+    #[test]
+    fn test_completion() {
+        let (analysis, uri) = new_analysis();
+
+        let names: Vec<_> = analysis
+            .completion(&uri, Position::new(13, 5))
+            .into_iter()
+            .map(|x| x.label)
+            .collect();
+        assert!(names.contains(&"Conv2D".to_string()));
+        assert!(names.contains(&"MyConv".to_string()));
+
+        let args: Vec<_> = analysis
+            .completion(&uri, Position::new(4, 12))
+            .into_iter()
+            .map(|x| x.label)
+            .collect();
+        assert!(args.contains(&"kernel size".to_string()));
+        assert!(args.contains(&"stride".to_string()));
+
+        // the shortcuts are given to the calls
+        let args: Vec<_> = analysis
+            .completion(&uri, Position::new(7, 18))
+            .into_iter()
+            .map(|x| x.label)
+            .collect();
+        assert!(args.contains(&"S".to_string()));
+    }
+}
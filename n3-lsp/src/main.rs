@@ -0,0 +1,261 @@
+mod analysis;
+mod text;
+
+use std::error::Error;
+
+use lsp_server::{Connection, ErrorCode, ExtractError, Message, Notification, Request, Response};
+use lsp_types::notification::{
+    DidChangeTextDocument, DidCloseTextDocument, DidOpenTextDocument,
+    Notification as NotificationTrait, PublishDiagnostics,
+};
+use lsp_types::request::{Completion, GotoDefinition, HoverRequest, Request as RequestTrait};
+use lsp_types::{
+    CompletionOptions, CompletionResponse, GotoDefinitionResponse, Hover, HoverContents,
+    HoverProviderCapability, MarkupContent, MarkupKind, OneOf, PublishDiagnosticsParams,
+    ServerCapabilities, TextDocumentSyncCapability, TextDocumentSyncKind, Url,
+};
+use n3_builder::GlobalVars;
+
+use self::analysis::Analysis;
+
+type Result<T> = std::result::Result<T, Box<dyn Error + Sync + Send>>;
+
+fn main() -> Result<()> {
+    // note: stdout is reserved for the protocol
+    let (connection, io_threads) = Connection::stdio();
+
+    let capabilities = ServerCapabilities {
+        text_document_sync: Some(TextDocumentSyncCapability::Kind(
+            TextDocumentSyncKind::INCREMENTAL,
+        )),
+        hover_provider: Some(HoverProviderCapability::Simple(true)),
+        definition_provider: Some(OneOf::Left(true)),
+        completion_provider: Some(CompletionOptions {
+            trigger_characters: Some(vec!["(".to_string(), ",".to_string()]),
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    connection.initialize(serde_json::to_value(&capabilities)?)?;
+
+    Server {
+        connection,
+        analysis: Analysis::new(GlobalVars::default()),
+    }
+    .run()?;
+
+    io_threads.join()?;
+    Ok(())
+}
+
+struct Server {
+    connection: Connection,
+    analysis: Analysis,
+}
+
+impl Server {
+    fn run(mut self) -> Result<()> {
+        while let Ok(msg) = self.connection.receiver.recv() {
+            match msg {
+                Message::Request(req) => {
+                    if self.connection.handle_shutdown(&req)? {
+                        break;
+                    }
+                    let response = self.handle_request(req);
+                    self.connection.sender.send(response.into())?;
+                }
+                Message::Notification(not) => self.handle_notification(not)?,
+                Message::Response(_) => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn handle_request(&self, req: Request) -> Response {
+        match req.method.as_str() {
+            HoverRequest::METHOD => respond::<HoverRequest>(req, |params| {
+                let params = params.text_document_position_params;
+                self.analysis
+                    .hover(&params.text_document.uri, params.position)
+                    .map(|(value, range)| Hover {
+                        contents: HoverContents::Markup(MarkupContent {
+                            kind: MarkupKind::Markdown,
+                            value,
+                        }),
+                        range: Some(range),
+                    })
+            }),
+            GotoDefinition::METHOD => respond::<GotoDefinition>(req, |params| {
+                let params = params.text_document_position_params;
+                self.analysis
+                    .definition(&params.text_document.uri, params.position)
+                    .map(GotoDefinitionResponse::Scalar)
+            }),
+            Completion::METHOD => respond::<Completion>(req, |params| {
+                let params = params.text_document_position;
+                let items = self
+                    .analysis
+                    .completion(&params.text_document.uri, params.position);
+                Some(CompletionResponse::Array(items))
+            }),
+            _ => Response::new_err(
+                req.id,
+                ErrorCode::MethodNotFound as i32,
+                format!("no such method: {}", req.method),
+            ),
+        }
+    }
+
+    fn handle_notification(&mut self, not: Notification) -> Result<()> {
+        match not.method.as_str() {
+            DidOpenTextDocument::METHOD => {
+                let params = match extract::<DidOpenTextDocument>(not) {
+                    Some(params) => params,
+                    None => return Ok(()),
+                };
+                let uris = self
+                    .analysis
+                    .open(params.text_document.uri, params.text_document.text);
+                self.publish_diagnostics(uris)
+            }
+            DidChangeTextDocument::METHOD => {
+                let params = match extract::<DidChangeTextDocument>(not) {
+                    Some(params) => params,
+                    None => return Ok(()),
+                };
+                let uris = self
+                    .analysis
+                    .change(&params.text_document.uri, params.content_changes);
+                self.publish_diagnostics(uris)
+            }
+            DidCloseTextDocument::METHOD => {
+                let params = match extract::<DidCloseTextDocument>(not) {
+                    Some(params) => params,
+                    None => return Ok(()),
+                };
+                let uris = self.analysis.close(&params.text_document.uri);
+                self.publish_diagnostics(uris)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn publish_diagnostics(&self, uris: Vec<Url>) -> Result<()> {
+        for uri in uris {
+            let diagnostics = self.analysis.diagnostics(&uri);
+            let params = PublishDiagnosticsParams::new(uri, diagnostics, None);
+            let not = Notification::new(PublishDiagnostics::METHOD.to_string(), params);
+            self.connection.sender.send(not.into())?;
+        }
+        Ok(())
+    }
+}
+
+/// Parse the params of the notification, or log and skip it if malformed.
+fn extract<N>(not: Notification) -> Option<N::Params>
+where
+    N: NotificationTrait,
+{
+    match not.extract(N::METHOD) {
+        Ok(params) => Some(params),
+        Err(e) => {
+            eprintln!("skipping the malformed notification: {}", e);
+            None
+        }
+    }
+}
+
+/// Reply the result of the request, or the error if the params are malformed.
+fn respond<R>(req: Request, f: impl FnOnce(R::Params) -> R::Result) -> Response
+where
+    R: RequestTrait,
+{
+    let id = req.id.clone();
+    match req.extract(R::METHOD) {
+        Ok((id, params)) => Response::new_ok(id, f(params)),
+        Err(ExtractError::JsonError { error, .. }) => {
+            Response::new_err(id, ErrorCode::InvalidParams as i32, error.to_string())
+        }
+        Err(ExtractError::MethodMismatch(req)) => Response::new_err(
+            id,
+            ErrorCode::MethodNotFound as i32,
+            format!("unexpected method: {}", req.method),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{json, Value};
+
+    use n3_builder::dirs;
+
+    use super::*;
+
+    fn new_server() -> (Server, Connection) {
+        let env = GlobalVars::default();
+        env.set(dirs::N3_ROOT, "../n3-builder/tests/data/").unwrap();
+        env.set(dirs::N3_SOURCE_ROOT, "../n3-torch/ffi/python/n3")
+            .unwrap();
+
+        let (connection, client) = Connection::memory();
+        let server = Server {
+            connection,
+            analysis: Analysis::new(env),
+        };
+        (server, client)
+    }
+
+    #[test]
+    fn test_handle_request() {
+        let (server, _client) = new_server();
+        let request = |method: &str, params| {
+            server.handle_request(Request::new(1.into(), method.to_string(), params))
+        };
+
+        // the documents which are not open
+        let params = json!({
+            "textDocument": { "uri": "file:///tmp/my_node.n3" },
+            "position": { "line": 0, "character": 0 },
+        });
+        let response = request(HoverRequest::METHOD, params);
+        assert!(response.error.is_none());
+        assert_eq!(response.result, Some(Value::Null));
+
+        // malformed params
+        let response = request(HoverRequest::METHOD, json!({}));
+        assert_eq!(
+            response.error.unwrap().code,
+            ErrorCode::InvalidParams as i32
+        );
+
+        // unknown methods
+        let response = request("n3/unknown", Value::Null);
+        assert_eq!(
+            response.error.unwrap().code,
+            ErrorCode::MethodNotFound as i32
+        );
+    }
+
+    #[test]
+    fn test_handle_notification() {
+        let (mut server, client) = new_server();
+
+        // malformed params should be skipped
+        let not = Notification::new(DidOpenTextDocument::METHOD.to_string(), json!({}));
+        assert!(server.handle_notification(not).is_ok());
+        assert!(client.receiver.try_recv().is_err());
+
+        // the server keeps handling the next notifications
+        let params = json!({
+            "textDocument": {
+                "uri": "file:///tmp/my_node.n3",
+                "languageId": "n3",
+                "version": 1,
+                "text": "",
+            },
+        });
+        let not = Notification::new(DidOpenTextDocument::METHOD.to_string(), params);
+        assert!(server.handle_notification(not).is_ok());
+    }
+}
@@ -0,0 +1,329 @@
+//! Line-based lookups on the source.
+//! The AST has no locations, so the symbols are found with the source text.
+
+/// A span of a line, by the character indices.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Span {
+    pub line: usize,
+    pub start: usize,
+    pub end: usize,
+}
+
+/// A `let` declaration.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LetDef {
+    pub shortcut: Option<String>,
+    pub name: String,
+    pub span: Span,
+    pub indent: usize,
+}
+
+pub fn indent_of(line: &str) -> usize {
+    line.len() - line.trim_start().len()
+}
+
+fn is_symbol_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// Get the candidate symbols under the cursor, the longest first.
+///
+/// The names may contain spaces (e.g. `kernel size`), so every run of the words
+/// which contains the cursor is a candidate.
+pub fn symbols_at(source: &str, line: usize, column: usize) -> Vec<(String, Span)> {
+    let chars: Vec<char> = match source.lines().nth(line) {
+        Some(text) => text.chars().collect(),
+        None => return vec![],
+    };
+
+    // the words, which are not separated by any operator
+    let mut words = vec![];
+    let mut start = None;
+    for (at, &c) in chars.iter().chain(&[' ']).enumerate() {
+        match (start, is_symbol_char(c)) {
+            (None, true) => start = Some(at),
+            (Some(s), false) => {
+                words.push((s, at));
+                start = None;
+            }
+            _ => {}
+        }
+        if !is_symbol_char(c) && !c.is_whitespace() {
+            words.push((at, at));
+        }
+    }
+
+    let cursor = match words
+        .iter()
+        .position(|&(s, e)| s < e && s <= column && column <= e)
+    {
+        Some(cursor) => cursor,
+        None => return vec![],
+    };
+
+    let mut first = cursor;
+    while first > 0 && words[first - 1].0 < words[first - 1].1 {
+        first -= 1;
+    }
+    let mut last = cursor;
+    while last + 1 < words.len() && words[last + 1].0 < words[last + 1].1 {
+        last += 1;
+    }
+
+    let mut result = vec![];
+    for s in first..=cursor {
+        for e in cursor..=last {
+            let (start, end) = (words[s].0, words[e].1);
+            let text: String = chars[start..end].iter().collect();
+            result.push((text, Span { line, start, end }));
+        }
+    }
+    result.sort_by_key(|(_, span)| std::cmp::Reverse(span.end - span.start));
+    result
+}
+
+/// Find the `node` declarations: `[extern|data|optim|exec] node <name>:`
+pub fn find_node_defs(source: &str) -> Vec<(String, Span)> {
+    source
+        .lines()
+        .enumerate()
+        .filter_map(|(line, text)| {
+            let code = text.trim_start();
+            let code = ["extern ", "data ", "optim ", "exec "]
+                .iter()
+                .find_map(|x| code.strip_prefix(x))
+                .unwrap_or(code)
+                .trim_start();
+            let name = code.strip_prefix("node ")?.split(':').next()?.trim();
+
+            let start = text.find(name)?;
+            Some((
+                name.to_string(),
+                Span {
+                    line,
+                    start: text[..start].chars().count(),
+                    end: text[..start].chars().count() + name.chars().count(),
+                },
+            ))
+        })
+        .collect()
+}
+
+/// Find the `let` declarations: `let [<shortcut>:] <name> = ...`
+pub fn find_let_defs(source: &str) -> Vec<LetDef> {
+    source
+        .lines()
+        .enumerate()
+        .filter_map(|(line, text)| {
+            let lhs = text.trim_start().strip_prefix("let ")?.split('=').next()?;
+            let (shortcut, name) = match lhs.find(':') {
+                Some(at) => (Some(lhs[..at].trim()), lhs[at + 1..].trim()),
+                None => (None, lhs.trim()),
+            };
+
+            let start = text.find(lhs)? + lhs.find(name)?;
+            let start = text[..start].chars().count();
+            Some(LetDef {
+                shortcut: shortcut.map(|x| x.to_string()),
+                name: name.to_string(),
+                span: Span {
+                    line,
+                    start,
+                    end: start + name.chars().count(),
+                },
+                indent: indent_of(text),
+            })
+        })
+        .collect()
+}
+
+/// Find the nearest `let` declaration, which is visible from the line.
+pub fn find_let_def(source: &str, name: &str, line: usize) -> Option<LetDef> {
+    let lines: Vec<_> = source.lines().collect();
+    let defs = find_let_defs(source);
+
+    // the declarations of the outer blocks are visible
+    let mut indent = lines.get(line).map(|x| indent_of(x)).unwrap_or(0);
+    let mut matched = None;
+    for (at, text) in lines.iter().enumerate().take(line + 1).rev() {
+        if text.trim().is_empty() {
+            continue;
+        }
+        if let Some(def) = defs.iter().find(|x| x.span.line == at) {
+            if def.indent <= indent
+                && (def.name == name || def.shortcut.as_deref() == Some(name))
+                && matched.is_none()
+            {
+                matched = Some(def.clone());
+            }
+        }
+        indent = indent.min(indent_of(text));
+    }
+    matched
+}
+
+/// The node which owns the arguments under the cursor.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ArgsOwner {
+    pub name: String,
+    /// Whether the arguments are given by the shortcuts
+    pub shortcut: bool,
+}
+
+/// Find the node which owns the arguments under the cursor.
+///
+/// * `with <node>:` blocks, which contain `set <name> = ...`
+/// * graph calls, as `<node>(<shortcut>=...)`
+pub fn find_args_owner(source: &str, line: usize, column: usize) -> Option<ArgsOwner> {
+    let lines: Vec<_> = source.lines().collect();
+    let text = lines.get(line)?;
+
+    // graph calls
+    let prefix: String = text.chars().take(column).collect();
+    if let Some(open) = prefix.rfind('(') {
+        // note: the values are not the arguments
+        let arg = prefix[open..].rsplit(',').next().unwrap_or_default();
+        if !prefix[open..].contains(')') && !arg.contains('=') {
+            let name = prefix[..open]
+                .trim_end()
+                .rsplit(|c: char| !is_symbol_char(c))
+                .next()?;
+            return Some(ArgsOwner {
+                name: name.to_string(),
+                shortcut: true,
+            });
+        }
+    }
+
+    // with blocks
+    if text.trim_start().starts_with("set ") && !prefix.contains('=') {
+        let indent = indent_of(text);
+        return lines[..line]
+            .iter()
+            .rev()
+            .filter(|x| !x.trim().is_empty())
+            .find(|x| indent_of(x) < indent)
+            .and_then(|x| x.trim().strip_prefix("with "))
+            .map(|x| ArgsOwner {
+                name: x.trim_end_matches(':').trim().to_string(),
+                shortcut: false,
+            });
+    }
+    None
+}
+
+/// Get the id of the graph line: `<id>. ...`
+pub fn graph_line_id(source: &str, line: usize) -> Option<u64> {
+    let text = source.lines().nth(line)?.trim_start();
+    let digits = text.chars().take_while(|c| c.is_ascii_digit()).count();
+    if digits > 0 && text[digits..].starts_with('.') {
+        text[..digits].parse().ok()
+    } else {
+        None
+    }
+}
+
+/// Convert the UTF-16 column of the line, which is used by LSP, into the character index.
+pub fn from_utf16(source: &str, line: usize, column: usize) -> usize {
+    let text = source.lines().nth(line).unwrap_or_default();
+    let mut units = 0;
+    for (at, c) in text.chars().enumerate() {
+        if units >= column {
+            return at;
+        }
+        units += c.len_utf16();
+    }
+    text.chars().count()
+}
+
+/// Convert the character index of the line into the UTF-16 column, which is used by LSP.
+pub fn to_utf16(source: &str, line: usize, column: usize) -> usize {
+    let text = source.lines().nth(line).unwrap_or_default();
+    text.chars().take(column).map(char::len_utf16).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = "node MyNode:
+    let K: kernel size = int 5
+    let W: width = dim
+
+    with Conv2D:
+        set kernel size = K
+
+    node MyConv:
+        let K: kernel size = int 3
+
+        1. Conv2D(K=K)
+
+    0. Input    = 3, W
+    1. MyConv   = 32, W/2
+";
+
+    #[test]
+    fn test_symbols_at() {
+        let symbols: Vec<_> = symbols_at(SOURCE, 5, 14)
+            .into_iter()
+            .map(|(x, _)| x)
+            .collect();
+        assert_eq!(
+            symbols,
+            ["set kernel size", "kernel size", "set kernel", "kernel"]
+        );
+
+        let symbols = symbols_at(SOURCE, 13, 8);
+        assert_eq!(symbols[0].0, "MyConv");
+        assert_eq!(
+            symbols[0].1,
+            Span {
+                line: 13,
+                start: 7,
+                end: 13
+            }
+        );
+    }
+
+    #[test]
+    fn test_find_defs() {
+        let nodes: Vec<_> = find_node_defs(SOURCE).into_iter().map(|x| x.0).collect();
+        assert_eq!(nodes, ["MyNode", "MyConv"]);
+
+        // the inner declaration shadows the outer one
+        assert_eq!(find_let_def(SOURCE, "K", 10).unwrap().span.line, 8);
+        assert_eq!(find_let_def(SOURCE, "K", 5).unwrap().span.line, 1);
+        assert_eq!(find_let_def(SOURCE, "width", 13).unwrap().span.line, 2);
+        assert_eq!(find_let_def(SOURCE, "no such", 13), None);
+    }
+
+    #[test]
+    fn test_utf16() {
+        // the emoji is a surrogate pair in UTF-16
+        let source = "node MyNode:\n    # 😀 = é\n";
+        assert_eq!(from_utf16(source, 1, 6), 6);
+        assert_eq!(from_utf16(source, 1, 8), 7);
+        assert_eq!(from_utf16(source, 1, 11), 10);
+        assert_eq!(from_utf16(source, 1, 100), 11);
+        assert_eq!(to_utf16(source, 1, 7), 8);
+        assert_eq!(to_utf16(source, 1, 11), 12);
+    }
+
+    #[test]
+    fn test_find_args_owner() {
+        let owner = |name: &str, shortcut| {
+            Some(ArgsOwner {
+                name: name.to_string(),
+                shortcut,
+            })
+        };
+        assert_eq!(find_args_owner(SOURCE, 5, 14), owner("Conv2D", false));
+        assert_eq!(find_args_owner(SOURCE, 10, 18), owner("Conv2D", true));
+        assert_eq!(find_args_owner(SOURCE, 5, 26), None);
+        assert_eq!(find_args_owner(SOURCE, 10, 21), None);
+        assert_eq!(find_args_owner(SOURCE, 13, 8), None);
+
+        assert_eq!(graph_line_id(SOURCE, 13), Some(1));
+        assert_eq!(graph_line_id(SOURCE, 5), None);
+    }
+}
@@ -20,6 +20,44 @@ pub struct NetError(pub Box<dyn std::error::Error>);
 #[derive(Debug)]
 pub struct SMPError(pub Box<dyn std::error::Error>);
 
+impl Error {
+    /// Get the stable code of the error, which is explained by [`explain`].
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::QueryError(e) => e.code(),
+            Self::NetError(_) => "N3-E1003",
+            Self::SMPError(_) => "N3-E1004",
+            Self::DeviceError(_) => "N3-E1005",
+        }
+    }
+}
+
+impl QueryError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::UnexpectedTokens { .. } => "N3-E1001",
+            Self::EmptyMachines => "N3-E1002",
+        }
+    }
+}
+
+/// The long-form explanations of the error codes, with the examples.
+pub static EXPLANATIONS: &[(&str, &str)] = &[
+    ("N3-E1001", include_str!("../errors/N3-E1001.md")),
+    ("N3-E1002", include_str!("../errors/N3-E1002.md")),
+    ("N3-E1003", include_str!("../errors/N3-E1003.md")),
+    ("N3-E1004", include_str!("../errors/N3-E1004.md")),
+    ("N3-E1005", include_str!("../errors/N3-E1005.md")),
+];
+
+/// Get the long-form explanation of the error code.
+pub fn explain(code: &str) -> Option<&'static str> {
+    EXPLANATIONS
+        .iter()
+        .find(|(k, _)| *k == code)
+        .map(|(_, v)| *v)
+}
+
 impl From<QueryError> for Error {
     fn from(error: QueryError) -> Self {
         Self::QueryError(error)
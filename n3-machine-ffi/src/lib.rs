@@ -37,6 +37,8 @@ pub struct WorkStatus {
     pub error_msg: Option<String>,
     pub date_begin: Option<DateTime>,
     pub date_end: Option<DateTime>,
+    /// The canonical hash of the program, to tell whether the works share the model and code
+    pub program_hash: Option<ProgramHash>,
 }
 
 pub type WorkId = u128;
@@ -54,6 +56,8 @@ pub struct MachineIdSet {
 
 pub type MachineId = u64;
 
+pub type ProgramHash = [u8; 32];
+
 pub type ProgramTextVec = Vec<u8>;
 pub type ProgramText = [u8];
 
@@ -138,6 +142,10 @@ impl WorkHandler {
         })
     }
 
+    pub fn set_program_hash(&self, hash: ProgramHash) -> Result<()> {
+        self.status.with_inner(|x| x.program_hash = Some(hash))
+    }
+
     pub fn start(&self) -> Result<()> {
         self.status.with_inner(|x| {
             x.is_running = true;
@@ -0,0 +1,103 @@
+use std::collections::BTreeMap;
+
+use n3_program::ast;
+use n3_program::graph::{Table, ToValues, Values};
+use n3_program::BuildValue;
+
+use crate::error::{Error, Result};
+
+/// The dims of each tensor, excluding the batch dim.
+pub type Shapes = BTreeMap<String, Option<Vec<Option<usize>>>>;
+
+/// The resolved variables of the extern node.
+pub struct Args(Values);
+
+impl<'a> From<&'a Table> for Args {
+    fn from(graph: &'a Table) -> Self {
+        Self(
+            graph
+                .to_values()
+                .into_iter()
+                .map(|(k, v)| (k, v.map(|x| x.build())))
+                .collect(),
+        )
+    }
+}
+
+impl Args {
+    pub fn get(&self, name: &str) -> Result<&ast::Value> {
+        match self.0.get(name) {
+            Some(Some(value)) => Ok(value),
+            _ => Error::EmptyValue {
+                name: name.to_string(),
+            }
+            .into(),
+        }
+    }
+
+    pub fn get_bool(&self, name: &str) -> Result<bool> {
+        match self.get(name)? {
+            ast::Value::Bool(value) => Ok(*value),
+            value => self.mismatched(name, ast::LetType::Bool, value),
+        }
+    }
+
+    pub fn get_uint(&self, name: &str) -> Result<usize> {
+        let value = self.get(name)?;
+        match value.unwrap_uint() {
+            Some(value) => Ok(value as usize),
+            None => self.mismatched(name, ast::LetType::UInt, value),
+        }
+    }
+
+    pub fn get_int(&self, name: &str) -> Result<i64> {
+        let value = self.get(name)?;
+        match value.unwrap_int() {
+            Some(value) => Ok(value),
+            None => self.mismatched(name, ast::LetType::Int, value),
+        }
+    }
+
+    pub fn get_real(&self, name: &str) -> Result<f64> {
+        let value = self.get(name)?;
+        match value.unwrap_real() {
+            Some(value) => Ok(value),
+            None => self.mismatched(name, ast::LetType::Real, value),
+        }
+    }
+
+    /// Get the dims of the shapes, like `{x: [1, 28, 28]}`.
+    ///
+    /// The dims which are not resolved are `None`.
+    pub fn get_shapes(&self, name: &str) -> Result<Shapes> {
+        let value = self.get(name)?;
+        let shapes = match value {
+            ast::Value::Map(shapes) => shapes,
+            _ => {
+                let ty = ast::LetType::Map(ast::LetType::List(ast::LetType::UInt.into()).into());
+                return self.mismatched(name, ty, value);
+            }
+        };
+
+        Ok(shapes
+            .iter()
+            .map(|(k, v)| {
+                let dims = v.as_ref().and_then(|x| x.unwrap_list()).map(|dims| {
+                    dims.iter()
+                        .map(|x| x.unwrap_uint().map(|x| x as usize))
+                        .collect()
+                });
+                (k.clone(), dims)
+            })
+            .collect())
+    }
+
+    fn mismatched<T>(&self, name: &str, expected: ast::LetType, given: &ast::Value) -> Result<T> {
+        Error::MismatchedValue {
+            name: name.to_string(),
+            expected,
+            given: Some(given.clone()),
+        }
+        .into()
+    }
+}
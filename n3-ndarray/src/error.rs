@@ -0,0 +1,32 @@
+use n3_program::ast;
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+#[derive(Debug, PartialEq)]
+pub enum Error {
+    /// The extern node is not implemented in pure Rust
+    NoSuchExtern { name: String },
+    /// The node is called without the input
+    NoSuchInput { name: String },
+    /// The node does not return the output
+    NoSuchOutput { name: String },
+    /// The variable of the extern node is not given, or is not resolved yet
+    EmptyValue { name: String },
+    MismatchedValue {
+        name: String,
+        expected: ast::LetType,
+        given: Option<ast::Value>,
+    },
+    /// The tensor does not fit the shape, excluding the batch dim
+    MismatchedShape {
+        name: String,
+        expected: Vec<Option<usize>>,
+        given: Vec<usize>,
+    },
+}
+
+impl<T> From<Error> for Result<T> {
+    fn from(error: Error) -> Self {
+        Err(error)
+    }
+}
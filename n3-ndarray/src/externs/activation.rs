@@ -0,0 +1,100 @@
+use ndarray::Axis;
+
+use super::{output_x, take_x, Extern, NewExtern};
+use crate::args::Args;
+use crate::error::{Error, Result};
+use crate::tensor::{Rng, Tensors};
+use n3_program::ast;
+
+pub struct Dropout;
+
+impl NewExtern for Dropout {
+    fn new(args: &Args, _: &mut Rng) -> Result<Self> {
+        // validate the probability, though it is not used on inference
+        args.get_real("probability")?;
+        Ok(Self)
+    }
+}
+
+impl Extern for Dropout {
+    fn forward(&self, input: Tensors) -> Result<Tensors> {
+        output_x(take_x(input)?)
+    }
+}
+
+pub struct Relu;
+
+impl NewExtern for Relu {
+    fn new(_: &Args, _: &mut Rng) -> Result<Self> {
+        Ok(Self)
+    }
+}
+
+impl Extern for Relu {
+    fn forward(&self, input: Tensors) -> Result<Tensors> {
+        output_x(take_x(input)?.mapv_into(|x| x.max(0.0)))
+    }
+}
+
+pub struct Softmax {
+    dimension: i64,
+}
+
+impl NewExtern for Softmax {
+    fn new(args: &Args, _: &mut Rng) -> Result<Self> {
+        Ok(Self {
+            dimension: args.get_int("dimension")?,
+        })
+    }
+}
+
+impl Extern for Softmax {
+    fn forward(&self, input: Tensors) -> Result<Tensors> {
+        let mut x = take_x(input)?;
+
+        // note: the dimension includes the batch dim, like PyTorch
+        let ndim = x.ndim() as i64;
+        let axis = if self.dimension >= 0 {
+            self.dimension
+        } else {
+            ndim + self.dimension
+        };
+        if axis < 0 || axis >= ndim {
+            return Error::MismatchedValue {
+                name: "dimension".to_string(),
+                expected: ast::LetType::Int,
+                given: Some(self.dimension.into()),
+            }
+            .into();
+        }
+
+        for mut lane in x.lanes_mut(Axis(axis as usize)) {
+            let max = lane.fold(f32::NEG_INFINITY, |a, &b| a.max(b));
+            lane.mapv_inplace(|x| (x - max).exp());
+            let sum = lane.sum();
+            lane.mapv_inplace(|x| x / sum);
+        }
+        output_x(x)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::arr2;
+
+    use super::*;
+    use crate::externs::forward_x;
+
+    #[test]
+    fn test_softmax() {
+        let node = Softmax { dimension: -1 };
+
+        let x = arr2(&[[1.0, 2.0, 3.0], [1000.0, 1000.0, 1000.0]]).into_dyn();
+        let y = forward_x(&node, x);
+        for row in y.outer_iter() {
+            assert!((row.sum() - 1.0).abs() < 1e-6);
+        }
+        assert!((y[[1, 0]] - 1.0 / 3.0).abs() < 1e-6);
+        assert!(y[[0, 0]] < y[[0, 1]] && y[[0, 1]] < y[[0, 2]]);
+    }
+}
@@ -0,0 +1,190 @@
+use n3_program::ast;
+use ndarray::{Axis, IxDyn};
+
+use super::{assert_shape, output_x, take_x, Extern, NewExtern};
+use crate::args::{Args, Shapes};
+use crate::error::{Error, Result};
+use crate::tensor::{Rng, Tensor, Tensors};
+
+pub struct AssertShape {
+    output_shapes: Shapes,
+}
+
+impl NewExtern for AssertShape {
+    fn new(args: &Args, _: &mut Rng) -> Result<Self> {
+        // note: the input node may not be sized
+        let output_shapes = match args.get("output shapes") {
+            Ok(_) => args.get_shapes("output shapes")?,
+            Err(_) => Default::default(),
+        };
+        Ok(Self { output_shapes })
+    }
+}
+
+impl Extern for AssertShape {
+    fn forward(&self, input: Tensors) -> Result<Tensors> {
+        for (name, x) in &input {
+            if let Some(Some(dims)) = self.output_shapes.get(name) {
+                assert_shape(name, x, dims)?;
+            }
+        }
+        Ok(input)
+    }
+}
+
+pub struct Concat {
+    axis: i64,
+}
+
+impl NewExtern for Concat {
+    fn new(args: &Args, _: &mut Rng) -> Result<Self> {
+        Ok(Self {
+            axis: args.get_int("axis")?,
+        })
+    }
+}
+
+impl Extern for Concat {
+    fn forward(&self, mut input: Tensors) -> Result<Tensors> {
+        // the inputs are keyed by their indices
+        let xs = (0..input.len())
+            .map(|i| {
+                let name = i.to_string();
+                input.remove(&name).ok_or(Error::NoSuchInput { name })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let first = match xs.first() {
+            Some(x) => x,
+            None => {
+                return Error::NoSuchInput {
+                    name: "0".to_string(),
+                }
+                .into()
+            }
+        };
+
+        // skip the batch dim
+        let ndim = first.ndim() as i64;
+        let axis = if self.axis >= 0 {
+            self.axis + 1
+        } else {
+            ndim + self.axis
+        };
+        if axis < 1 || axis >= ndim {
+            return Error::MismatchedValue {
+                name: "axis".to_string(),
+                expected: ast::LetType::Int,
+                given: Some(self.axis.into()),
+            }
+            .into();
+        }
+
+        // the dims should be same, except the axis
+        let mut dims: Vec<_> = first.shape()[1..].iter().map(|&x| Some(x)).collect();
+        dims[axis as usize - 1] = None;
+        for (i, x) in xs.iter().enumerate() {
+            assert_shape(&i.to_string(), x, &dims)?;
+        }
+
+        let views: Vec<_> = xs.iter().map(|x| x.view()).collect();
+        output_x(ndarray::stack(Axis(axis as usize), &views).unwrap())
+    }
+}
+
+pub struct ToLinear;
+
+impl NewExtern for ToLinear {
+    fn new(_: &Args, _: &mut Rng) -> Result<Self> {
+        Ok(Self)
+    }
+}
+
+impl Extern for ToLinear {
+    fn forward(&self, input: Tensors) -> Result<Tensors> {
+        let x = take_x(input)?;
+        let batch = x.shape()[0];
+        let size = x.len() / batch.max(1);
+        output_x(reshape(x, &[batch, size])?)
+    }
+}
+
+pub struct Transform {
+    dims: Vec<Option<usize>>,
+}
+
+impl NewExtern for Transform {
+    fn new(args: &Args, _: &mut Rng) -> Result<Self> {
+        match args.get_shapes("output shapes")?.remove("x") {
+            Some(Some(dims)) => Ok(Self { dims }),
+            _ => Error::EmptyValue {
+                name: "output shapes".to_string(),
+            }
+            .into(),
+        }
+    }
+}
+
+impl Extern for Transform {
+    fn forward(&self, input: Tensors) -> Result<Tensors> {
+        let x = take_x(input)?;
+
+        let mut shape = vec![x.shape()[0]];
+        for dim in &self.dims {
+            match dim {
+                Some(dim) => shape.push(*dim),
+                None => {
+                    return Error::EmptyValue {
+                        name: "output shapes".to_string(),
+                    }
+                    .into()
+                }
+            }
+        }
+        output_x(reshape(x, &shape)?)
+    }
+}
+
+fn reshape(x: Tensor, shape: &[usize]) -> Result<Tensor> {
+    let given = x.shape()[1..].to_vec();
+    x.as_standard_layout()
+        .into_owned()
+        .into_shape(IxDyn(shape))
+        .map_err(|_| Error::MismatchedShape {
+            name: "x".to_string(),
+            expected: shape[1..].iter().map(|&x| Some(x)).collect(),
+            given,
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_concat() {
+        let node = Concat { axis: 0 };
+
+        let input = vec![
+            ("0".to_string(), Tensor::zeros(vec![2, 1, 3])),
+            ("1".to_string(), Tensor::ones(vec![2, 2, 3])),
+        ];
+        let y = node.forward(input.into_iter().collect()).unwrap();
+        assert_eq!(y["x"].shape(), &[2, 3, 3]);
+        assert_eq!(y["x"].sum(), 12.0);
+
+        // mismatched dims, except the axis
+        let input = vec![
+            ("0".to_string(), Tensor::zeros(vec![2, 1, 3])),
+            ("1".to_string(), Tensor::ones(vec![2, 2, 4])),
+        ];
+        assert_eq!(
+            node.forward(input.into_iter().collect()),
+            Err(Error::MismatchedShape {
+                name: "1".to_string(),
+                expected: vec![None, Some(3)],
+                given: vec![2, 4],
+            })
+        );
+    }
+}
@@ -0,0 +1,269 @@
+use ndarray::{Array, Array4, Axis, Ix4};
+
+use super::{assert_shape, init_uniform, output_x, take_x, Extern, NewExtern};
+use crate::args::Args;
+use crate::error::{Error, Result};
+use crate::tensor::{Rng, Tensor, Tensors};
+
+/// The epsilon of `BatchNorm2D`, like PyTorch.
+const BATCH_NORM_EPS: f32 = 1e-5;
+
+pub struct BatchNorm2D {
+    channels: usize,
+
+    weight: Tensor,
+    bias: Tensor,
+    running_mean: Tensor,
+    running_var: Tensor,
+}
+
+impl NewExtern for BatchNorm2D {
+    fn new(args: &Args, _: &mut Rng) -> Result<Self> {
+        let channels = args.get_uint("channels")?;
+        Ok(Self {
+            channels,
+
+            weight: Tensor::ones(vec![channels]),
+            bias: Tensor::zeros(vec![channels]),
+            running_mean: Tensor::zeros(vec![channels]),
+            running_var: Tensor::ones(vec![channels]),
+        })
+    }
+}
+
+impl Extern for BatchNorm2D {
+    fn forward(&self, input: Tensors) -> Result<Tensors> {
+        let mut x = to_4d(take_x(input)?, self.channels)?;
+
+        // note: the running stats are used on inference
+        for (c, mut x) in x.axis_iter_mut(Axis(1)).enumerate() {
+            let scale = self.weight[c] / (self.running_var[c] + BATCH_NORM_EPS).sqrt();
+            let shift = self.bias[c] - self.running_mean[c] * scale;
+            x.mapv_inplace(|x| x * scale + shift);
+        }
+        output_x(x.into_dyn())
+    }
+
+    fn parameters_mut(&mut self) -> Vec<(&'static str, &mut Tensor)> {
+        vec![
+            ("weight", &mut self.weight),
+            ("bias", &mut self.bias),
+            ("running_mean", &mut self.running_mean),
+            ("running_var", &mut self.running_var),
+        ]
+    }
+}
+
+pub struct Conv2D {
+    kernel_size: usize,
+    padding: usize,
+    stride: usize,
+
+    input_channels: usize,
+    output_channels: usize,
+
+    weight: Tensor,
+    bias: Option<Tensor>,
+}
+
+impl NewExtern for Conv2D {
+    fn new(args: &Args, rng: &mut Rng) -> Result<Self> {
+        let kernel_size = args.get_uint("kernel size")?;
+        let input_channels = args.get_uint("input channels")?;
+        let output_channels = args.get_uint("output channels")?;
+
+        let fan_in = input_channels * kernel_size * kernel_size;
+        let weight = init_uniform(
+            &[output_channels, input_channels, kernel_size, kernel_size],
+            fan_in,
+            rng,
+        );
+        let bias = if args.get_bool("bias")? {
+            Some(init_uniform(&[output_channels], fan_in, rng))
+        } else {
+            None
+        };
+
+        Ok(Self {
+            kernel_size,
+            padding: args.get_uint("padding")?,
+            stride: args.get_uint("stride")?.max(1),
+
+            input_channels,
+            output_channels,
+
+            weight,
+            bias,
+        })
+    }
+}
+
+impl Extern for Conv2D {
+    fn forward(&self, input: Tensors) -> Result<Tensors> {
+        let x = to_4d(take_x(input)?, self.input_channels)?;
+        let (batch, _, height, width) = x.dim();
+
+        let k = self.kernel_size;
+        let p = self.padding;
+        let s = self.stride;
+        let out_height = output_size(height, k, p, s)?;
+        let out_width = output_size(width, k, p, s)?;
+
+        let mut y = Array4::zeros((batch, self.output_channels, out_height, out_width));
+        for ((b, o, i, j), y) in y.indexed_iter_mut() {
+            let mut sum = match &self.bias {
+                Some(bias) => bias[o],
+                None => 0.0,
+            };
+            for c in 0..self.input_channels {
+                for (ki, kj, x) in window(&x, b, c, i, j, k, p, s) {
+                    if let Some(x) = x {
+                        sum += x * self.weight[[o, c, ki, kj]];
+                    }
+                }
+            }
+            *y = sum;
+        }
+        output_x(y.into_dyn())
+    }
+
+    fn parameters_mut(&mut self) -> Vec<(&'static str, &mut Tensor)> {
+        let mut parameters = vec![("weight", &mut self.weight)];
+        if let Some(bias) = &mut self.bias {
+            parameters.push(("bias", bias));
+        }
+        parameters
+    }
+}
+
+pub struct MaxPool2D {
+    kernel_size: usize,
+    padding: usize,
+    stride: usize,
+}
+
+impl NewExtern for MaxPool2D {
+    fn new(args: &Args, _: &mut Rng) -> Result<Self> {
+        Ok(Self {
+            kernel_size: args.get_uint("kernel size")?,
+            padding: args.get_uint("padding")?,
+            stride: args.get_uint("stride")?.max(1),
+        })
+    }
+}
+
+impl Extern for MaxPool2D {
+    fn forward(&self, input: Tensors) -> Result<Tensors> {
+        let x = take_x(input)?;
+        let channels = x.shape().get(1).copied().unwrap_or_default();
+        let x = to_4d(x, channels)?;
+        let (batch, _, height, width) = x.dim();
+
+        let k = self.kernel_size;
+        let p = self.padding;
+        let s = self.stride;
+        let out_height = output_size(height, k, p, s)?;
+        let out_width = output_size(width, k, p, s)?;
+
+        // note: the padded elements are treated as `-inf`
+        let y = Array::from_shape_fn((batch, channels, out_height, out_width), |(b, c, i, j)| {
+            window(&x, b, c, i, j, k, p, s)
+                .filter_map(|(_, _, x)| x)
+                .fold(f32::NEG_INFINITY, f32::max)
+        });
+        output_x(y.into_dyn())
+    }
+}
+
+/// Convert the tensor into `[batch, channels, height, width]`.
+fn to_4d(x: Tensor, channels: usize) -> Result<Array4<f32>> {
+    assert_shape("x", &x, &[Some(channels), None, None])?;
+    Ok(x.into_dimensionality::<Ix4>().unwrap())
+}
+
+/// Get the size of the output, like PyTorch.
+fn output_size(size: usize, kernel_size: usize, padding: usize, stride: usize) -> Result<usize> {
+    let padded = size + 2 * padding;
+    if padded < kernel_size {
+        return Error::MismatchedShape {
+            name: "x".to_string(),
+            expected: vec![Some(kernel_size)],
+            given: vec![padded],
+        }
+        .into();
+    }
+    Ok((padded - kernel_size) / stride + 1)
+}
+
+/// Iterate the elements in the kernel window, which are `None` if padded.
+#[allow(clippy::too_many_arguments)]
+fn window<'a>(
+    x: &'a Array4<f32>,
+    b: usize,
+    c: usize,
+    i: usize,
+    j: usize,
+    kernel_size: usize,
+    padding: usize,
+    stride: usize,
+) -> impl Iterator<Item = (usize, usize, Option<f32>)> + 'a {
+    let (_, _, height, width) = x.dim();
+    (0..kernel_size)
+        .flat_map(move |ki| (0..kernel_size).map(move |kj| (ki, kj)))
+        .map(move |(ki, kj)| {
+            let xi = (i * stride + ki)
+                .checked_sub(padding)
+                .filter(|&xi| xi < height);
+            let xj = (j * stride + kj)
+                .checked_sub(padding)
+                .filter(|&xj| xj < width);
+            let x = xi.and_then(|xi| xj.map(|xj| x[[b, c, xi, xj]]));
+            (ki, kj, x)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::{arr1, Array};
+
+    use super::*;
+    use crate::externs::forward_x;
+
+    #[test]
+    fn test_conv2d() {
+        // the identity kernel
+        let mut weight = Tensor::zeros(vec![1, 1, 3, 3]);
+        weight[[0, 0, 1, 1]] = 1.0;
+        let node = Conv2D {
+            kernel_size: 3,
+            padding: 1,
+            stride: 1,
+            input_channels: 1,
+            output_channels: 1,
+            weight,
+            bias: Some(arr1(&[1.0]).into_dyn()),
+        };
+
+        let x = Array::range(0.0, 16.0, 1.0)
+            .into_shape(vec![1, 1, 4, 4])
+            .unwrap();
+        assert_eq!(forward_x(&node, x.clone()), x + 1.0);
+    }
+
+    #[test]
+    fn test_max_pool2d() {
+        let node = MaxPool2D {
+            kernel_size: 2,
+            padding: 0,
+            stride: 2,
+        };
+
+        let x = Array::range(0.0, 16.0, 1.0)
+            .into_shape(vec![1, 1, 4, 4])
+            .unwrap();
+        let y = Array::from(vec![5.0, 7.0, 13.0, 15.0])
+            .into_shape(vec![1, 1, 2, 2])
+            .unwrap();
+        assert_eq!(forward_x(&node, x), y);
+    }
+}
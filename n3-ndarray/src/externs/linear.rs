@@ -0,0 +1,92 @@
+use ndarray::{Ix1, Ix2};
+
+use super::{assert_shape, init_uniform, output_x, take_x, Extern, NewExtern};
+use crate::args::Args;
+use crate::error::Result;
+use crate::tensor::{Rng, Tensor, Tensors};
+
+pub struct Linear {
+    input_channels: usize,
+
+    weight: Tensor,
+    bias: Option<Tensor>,
+}
+
+impl NewExtern for Linear {
+    fn new(args: &Args, rng: &mut Rng) -> Result<Self> {
+        let input_channels = args.get_uint("input channels")?;
+        let output_channels = args.get_uint("output channels")?;
+
+        let weight = init_uniform(&[output_channels, input_channels], input_channels, rng);
+        let bias = if args.get_bool("bias")? {
+            Some(init_uniform(&[output_channels], input_channels, rng))
+        } else {
+            None
+        };
+
+        Ok(Self {
+            input_channels,
+
+            weight,
+            bias,
+        })
+    }
+}
+
+impl Extern for Linear {
+    fn forward(&self, input: Tensors) -> Result<Tensors> {
+        let x = take_x(input)?;
+        assert_shape("x", &x, &[Some(self.input_channels)])?;
+
+        let x = x.into_dimensionality::<Ix2>().unwrap();
+        let weight = self.weight.view().into_dimensionality::<Ix2>().unwrap();
+
+        let mut y = x.dot(&weight.t());
+        if let Some(bias) = &self.bias {
+            y += &bias.view().into_dimensionality::<Ix1>().unwrap();
+        }
+        output_x(y.into_dyn())
+    }
+
+    fn parameters_mut(&mut self) -> Vec<(&'static str, &mut Tensor)> {
+        let mut parameters = vec![("weight", &mut self.weight)];
+        if let Some(bias) = &mut self.bias {
+            parameters.push(("bias", bias));
+        }
+        parameters
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::{arr1, arr2};
+
+    use super::*;
+    use crate::error::Error;
+    use crate::externs::forward_x;
+
+    #[test]
+    fn test_linear() {
+        let mut node = Linear {
+            input_channels: 2,
+            weight: arr2(&[[1.0, 2.0], [3.0, 4.0], [5.0, 6.0]]).into_dyn(),
+            bias: Some(arr1(&[0.5, 0.0, -0.5]).into_dyn()),
+        };
+        assert_eq!(node.parameters_mut().len(), 2);
+
+        let x = arr2(&[[1.0, 1.0], [0.0, -1.0]]).into_dyn();
+        let y = arr2(&[[3.5, 7.0, 10.5], [-1.5, -4.0, -6.5]]).into_dyn();
+        assert_eq!(forward_x(&node, x), y);
+
+        // mismatched input channels
+        let x = arr2(&[[1.0, 1.0, 1.0]]).into_dyn();
+        assert_eq!(
+            node.forward(Some(("x".to_string(), x)).into_iter().collect()),
+            Err(Error::MismatchedShape {
+                name: "x".to_string(),
+                expected: vec![Some(2)],
+                given: vec![3],
+            })
+        );
+    }
+}
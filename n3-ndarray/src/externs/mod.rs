@@ -0,0 +1,133 @@
+mod activation;
+mod builtins;
+mod conv;
+mod linear;
+
+use rand::distributions::{Distribution, Uniform};
+
+use n3_builder::{Backend, NodeRoot};
+
+use crate::args::Args;
+use crate::error::{Error, Result};
+use crate::tensor::{Rng, Tensor, Tensors};
+
+pub use self::activation::{Dropout, Relu, Softmax};
+pub use self::builtins::{AssertShape, Concat, ToLinear, Transform};
+pub use self::conv::{BatchNorm2D, Conv2D, MaxPool2D};
+pub use self::linear::Linear;
+
+/// The implementation of the extern node, which runs on inference.
+pub trait Extern {
+    fn forward(&self, input: Tensors) -> Result<Tensors>;
+
+    /// The learnable parameters and the buffers, like `weight`.
+    fn parameters_mut(&mut self) -> Vec<(&'static str, &mut Tensor)> {
+        vec![]
+    }
+}
+
+pub trait NewExtern: Extern + Sized {
+    fn new(args: &Args, rng: &mut Rng) -> Result<Self>;
+}
+
+type BuildExtern = fn(&Args, &mut Rng) -> Result<Box<dyn Extern>>;
+
+/// The extern nodes which are implemented, by their names.
+pub static EXTERNS: &[(&str, BuildExtern)] = &[
+    // builtins
+    ("AssertShape", build::<AssertShape>),
+    ("Concat", build::<Concat>),
+    ("ToLinear", build::<ToLinear>),
+    ("Transform", build::<Transform>),
+    // nn
+    ("BatchNorm2D", build::<BatchNorm2D>),
+    ("Conv2D", build::<Conv2D>),
+    ("Dropout", build::<Dropout>),
+    ("Linear", build::<Linear>),
+    ("MaxPool2D", build::<MaxPool2D>),
+    ("Relu", build::<Relu>),
+    ("Softmax", build::<Softmax>),
+];
+
+/// Register the extern nodes which are implemented, to build the programs for this backend.
+pub fn add_externs(root: &NodeRoot) {
+    for (name, _) in EXTERNS {
+        root.add_extern(Backend::NdArray, name.to_string(), name.to_string());
+    }
+}
+
+pub fn build_extern(name: &str, args: &Args, rng: &mut Rng) -> Result<Box<dyn Extern>> {
+    match EXTERNS.iter().find(|(x, _)| *x == name) {
+        Some((_, build)) => build(args, rng),
+        None => Error::NoSuchExtern {
+            name: name.to_string(),
+        }
+        .into(),
+    }
+}
+
+fn build<T: NewExtern + 'static>(args: &Args, rng: &mut Rng) -> Result<Box<dyn Extern>> {
+    Ok(Box::new(T::new(args, rng)?))
+}
+
+/// Take the only input, which is named `x`.
+fn take_x(mut input: Tensors) -> Result<Tensor> {
+    input.remove("x").ok_or_else(|| Error::NoSuchInput {
+        name: "x".to_string(),
+    })
+}
+
+fn output_x(x: Tensor) -> Result<Tensors> {
+    Ok(Some(("x".to_string(), x)).into_iter().collect())
+}
+
+/// Sample the parameters from `U(-1/sqrt(fan_in), 1/sqrt(fan_in))`, like PyTorch does.
+fn init_uniform(shape: &[usize], fan_in: usize, rng: &mut Rng) -> Tensor {
+    let bound = 1.0 / (fan_in.max(1) as f32).sqrt();
+    let dist = Uniform::new_inclusive(-bound, bound);
+    Tensor::from_shape_simple_fn(shape, || dist.sample(rng))
+}
+
+/// Test the dims of the tensor, excluding the batch dim.
+pub(crate) fn assert_shape(name: &str, x: &Tensor, expected: &[Option<usize>]) -> Result<()> {
+    let given = &x.shape()[1.min(x.ndim())..];
+    if given.len() != expected.len()
+        || given
+            .iter()
+            .zip(expected)
+            .any(|(x, y)| y.map(|y| y != *x).unwrap_or_default())
+    {
+        return Error::MismatchedShape {
+            name: name.to_string(),
+            expected: expected.to_vec(),
+            given: given.to_vec(),
+        }
+        .into();
+    }
+    Ok(())
+}
+
+/// Run the extern node with the only input `x`.
+#[cfg(test)]
+fn forward_x(node: &dyn Extern, x: Tensor) -> Tensor {
+    node.forward(Some(("x".to_string(), x)).into_iter().collect())
+        .unwrap()
+        .remove("x")
+        .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_add_externs() {
+        let root = NodeRoot::new(Some("../n3-torch/ffi/python/n3"));
+        assert!(root.get_extern_names(Backend::NdArray).is_empty());
+
+        add_externs(&root);
+        let names = root.get_extern_names(Backend::NdArray);
+        assert_eq!(names.len(), EXTERNS.len());
+        assert!(names.contains("Conv2D"));
+    }
+}
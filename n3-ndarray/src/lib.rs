@@ -0,0 +1,17 @@
+//! The reference interpreter of the N3 programs, written in pure Rust.
+//!
+//! It runs the forward passes of the built codes on the CPU, without Python.
+//! Only the inference is supported: `Dropout` is skipped and `BatchNorm2D` uses the running stats.
+
+#![deny(clippy::all)]
+
+mod args;
+mod error;
+pub mod externs;
+mod node;
+mod tensor;
+
+pub use self::args::{Args, Shapes};
+pub use self::error::{Error, Result};
+pub use self::node::{Executable, ExternExecutable, NodeExecutable};
+pub use self::tensor::{Rng, Tensor, Tensors};
@@ -0,0 +1,283 @@
+use std::collections::BTreeMap;
+
+use rand::SeedableRng;
+
+use n3_program::ast;
+use n3_program::{BuildValue, Code, ExternCode, NodeCode};
+
+use crate::args::Args;
+use crate::error::{Error, Result};
+use crate::externs::{assert_shape, build_extern, Extern};
+use crate::tensor::{Rng, Tensor, Tensors};
+
+/// The executable tree of the node, which runs on the CPU.
+pub enum Executable {
+    Node(NodeExecutable),
+    Extern(ExternExecutable),
+}
+
+impl Executable {
+    /// Build the tree, initializing the parameters with the given seed.
+    pub fn build(code: &Code, seed: u64) -> Result<Self> {
+        Self::build_with_rng(code, &mut Rng::seed_from_u64(seed))
+    }
+
+    fn build_with_rng(code: &Code, rng: &mut Rng) -> Result<Self> {
+        match code {
+            Code::Node(code) => NodeExecutable::build(code, rng).map(Self::Node),
+            Code::Extern(code) => ExternExecutable::build(code, rng).map(Self::Extern),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            Self::Node(node) => &node.name,
+            Self::Extern(node) => &node.name,
+        }
+    }
+
+    /// Run the forward pass, with the tensors keyed by the input names.
+    pub fn forward(&self, input: Tensors) -> Result<Tensors> {
+        match self {
+            Self::Node(node) => node.forward(input),
+            Self::Extern(node) => node.forward(input),
+        }
+    }
+
+    /// The parameters of the whole tree, keyed by their paths like `1.0.weight`.
+    pub fn parameters_mut(&mut self) -> BTreeMap<String, &mut Tensor> {
+        match self {
+            Self::Node(node) => node.parameters_mut(),
+            Self::Extern(node) => node
+                .inner
+                .parameters_mut()
+                .into_iter()
+                .map(|(k, v)| (k.to_string(), v))
+                .collect(),
+        }
+    }
+
+    fn input(&self) -> &ast::Outs {
+        match self {
+            Self::Node(node) => &node.input,
+            Self::Extern(node) => &node.input,
+        }
+    }
+
+    fn output(&self) -> &ast::Outs {
+        match self {
+            Self::Node(node) => &node.output,
+            Self::Extern(node) => &node.output,
+        }
+    }
+}
+
+pub struct NodeExecutable {
+    name: String,
+    input: ast::Outs,
+    output: ast::Outs,
+    tensor_graph: Vec<Executable>,
+}
+
+impl NodeExecutable {
+    fn build(code: &NodeCode, rng: &mut Rng) -> Result<Self> {
+        Ok(Self {
+            name: code.data.name.clone(),
+            input: code.data.input.clone(),
+            output: code.data.output.clone(),
+            tensor_graph: code
+                .tensor_graph
+                .iter()
+                .map(|x| Executable::build_with_rng(x, rng))
+                .collect::<Result<_>>()?,
+        })
+    }
+
+    fn forward(&self, input: Tensors) -> Result<Tensors> {
+        // the inputs of the node are the outputs of the first one
+        let mut outputs: BTreeMap<ast::Out, Tensor> = input
+            .into_iter()
+            .map(|(k, x)| (ast::Out::new(1, k), x))
+            .collect();
+        let mut x = BTreeMap::new();
+
+        for node in &self.tensor_graph {
+            let input = node
+                .input()
+                .iter()
+                .map(|(k, n)| match outputs.get(n) {
+                    Some(x) => Ok((k.clone(), x.clone())),
+                    None => Error::NoSuchInput {
+                        name: format!("{:?}", n),
+                    }
+                    .into(),
+                })
+                .collect::<Result<_>>()?;
+
+            let mut y = node.forward(input)?;
+            x = node
+                .output()
+                .iter()
+                .map(|(k, n)| match y.remove(k) {
+                    Some(y) => Ok((n.clone(), y)),
+                    None => Error::NoSuchOutput { name: k.clone() }.into(),
+                })
+                .collect::<Result<BTreeMap<_, _>>>()?;
+            outputs.extend(x.iter().map(|(k, v)| (k.clone(), v.clone())));
+        }
+
+        Ok(x.into_iter().map(|(k, v)| (k.name, v)).collect())
+    }
+
+    fn parameters_mut(&mut self) -> BTreeMap<String, &mut Tensor> {
+        self.tensor_graph
+            .iter_mut()
+            .enumerate()
+            .flat_map(|(i, node)| {
+                node.parameters_mut()
+                    .into_iter()
+                    .map(move |(k, v)| (format!("{}.{}", i, k), v))
+            })
+            .collect()
+    }
+}
+
+pub struct ExternExecutable {
+    name: String,
+    input: ast::Outs,
+    output: ast::Outs,
+    output_shapes: BTreeMap<String, Vec<Option<usize>>>,
+    inner: Box<dyn Extern>,
+}
+
+impl ExternExecutable {
+    fn build(code: &ExternCode, rng: &mut Rng) -> Result<Self> {
+        let args = Args::from(&code.data.graph);
+
+        // the dims which are resolved on compile time
+        let output_shapes = match &code.shapes.output {
+            Some(shapes) => shapes
+                .0
+                .borrow()
+                .iter()
+                .filter_map(|(k, v)| v.as_ref().map(|v| (k, v)))
+                .map(|(k, v)| {
+                    let dims =
+                        v.0.iter()
+                            .map(|x| x.build().unwrap_uint().map(|x| x as usize))
+                            .collect();
+                    (k.clone(), dims)
+                })
+                .collect(),
+            None => Default::default(),
+        };
+
+        Ok(Self {
+            name: code.data.name.clone(),
+            input: code.data.input.clone(),
+            output: code.data.output.clone(),
+            output_shapes,
+            inner: build_extern(&code.data.name, &args, rng)?,
+        })
+    }
+
+    fn forward(&self, input: Tensors) -> Result<Tensors> {
+        let output = self.inner.forward(input)?;
+        for (name, x) in &output {
+            if let Some(dims) = self.output_shapes.get(name) {
+                assert_shape(name, x, dims)?;
+            }
+        }
+        Ok(output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use n3_builder::optimize::Pipeline;
+    use n3_builder::NodeRoot;
+    use n3_program::code::Codes;
+    use ndarray::s;
+
+    use super::*;
+
+    fn build_code(root: &NodeRoot, name: &str, dims: &[u64]) -> Code {
+        let shape = ast::Shape(dims.iter().map(|&x| x.into()).collect());
+        let input = ast::Shapes::new(Some(("x".to_string(), Some(shape))).into_iter().collect());
+
+        root.build_code(name, Some(&input)).unwrap()
+    }
+
+    fn forward(node: &Executable, x: Tensor) -> Tensor {
+        let mut output = node
+            .forward(Some(("x".to_string(), x)).into_iter().collect())
+            .unwrap();
+        output.remove("x").unwrap()
+    }
+
+    #[test]
+    fn test_lenet5() {
+        let root = NodeRoot::new(Some("../n3-torch/ffi/python/n3"));
+        let code = build_code(&root, "LeNet5", &[1, 28, 28]);
+
+        let mut node = Executable::build(&code, 42).unwrap();
+        assert_eq!(node.name(), "LeNet5");
+        assert!(node.parameters_mut().keys().any(|x| x.ends_with(".weight")));
+
+        let x = Tensor::ones(vec![2, 1, 28, 28]);
+        let y = forward(&node, x.clone());
+        assert_eq!(y.shape(), &[2, 10]);
+
+        // the same seed gives the same parameters
+        let node = Executable::build(&code, 42).unwrap();
+        assert_eq!(forward(&node, x.clone()), y);
+
+        // mismatched input shape
+        let x = Tensor::ones(vec![2, 3, 28, 28]);
+        assert!(matches!(
+            node.forward(Some(("x".to_string(), x)).into_iter().collect()),
+            Err(Error::MismatchedShape { .. })
+        ));
+    }
+
+    #[test]
+    fn test_optimized() {
+        let root = NodeRoot::new(Some("../n3-torch/ffi/python/n3"));
+        let code = build_code(&root, "LeNet5", &[1, 28, 28]);
+
+        let mut nodes = Codes::new();
+        nodes.insert(
+            "LeNet5".to_string(),
+            build_code(&root, "LeNet5", &[1, 28, 28]),
+        );
+        Pipeline::default().run(&mut nodes);
+        let optimized = &nodes["LeNet5"];
+
+        let node = Executable::build(&code, 42).unwrap();
+        let node_optimized = Executable::build(optimized, 42).unwrap();
+
+        let x = Tensor::from_shape_fn(vec![2, 1, 28, 28], |x| (x[2] * x[3]) as f32 / 784.0);
+        assert_eq!(forward(&node, x.clone()), forward(&node_optimized, x));
+    }
+
+    #[test]
+    fn test_concat() {
+        let model = "
+node MyNode:
+    0. Input          = 4
+    1. Linear         = 8
+    2. Concat[$0, $1] (axis=-1)
+";
+        let root = NodeRoot::new(Some("../n3-torch/ffi/python/n3"));
+        root.add_source("MyNode".to_string(), model.to_string());
+        let code = root.build_code("MyNode", None).unwrap();
+
+        // the inputs of Concat are keyed by their indices
+        let node = Executable::build(&code, 42).unwrap();
+        let x = Tensor::from_shape_fn(vec![2, 4], |x| (x[0] * 4 + x[1]) as f32);
+        let y = forward(&node, x.clone());
+        assert_eq!(y.shape(), &[2, 12]);
+        assert_eq!(y.slice(s![.., ..4]).into_dyn(), x);
+        assert_ne!(y.slice(s![.., 4..]).into_dyn(), Tensor::zeros(vec![2, 8]));
+    }
+}
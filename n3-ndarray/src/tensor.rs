@@ -0,0 +1,10 @@
+use std::collections::BTreeMap;
+
+/// The tensor, which begins with the batch dim.
+pub type Tensor = ndarray::ArrayD<f32>;
+
+/// The tensors, by their names.
+pub type Tensors = BTreeMap<String, Tensor>;
+
+/// The reproducible random generator, to initialize the parameters.
+pub type Rng = rand_pcg::Pcg64Mcg;
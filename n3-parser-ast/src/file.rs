@@ -1,12 +1,15 @@
-use std::collections::BTreeMap;
 use std::fmt;
 
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+
 use super::fmt::FmtGuard;
 use super::node::Node;
 use super::uses::Use;
 
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
 pub struct File {
-    pub uses: BTreeMap<String, Use>,
+    pub uses: IndexMap<String, Use>,
     pub node: Node,
 }
 
@@ -1,3 +1,4 @@
+use std::fmt;
 use std::ops::Deref;
 
 const INDENT: &str = "    ";
@@ -32,6 +33,19 @@ impl<'a, T> Deref for FmtGuard<'a, T> {
     }
 }
 
+pub fn fmt_doc_comment(f: &mut fmt::Formatter<'_>, indent: &str, doc: Option<&str>) -> fmt::Result {
+    if let Some(doc) = doc {
+        for line in doc.lines() {
+            if line.is_empty() {
+                writeln!(f, "{}##", indent)?;
+            } else {
+                writeln!(f, "{}## {}", indent, line)?;
+            }
+        }
+    }
+    Ok(())
+}
+
 #[macro_export]
 macro_rules! impl_debug_no_guard(
     ($t:ty) => {
@@ -0,0 +1,294 @@
+//! Rebuild the AST by taking the ownership.
+//!
+//! Each method of `Fold` rebuilds the children by default.
+//! Override it to replace the node, and call the free function of the same name
+//! to keep folding the children.
+
+use super::file::File;
+use super::graph::{GraphCall, GraphInputs, GraphNode, Shape, Shapes};
+use super::node::{Node, With};
+use super::uses::Use;
+use super::variable::{Expr, NodeLet, RefVariable, Value, Variable};
+
+pub trait Fold {
+    fn fold_file(&mut self, file: File) -> File {
+        fold_file(self, file)
+    }
+
+    fn fold_use(&mut self, u: Use) -> Use {
+        u
+    }
+
+    fn fold_node(&mut self, node: Node) -> Node {
+        fold_node(self, node)
+    }
+
+    fn fold_node_let(&mut self, node_let: NodeLet) -> NodeLet {
+        fold_node_let(self, node_let)
+    }
+
+    fn fold_with(&mut self, with: With) -> With {
+        fold_with(self, with)
+    }
+
+    fn fold_graph_node(&mut self, graph_node: GraphNode) -> GraphNode {
+        fold_graph_node(self, graph_node)
+    }
+
+    fn fold_graph_call(&mut self, call: GraphCall) -> GraphCall {
+        fold_graph_call(self, call)
+    }
+
+    fn fold_graph_inputs(&mut self, inputs: GraphInputs) -> GraphInputs {
+        inputs
+    }
+
+    fn fold_shapes(&mut self, shapes: Shapes) -> Shapes {
+        fold_shapes(self, shapes)
+    }
+
+    fn fold_shape(&mut self, shape: Shape) -> Shape {
+        fold_shape(self, shape)
+    }
+
+    fn fold_value(&mut self, value: Value) -> Value {
+        fold_value(self, value)
+    }
+
+    fn fold_expr(&mut self, expr: Expr) -> Expr {
+        fold_expr(self, expr)
+    }
+
+    /// The variables may be shared, so their values are not folded by default.
+    fn fold_variable(&mut self, var: RefVariable) -> Value {
+        Value::Variable(var)
+    }
+}
+
+/// Copy the AST without sharing the variables.
+pub struct DeepCopy;
+
+impl Fold for DeepCopy {
+    fn fold_variable(&mut self, var: RefVariable) -> Value {
+        let var = var.borrow().clone();
+        let var = Variable {
+            value: var.value.map(|x| self.fold_value(x)),
+            ..var
+        };
+        var.into()
+    }
+}
+
+pub fn fold_file<F: Fold + ?Sized>(f: &mut F, file: File) -> File {
+    File {
+        uses: file
+            .uses
+            .into_iter()
+            .map(|(k, u)| (k, f.fold_use(u)))
+            .collect(),
+        node: f.fold_node(file.node),
+    }
+}
+
+pub fn fold_node<F: Fold + ?Sized>(f: &mut F, node: Node) -> Node {
+    Node {
+        name: node.name,
+        ty: node.ty,
+        description: node.description,
+        graph: node
+            .graph
+            .into_iter()
+            .map(|(k, x)| (k, f.fold_node_let(x)))
+            .collect(),
+        cost: node
+            .cost
+            .into_iter()
+            .map(|(k, x)| (k, f.fold_value(x)))
+            .collect(),
+        withs: node
+            .withs
+            .into_iter()
+            .map(|(k, x)| (k, f.fold_with(x)))
+            .collect(),
+        children: node
+            .children
+            .into_iter()
+            .map(|(k, x)| (k, f.fold_node(x)))
+            .collect(),
+        tensor_graph: node
+            .tensor_graph
+            .into_iter()
+            .map(|(k, x)| (k, f.fold_graph_node(x)))
+            .collect(),
+    }
+}
+
+pub fn fold_node_let<F: Fold + ?Sized>(f: &mut F, node_let: NodeLet) -> NodeLet {
+    NodeLet {
+        value: node_let.value.map(|x| f.fold_value(x)),
+        ..node_let
+    }
+}
+
+pub fn fold_with<F: Fold + ?Sized>(f: &mut F, with: With) -> With {
+    With {
+        name: with.name,
+        graph: with
+            .graph
+            .into_iter()
+            .map(|(k, x)| (k, f.fold_value(x)))
+            .collect(),
+    }
+}
+
+pub fn fold_graph_node<F: Fold + ?Sized>(f: &mut F, graph_node: GraphNode) -> GraphNode {
+    GraphNode {
+        id: graph_node.id,
+        calls: graph_node
+            .calls
+            .into_iter()
+            .map(|x| f.fold_graph_call(x))
+            .collect(),
+        shapes: graph_node.shapes.map(|x| f.fold_shapes(x)),
+    }
+}
+
+pub fn fold_graph_call<F: Fold + ?Sized>(f: &mut F, call: GraphCall) -> GraphCall {
+    GraphCall {
+        name: call.name,
+        inputs: call.inputs.map(|x| f.fold_graph_inputs(x)),
+        args: call.args.map(|args| {
+            args.into_iter()
+                .map(|(k, x)| (k, f.fold_value(x)))
+                .collect()
+        }),
+        repeat: call.repeat.map(|x| f.fold_value(x)),
+    }
+}
+
+pub fn fold_shapes<F: Fold + ?Sized>(f: &mut F, shapes: Shapes) -> Shapes {
+    Shapes::new(
+        shapes
+            .0
+            .into_inner()
+            .into_iter()
+            .map(|(k, x)| (k, x.map(|x| f.fold_shape(x))))
+            .collect(),
+    )
+}
+
+pub fn fold_shape<F: Fold + ?Sized>(f: &mut F, shape: Shape) -> Shape {
+    Shape(shape.0.into_iter().map(|x| f.fold_value(x)).collect())
+}
+
+pub fn fold_value<F: Fold + ?Sized>(f: &mut F, value: Value) -> Value {
+    match value {
+        Value::Variable(var) => f.fold_variable(var),
+        Value::Expr(expr) => Value::Expr(Box::new(f.fold_expr(*expr))),
+        Value::List(list) => Value::List(list.into_iter().map(|x| f.fold_value(x)).collect()),
+        Value::Map(map) => Value::Map(
+            map.into_iter()
+                .map(|(k, x)| (k, x.map(|x| f.fold_value(x))))
+                .collect(),
+        ),
+        _ => value,
+    }
+}
+
+pub fn fold_expr<F: Fold + ?Sized>(f: &mut F, expr: Expr) -> Expr {
+    Expr {
+        op: expr.op,
+        lhs: f.fold_value(expr.lhs),
+        rhs: expr.rhs.map(|x| f.fold_value(x)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::visit::{self, Visit};
+    use crate::visit_mut::{self, VisitMut};
+    use crate::{Operator, Variable};
+
+    fn sample_value() -> Value {
+        // (a + 1) * [b, c]
+        let var = |name: &str| Value::from(Variable::with_name(name.to_string()));
+        Value::from(Expr {
+            op: Operator::Mul,
+            lhs: Expr {
+                op: Operator::Add,
+                lhs: var("a"),
+                rhs: Some(1u64.into()),
+            }
+            .into(),
+            rhs: Some(Value::List(vec![var("b"), var("c")])),
+        })
+    }
+
+    #[derive(Default)]
+    struct VariableNames(Vec<String>);
+
+    impl Visit for VariableNames {
+        fn visit_variable(&mut self, var: &RefVariable) {
+            self.0.push(var.borrow().name.clone());
+        }
+    }
+
+    #[test]
+    fn test_visit() {
+        let mut names = VariableNames::default();
+        names.visit_value(&sample_value());
+        assert_eq!(names.0, ["a", "b", "c"]);
+
+        // skip the right side of the expressions
+        struct LhsOnly(VariableNames);
+        impl Visit for LhsOnly {
+            fn visit_expr(&mut self, expr: &Expr) {
+                self.visit_value(&expr.lhs);
+            }
+
+            fn visit_variable(&mut self, var: &RefVariable) {
+                self.0.visit_variable(var);
+            }
+        }
+
+        let mut names = LhsOnly(VariableNames::default());
+        visit::visit_value(&mut names, &sample_value());
+        assert_eq!((names.0).0, ["a"]);
+    }
+
+    #[test]
+    fn test_visit_mut() {
+        // replace all constants into zero
+        struct Zero;
+        impl VisitMut for Zero {
+            fn visit_value_mut(&mut self, value: &mut Value) {
+                match value {
+                    Value::UInt(_) => *value = 0u64.into(),
+                    _ => visit_mut::visit_value_mut(self, value),
+                }
+            }
+        }
+
+        let mut value = sample_value();
+        Zero.visit_value_mut(&mut value);
+        assert_eq!(format!("{:?}", value), "((a + 0) * [b, c, ])");
+    }
+
+    #[test]
+    fn test_fold() {
+        // rename all variables
+        struct Rename;
+        impl Fold for Rename {
+            fn fold_variable(&mut self, var: RefVariable) -> Value {
+                let name = var.borrow().name.to_uppercase();
+                Variable::with_name(name).into()
+            }
+        }
+
+        let value = Rename.fold_value(sample_value());
+        let mut names = VariableNames::default();
+        names.visit_value(&value);
+        assert_eq!(names.0, ["A", "B", "C"]);
+    }
+}
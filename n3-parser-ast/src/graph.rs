@@ -54,7 +54,7 @@ impl fmt::Debug for Out {
     }
 }
 
-#[derive(Clone, PartialEq)]
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
 pub struct Shape(pub Vec<Value>);
 
 impl Shape {
@@ -76,7 +76,7 @@ impl fmt::Debug for Shape {
     }
 }
 
-#[derive(Clone, PartialEq)]
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
 pub struct Shapes(pub RefCell<ShapesInner>);
 
 type ShapesInner = BTreeMap<String, Option<Shape>>;
@@ -121,7 +121,7 @@ impl<'a> fmt::Debug for FmtGuard<'a, Shapes> {
     }
 }
 
-#[derive(Clone)]
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
 pub enum GraphInputs {
     Dict(Outs),
     List(Vec<Out>),
@@ -184,7 +184,7 @@ pub enum GraphInputsType {
     List,
 }
 
-#[derive(Clone)]
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
 pub struct GraphCall {
     pub name: String,
     pub inputs: Option<GraphInputs>,
@@ -222,7 +222,7 @@ impl fmt::Debug for GraphCall {
     }
 }
 
-#[derive(Clone)]
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
 pub struct GraphNode {
     pub id: u64,
     pub calls: Vec<GraphCall>,
@@ -1,12 +1,25 @@
+pub mod fold;
+pub mod visit;
+pub mod visit_mut;
+
 mod file;
 mod fmt;
 mod graph;
 mod node;
+mod shared;
+mod symbol;
 mod uses;
 mod variable;
 
+pub use indexmap::IndexMap;
+
 pub use self::file::*;
+pub use self::fold::Fold;
 pub use self::graph::*;
 pub use self::node::*;
+pub use self::shared::*;
+pub use self::symbol::*;
 pub use self::uses::*;
 pub use self::variable::*;
+pub use self::visit::Visit;
+pub use self::visit_mut::VisitMut;
@@ -1,12 +1,14 @@
 use std::collections::BTreeMap;
 use std::fmt;
 
+use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
 
 use super::fmt::FmtGuard;
 use super::graph::GraphNode;
 use super::variable::{Keywords, NodeLet, Value};
 
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
 pub struct With {
     pub name: String,
     pub graph: Keywords,
@@ -25,6 +27,20 @@ impl<'a> fmt::Debug for FmtGuard<'a, With> {
     }
 }
 
+struct FmtCost<'a>(&'a Keywords);
+
+impl<'a> fmt::Debug for FmtGuard<'a, FmtCost<'a>> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let indent = self.indent();
+        writeln!(f, "{}cost:", &indent)?;
+
+        for (name, value) in self.0 {
+            self.child(&FmtWithSet { name, value }).fmt(f)?;
+        }
+        Ok(())
+    }
+}
+
 struct FmtWithSet<'a> {
     name: &'a str,
     value: &'a Value,
@@ -37,7 +53,7 @@ impl<'a> fmt::Debug for FmtGuard<'a, FmtWithSet<'a>> {
     }
 }
 
-#[derive(Copy, Clone, PartialEq)]
+#[derive(Copy, Clone, PartialEq, Serialize, Deserialize)]
 pub enum NodeType {
     Default,
     Exec,
@@ -94,13 +110,17 @@ impl fmt::Debug for ExternNodeType {
     }
 }
 
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
 pub struct Node {
     pub name: String,
     pub ty: NodeType,
+    pub description: Option<String>,
 
-    pub graph: BTreeMap<String, NodeLet>,
-    pub withs: BTreeMap<String, With>,
-    pub children: BTreeMap<String, Node>,
+    pub graph: IndexMap<String, NodeLet>,
+    /// The estimated cost, which is declared on the extern nodes
+    pub cost: Keywords,
+    pub withs: IndexMap<String, With>,
+    pub children: IndexMap<String, Node>,
     pub tensor_graph: BTreeMap<u64, GraphNode>,
 }
 
@@ -108,11 +128,15 @@ crate::impl_debug_no_guard!(Node);
 impl<'a> fmt::Debug for FmtGuard<'a, Node> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let indent = self.indent();
+        crate::fmt::fmt_doc_comment(f, &indent, self.description.as_deref())?;
         writeln!(f, "{}{:?}node {}:", &indent, &self.ty, &self.name)?;
 
         for value in self.graph.values() {
             self.child(value).fmt(f)?;
         }
+        if !self.cost.is_empty() {
+            self.child(&FmtCost(&self.cost)).fmt(f)?;
+        }
         for value in self.withs.values() {
             self.child(value).fmt(f)?;
         }
@@ -0,0 +1,145 @@
+//! Serialization of the shared variables.
+//!
+//! The same `RefVariable` may be referred by many values.
+//! Inside of `with_shared_variables`, each variable is serialized only once,
+//! and the others are serialized as the references, so that the identities are restored.
+//! Serializing the variables outside of it is an error, as their identities would be lost.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use super::variable::{RefVariable, Variable};
+
+#[derive(Default)]
+struct SharedContext {
+    serialized: HashMap<*const RefCell<Variable>, u64>,
+    deserialized: HashMap<u64, RefVariable>,
+}
+
+thread_local! {
+    static CONTEXT: RefCell<Option<SharedContext>> = const { RefCell::new(None) };
+}
+
+/// Preserve the identities of the variables while (de)serializing.
+pub fn with_shared_variables<T>(f: impl FnOnce() -> T) -> T {
+    let parent = CONTEXT.with(|ctx| ctx.replace(Some(Default::default())));
+    let result = f();
+    CONTEXT.with(|ctx| ctx.replace(parent));
+    result
+}
+
+#[derive(Serialize, Deserialize)]
+enum SharedVariable<T> {
+    Def { id: u64, variable: T },
+    Ref { id: u64 },
+}
+
+impl Serialize for RefVariable {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let ptr = &**self as *const RefCell<Variable>;
+        let (id, is_new) = CONTEXT
+            .with(|ctx| {
+                ctx.borrow_mut().as_mut().map(|ctx| {
+                    let len = ctx.serialized.len() as u64;
+                    match ctx.serialized.get(&ptr) {
+                        Some(id) => (*id, false),
+                        None => {
+                            ctx.serialized.insert(ptr, len);
+                            (len, true)
+                        }
+                    }
+                })
+            })
+            .ok_or_else(|| {
+                serde::ser::Error::custom(
+                    "the variables should be serialized in `with_shared_variables`",
+                )
+            })?;
+
+        if is_new {
+            let variable = self.borrow();
+            SharedVariable::Def {
+                id,
+                variable: &*variable,
+            }
+            .serialize(serializer)
+        } else {
+            SharedVariable::<&Variable>::Ref { id }.serialize(serializer)
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for RefVariable {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        match SharedVariable::<Variable>::deserialize(deserializer)? {
+            SharedVariable::Def { id, variable } => {
+                let variable: Self = variable.into();
+                CONTEXT.with(|ctx| {
+                    if let Some(ctx) = ctx.borrow_mut().as_mut() {
+                        ctx.deserialized.insert(id, variable.clone());
+                    }
+                });
+                Ok(variable)
+            }
+            SharedVariable::Ref { id } => CONTEXT
+                .with(|ctx| {
+                    ctx.borrow()
+                        .as_ref()
+                        .and_then(|ctx| ctx.deserialized.get(&id).cloned())
+                })
+                .ok_or_else(|| serde::de::Error::custom(format!("no such variable: #{}", id))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Expr, Operator, Value};
+
+    #[test]
+    fn test_shared_variables() {
+        let a: RefVariable = Variable::with_name_value("a".to_string(), Some(3u64.into())).into();
+
+        // a + a
+        let value: Value = Expr {
+            op: Operator::Add,
+            lhs: a.clone().into(),
+            rhs: Some(a.into()),
+        }
+        .into();
+
+        let json = with_shared_variables(|| serde_json::to_string(&value)).unwrap();
+        let value: Value = with_shared_variables(|| serde_json::from_str(&json)).unwrap();
+
+        match value {
+            Value::Expr(expr) => match (&expr.lhs, &expr.rhs) {
+                (Value::Variable(lhs), Some(Value::Variable(rhs))) => {
+                    assert_eq!(lhs.borrow().value, Some(3u64.into()));
+                    // the identity should be preserved
+                    lhs.borrow_mut().value = Some(4u64.into());
+                    assert_eq!(rhs.borrow().value, Some(4u64.into()));
+                }
+                _ => unreachable!("expected variables"),
+            },
+            _ => unreachable!("expected an expression"),
+        }
+    }
+
+    #[test]
+    fn test_not_shared() {
+        let a: RefVariable = Variable::with_name_value("a".to_string(), Some(3u64.into())).into();
+        let value: Value = a.into();
+
+        // the identity would be lost
+        assert!(serde_json::to_string(&value).is_err());
+    }
+}
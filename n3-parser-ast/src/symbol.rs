@@ -0,0 +1,558 @@
+// The symbols are ordered by the names of the variables, which are not mutated while solving.
+#![allow(clippy::mutable_key_type)]
+
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::convert::TryFrom;
+
+use num_rational::Ratio;
+use num_traits::{CheckedAdd, CheckedMul, Pow, Signed, Zero};
+
+use super::graph::OutDim;
+use super::variable::{Expr, LetType, Operator, RefVariable, Value};
+
+type Coefficient = Ratio<i64>;
+type Monomial = BTreeMap<Symbol, u32>;
+
+/// The maximum exponent to be expanded into a polynomial.
+const MAX_EXPANDED_POW: i64 = 16;
+
+#[derive(Clone)]
+enum Symbol {
+    /// An unresolved variable.
+    Variable(RefVariable),
+    /// A dimension of the output, with the variable hinted by it.
+    Dim(OutDim, Option<RefVariable>),
+    /// An operation which cannot be expanded (e.g. `W / H`).
+    Apply(Operator, Box<Polynomial>, Option<Box<Polynomial>>),
+}
+
+impl Symbol {
+    fn rank(&self) -> u8 {
+        match self {
+            Self::Variable(_) => 0,
+            Self::Dim(_, _) => 1,
+            Self::Apply(_, _, _) => 2,
+        }
+    }
+
+    /// Returns the dim variable that can be solved.
+    fn unknown(&self) -> Option<&RefVariable> {
+        match self {
+            // the dynamic dims should not be solved
+            Self::Variable(var) | Self::Dim(_, Some(var))
+                if var.borrow().ty == Some(LetType::Dim) =>
+            {
+                Some(var)
+            }
+            _ => None,
+        }
+    }
+
+    fn has_unknowns(&self) -> bool {
+        match self {
+            Self::Apply(_, lhs, rhs) => {
+                lhs.has_unknowns() || rhs.as_ref().map(|x| x.has_unknowns()).unwrap_or_default()
+            }
+            _ => self.unknown().is_some(),
+        }
+    }
+
+    fn to_value(&self) -> Value {
+        match self {
+            Self::Variable(var) | Self::Dim(_, Some(var)) => var.clone().into(),
+            Self::Dim(dim, None) => dim.clone().into(),
+            Self::Apply(op, lhs, rhs) => Expr {
+                op: *op,
+                lhs: lhs.to_value(),
+                rhs: rhs.as_ref().map(|x| x.to_value()),
+            }
+            .into(),
+        }
+    }
+}
+
+impl PartialEq for Symbol {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Symbol {}
+
+impl PartialOrd for Symbol {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Symbol {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            // only test the name, as like as the variables
+            (Self::Variable(lhs), Self::Variable(rhs)) => lhs.borrow().name.cmp(&rhs.borrow().name),
+            (Self::Dim(lhs, _), Self::Dim(rhs, _)) => (&lhs.out, lhs.dim).cmp(&(&rhs.out, rhs.dim)),
+            (Self::Apply(lhs_op, lhs_lhs, lhs_rhs), Self::Apply(rhs_op, rhs_lhs, rhs_rhs)) => {
+                (*lhs_op as u8, lhs_lhs, lhs_rhs).cmp(&(*rhs_op as u8, rhs_lhs, rhs_rhs))
+            }
+            _ => self.rank().cmp(&other.rank()),
+        }
+    }
+}
+
+/// A normalized form of numeric values: the sum of the monomials with rational coefficients.
+///
+/// Note that the integer divisions of the symbolic terms are kept as opaque terms,
+/// as they truncate unless exact (e.g. `W / 2 * 2 != W`, but `W * 2 / 2 == W`).
+#[derive(Clone, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Polynomial {
+    terms: BTreeMap<Monomial, Coefficient>,
+}
+
+impl Polynomial {
+    fn constant(value: Coefficient) -> Self {
+        let mut terms = BTreeMap::new();
+        if !value.is_zero() {
+            terms.insert(Monomial::new(), value);
+        }
+        Self { terms }
+    }
+
+    fn symbol(symbol: Symbol) -> Self {
+        let mut monomial = Monomial::new();
+        monomial.insert(symbol, 1);
+
+        let mut terms = BTreeMap::new();
+        terms.insert(monomial, Coefficient::from_integer(1));
+        Self { terms }
+    }
+
+    fn apply(op: Operator, lhs: Self, rhs: Option<Self>) -> Self {
+        Self::symbol(Symbol::Apply(op, lhs.into(), rhs.map(|x| x.into())))
+    }
+
+    fn apply_div(lhs: Self, rhs: Self) -> Option<Self> {
+        // the integer division truncates, so that `-x / d == -(x / d)`
+        match lhs.terms.values().next() {
+            Some(c) if c.is_negative() => {
+                Self::apply(Operator::Div, lhs.checked_neg()?, Some(rhs)).checked_neg()
+            }
+            _ => Some(Self::apply(Operator::Div, lhs, Some(rhs))),
+        }
+    }
+
+    pub fn from_value(value: &Value) -> Option<Self> {
+        match value {
+            Value::Bool(value) => Some(Self::constant((*value as i64).into())),
+            Value::UInt(value) => i64::try_from(*value).ok().map(|x| Self::constant(x.into())),
+            Value::Int(value) => Some(Self::constant((*value).into())),
+            Value::Real(value) => Coefficient::approximate_float(*value).map(Self::constant),
+            Value::Dim(dim) => Some(Self::symbol(Symbol::Dim(dim.clone(), None))),
+            Value::Variable(var) => {
+                let var_ref = var.try_borrow().ok()?;
+                match &var_ref.value {
+                    Some(Value::Dim(dim)) => {
+                        Some(Self::symbol(Symbol::Dim(dim.clone(), Some(var.clone()))))
+                    }
+                    Some(value) => Self::from_value(value),
+                    None => Some(Self::symbol(Symbol::Variable(var.clone()))),
+                }
+            }
+            Value::Expr(expr) => Self::from_expr(expr),
+            _ => None,
+        }
+    }
+
+    fn from_expr(expr: &Expr) -> Option<Self> {
+        let lhs = Self::from_value(&expr.lhs)?;
+        let rhs = match &expr.rhs {
+            Some(rhs) => Some(Self::from_value(rhs)?),
+            None => None,
+        };
+
+        match (expr.op, rhs) {
+            (Operator::Pos, None) => Some(lhs),
+            (Operator::Neg, None) => lhs.checked_neg(),
+            (Operator::Add, Some(rhs)) => lhs.checked_add(&rhs),
+            (Operator::Sub, Some(rhs)) => lhs.checked_sub(&rhs),
+            (Operator::Mul, Some(rhs)) | (Operator::MulInt, Some(rhs)) => lhs.checked_mul(&rhs),
+            (Operator::Div, Some(rhs)) => match (lhs.as_constant(), rhs.as_constant()) {
+                (_, Some(r)) if r.is_zero() => None,
+                // follow the integer division
+                (Some(l), Some(r)) if l.is_integer() && r.is_integer() => {
+                    Some(Self::constant((l / r).trunc()))
+                }
+                // the integer division is not exact on the symbolic terms
+                (_, Some(r)) if r.is_integer() && !lhs.is_divisible(&r) => {
+                    Self::apply_div(lhs, rhs)
+                }
+                (_, Some(r)) => lhs.checked_scale(&r.recip()),
+                _ => Some(Self::apply(expr.op, lhs, Some(rhs))),
+            },
+            (Operator::Mod, Some(rhs)) => match (lhs.as_constant(), rhs.as_constant()) {
+                (_, Some(r)) if r.is_zero() => None,
+                (Some(l), Some(r)) if l.is_integer() && r.is_integer() => {
+                    Some(Self::constant(l % r))
+                }
+                _ => Some(Self::apply(expr.op, lhs, Some(rhs))),
+            },
+            (Operator::Pow, Some(rhs)) => match rhs.as_constant() {
+                Some(r) if r.is_integer() && (0..=MAX_EXPANDED_POW).contains(r.numer()) => {
+                    lhs.checked_pow(*r.numer() as u32)
+                }
+                _ => Some(Self::apply(expr.op, lhs, Some(rhs))),
+            },
+            (Operator::And, Some(rhs)) | (Operator::Or, Some(rhs)) | (Operator::Xor, Some(rhs)) => {
+                match (lhs.as_constant(), rhs.as_constant()) {
+                    (Some(l), Some(r)) if l.is_integer() && r.is_integer() => {
+                        let (l, r) = (l.to_integer(), r.to_integer());
+                        let value = match expr.op {
+                            Operator::And => l & r,
+                            Operator::Or => l | r,
+                            _ => l ^ r,
+                        };
+                        Some(Self::constant(value.into()))
+                    }
+                    _ => Some(Self::apply(expr.op, lhs, Some(rhs))),
+                }
+            }
+            _ => None,
+        }
+    }
+
+    pub fn is_constant(&self) -> bool {
+        self.as_constant().is_some()
+    }
+
+    fn as_constant(&self) -> Option<Coefficient> {
+        match self.terms.len() {
+            0 => Some(Coefficient::zero()),
+            1 => self.terms.get(&Monomial::new()).copied(),
+            _ => None,
+        }
+    }
+
+    fn is_divisible(&self, divisor: &Coefficient) -> bool {
+        self.terms
+            .values()
+            .all(|x| x.is_integer() && (x / divisor).is_integer())
+    }
+
+    fn has_unknowns(&self) -> bool {
+        self.terms
+            .keys()
+            .flat_map(|x| x.keys())
+            .any(|x| x.has_unknowns())
+    }
+
+    fn insert(&mut self, monomial: Monomial, coefficient: Coefficient) -> Option<()> {
+        let coefficient = match self.terms.remove(&monomial) {
+            Some(c) => c.checked_add(&coefficient)?,
+            None => coefficient,
+        };
+        if !coefficient.is_zero() {
+            self.terms.insert(monomial, coefficient);
+        }
+        Some(())
+    }
+
+    fn checked_neg(&self) -> Option<Self> {
+        self.checked_scale(&Coefficient::from_integer(-1))
+    }
+
+    fn checked_add(&self, rhs: &Self) -> Option<Self> {
+        let mut result = self.clone();
+        for (monomial, coefficient) in &rhs.terms {
+            result.insert(monomial.clone(), *coefficient)?;
+        }
+        Some(result)
+    }
+
+    fn checked_sub(&self, rhs: &Self) -> Option<Self> {
+        self.checked_add(&rhs.checked_neg()?)
+    }
+
+    fn checked_scale(&self, scale: &Coefficient) -> Option<Self> {
+        let mut result = Self::default();
+        for (monomial, coefficient) in &self.terms {
+            result.insert(monomial.clone(), coefficient.checked_mul(scale)?)?;
+        }
+        Some(result)
+    }
+
+    fn checked_mul(&self, rhs: &Self) -> Option<Self> {
+        let mut result = Self::default();
+        for (lhs_monomial, lhs_coefficient) in &self.terms {
+            for (rhs_monomial, rhs_coefficient) in &rhs.terms {
+                let mut monomial = lhs_monomial.clone();
+                for (symbol, exp) in rhs_monomial {
+                    *monomial.entry(symbol.clone()).or_default() += exp;
+                }
+                result.insert(monomial, lhs_coefficient.checked_mul(rhs_coefficient)?)?;
+            }
+        }
+        Some(result)
+    }
+
+    fn checked_pow(&self, exp: u32) -> Option<Self> {
+        let mut result = Self::constant(Coefficient::from_integer(1));
+        for _ in 0..exp {
+            result = result.checked_mul(self)?;
+        }
+        Some(result)
+    }
+
+    /// Converts into the value, which is simplified as possible.
+    pub fn to_value(&self) -> Value {
+        let mut terms = self.terms.iter();
+        let first = match terms.next() {
+            Some((monomial, coefficient)) => term_to_value(monomial, *coefficient),
+            None => return Value::UInt(0),
+        };
+        terms.fold(first, |lhs, (monomial, coefficient)| {
+            if coefficient.is_negative() {
+                lhs - term_to_value(monomial, -coefficient)
+            } else {
+                lhs + term_to_value(monomial, *coefficient)
+            }
+        })
+    }
+}
+
+fn coefficient_to_value(value: Coefficient) -> Value {
+    if value.is_integer() {
+        let value = value.to_integer();
+        match u64::try_from(value) {
+            Ok(value) => value.into(),
+            Err(_) => value.into(),
+        }
+    } else {
+        (*value.numer() as f64 / *value.denom() as f64).into()
+    }
+}
+
+fn term_to_value(monomial: &Monomial, coefficient: Coefficient) -> Value {
+    let base = monomial
+        .iter()
+        .map(|(symbol, exp)| {
+            let value = symbol.to_value();
+            if *exp == 1 {
+                value
+            } else {
+                value.pow(Value::UInt(*exp as u64))
+            }
+        })
+        .fold(None, |lhs: Option<Value>, rhs| match lhs {
+            Some(lhs) => Some(lhs * rhs),
+            None => Some(rhs),
+        });
+
+    match base {
+        Some(mut base) => {
+            let numer = *coefficient.numer();
+            let denom = *coefficient.denom();
+            if numer == -1 {
+                base = -base;
+            } else if numer != 1 {
+                base = coefficient_to_value(numer.into()) * base;
+            }
+            if denom != 1 {
+                base = base / coefficient_to_value(denom.into());
+            }
+            base
+        }
+        None => coefficient_to_value(coefficient),
+    }
+}
+
+impl Value {
+    /// Tests whether both of the numeric values are equal, by comparing their normalized forms.
+    pub fn eq_symbolic(&self, other: &Self) -> bool {
+        match (Polynomial::from_value(self), Polynomial::from_value(other)) {
+            (Some(lhs), Some(rhs)) => lhs == rhs,
+            _ => false,
+        }
+    }
+
+    /// Simplifies the numeric value (e.g. `(W + 0) / 2` into `W / 2`).
+    pub fn normalize(&self) -> Option<Self> {
+        Polynomial::from_value(self).map(|x| x.to_value())
+    }
+
+    /// Solves the linear equation `self = other` for a single unknown dim variable.
+    ///
+    /// Returns the variable and its solution if the equation can be solved
+    /// (e.g. `W / 4 = 7` into `W = 28`, the smallest one of the integer division).
+    /// The solution is `None` if it is not a non-negative integer (e.g. `2 * W = 7`).
+    pub fn solve(&self, other: &Self) -> Option<(RefVariable, Option<Self>)> {
+        Polynomial::from_value(self)?
+            .checked_sub(&Polynomial::from_value(other)?)?
+            .solve()
+    }
+}
+
+impl Polynomial {
+    /// Solves the linear equation `self = 0`.
+    fn solve(self) -> Option<(RefVariable, Option<Value>)> {
+        let mut unknown = None;
+        let mut rest = Self::default();
+        for (monomial, coefficient) in self.terms {
+            if !monomial.keys().any(|x| x.has_unknowns()) {
+                rest.insert(monomial, coefficient)?;
+                continue;
+            }
+
+            // only linear terms with a single unknown are supported
+            if unknown.is_some() || monomial.len() != 1 {
+                return None;
+            }
+            let (symbol, exp) = monomial.into_iter().next()?;
+            if exp != 1 {
+                return None;
+            }
+            unknown = Some((symbol, coefficient));
+        }
+
+        let (symbol, coefficient) = unknown?;
+        let solution = rest.checked_scale(&-coefficient.recip())?;
+        match symbol {
+            // lhs / d = q  -->  lhs = q * d, as the integer division truncates
+            Symbol::Apply(Operator::Div, lhs, Some(rhs)) => {
+                let divisor = rhs
+                    .as_constant()
+                    .filter(|x| x.is_integer() && x.is_positive())?;
+                let quotient = solution.as_constant()?;
+                let (var, solution) = lhs
+                    .checked_sub(&Self::constant(quotient.checked_mul(&divisor)?))?
+                    .solve()?;
+                Some((var, solution.filter(|_| quotient.is_integer())))
+            }
+            symbol => {
+                let var = symbol.unknown()?.clone();
+                // note: the symbolic solutions cannot be tested
+                match solution.as_constant() {
+                    Some(x) if !is_dim_size(&x) => Some((var, None)),
+                    _ => Some((var, Some(solution.to_value()))),
+                }
+            }
+        }
+    }
+}
+
+fn is_dim_size(value: &Coefficient) -> bool {
+    value.is_integer() && !value.is_negative()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::variable::Variable;
+
+    fn dim(name: &str) -> RefVariable {
+        let mut var = Variable::with_name(name.to_string());
+        var.ty = Some(LetType::Dim);
+        var.into()
+    }
+
+    fn expr(op: Operator, lhs: Value, rhs: Value) -> Value {
+        Expr {
+            op,
+            lhs,
+            rhs: Some(rhs),
+        }
+        .into()
+    }
+
+    #[test]
+    fn test_normalize() {
+        let w: Value = dim("W").into();
+
+        // W / 2 == (W + 0) / 2
+        let a = expr(Operator::Div, w.clone(), 2u64.into());
+        let b = expr(
+            Operator::Div,
+            expr(Operator::Add, w.clone(), 0u64.into()),
+            2u64.into(),
+        );
+        assert_eq!(a, b);
+
+        // W / 2 != (W + 1) / 2
+        let c = expr(
+            Operator::Div,
+            expr(Operator::Add, w.clone(), 1u64.into()),
+            2u64.into(),
+        );
+        assert_ne!(a, c);
+
+        // 2 * W - W == W
+        let d = expr(
+            Operator::Sub,
+            expr(Operator::Mul, 2u64.into(), w.clone()),
+            w.clone(),
+        );
+        assert_eq!(d.normalize().unwrap(), w);
+
+        // integer division on constants
+        let e = expr(Operator::Div, 7u64.into(), 2u64.into());
+        assert_eq!(e.normalize().unwrap(), Value::UInt(3));
+
+        // the floor division is not exact: W / 2 * 2 != W
+        let f = expr(Operator::Mul, a.clone(), 2u64.into());
+        assert_ne!(f, w);
+
+        // but it is exact if divisible: W * 2 / 2 == W
+        let g = expr(
+            Operator::Div,
+            expr(Operator::Mul, w.clone(), 2u64.into()),
+            2u64.into(),
+        );
+        assert_eq!(g.normalize().unwrap(), w);
+    }
+
+    #[test]
+    fn test_solve() {
+        let w = dim("W");
+        let h = dim("H");
+
+        // W / 4 = 7
+        let a = expr(Operator::Div, w.clone().into(), 4u64.into());
+        let (var, value) = a.solve(&7u64.into()).unwrap();
+        assert_eq!(var.borrow().name, "W");
+        assert_eq!(value, Some(Value::UInt(28)));
+
+        // (W - 3) * 2 + 1 = 11
+        let b = expr(
+            Operator::Add,
+            expr(
+                Operator::Mul,
+                expr(Operator::Sub, w.clone().into(), 3u64.into()),
+                2u64.into(),
+            ),
+            1u64.into(),
+        );
+        let (_, value) = b.solve(&11u64.into()).unwrap();
+        assert_eq!(value, Some(Value::UInt(8)));
+
+        // not an integer: 2 * W = 7
+        let e = expr(Operator::Mul, 2u64.into(), w.clone().into());
+        let (var, value) = e.solve(&7u64.into()).unwrap();
+        assert_eq!(var.borrow().name, "W");
+        assert_eq!(value, None);
+
+        // negative: W + 3 = 1
+        let f = expr(Operator::Add, w.clone().into(), 3u64.into());
+        assert_eq!(f.solve(&1u64.into()).unwrap().1, None);
+
+        // not an integer quotient: W / 4 * 2 = 7
+        let g = expr(Operator::Mul, a.clone(), 2u64.into());
+        assert_eq!(g.solve(&7u64.into()).unwrap().1, None);
+
+        // nonlinear: W * W = 4
+        let c = expr(Operator::Mul, w.clone().into(), w.clone().into());
+        assert!(c.solve(&4u64.into()).is_none());
+
+        // multiple unknowns: W + H = 4
+        let d = expr(Operator::Add, w.into(), h.into());
+        assert!(d.solve(&4u64.into()).is_none());
+    }
+}
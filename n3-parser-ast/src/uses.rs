@@ -1,5 +1,8 @@
 use std::fmt;
 
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
 pub enum UseBy {
     Local,
     Repo { author: String },
@@ -16,6 +19,7 @@ impl fmt::Debug for UseBy {
     }
 }
 
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
 pub struct Use {
     pub name: String,
     pub by: UseBy,
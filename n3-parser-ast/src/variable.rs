@@ -6,6 +6,7 @@ use std::iter::{Product, Sum};
 use std::ops;
 use std::rc::Rc;
 
+use indexmap::IndexMap;
 use num_traits::Pow;
 use serde::{Deserialize, Serialize};
 
@@ -46,7 +47,7 @@ impl fmt::Debug for RefVariable {
     }
 }
 
-#[derive(Clone, Default)]
+#[derive(Clone, Default, Serialize, Deserialize)]
 pub struct Variable {
     pub id: Option<u64>,
     pub id_old: Option<u64>,
@@ -57,6 +58,7 @@ pub struct Variable {
 
     pub ty: Option<LetType>,
     pub value: Option<Value>,
+    pub range: Option<LetRange>,
 }
 
 impl Variable {
@@ -76,11 +78,11 @@ impl Variable {
     }
 
     pub fn is_hint(&self) -> bool {
-        self.ty
-            .as_ref()
-            .map(|x| x == &LetType::Dim)
-            .unwrap_or_default()
-            || self.value.as_ref().map(|x| x.is_hint()).unwrap_or_default()
+        self.is_dim() || self.value.as_ref().map(|x| x.is_hint()).unwrap_or_default()
+    }
+
+    pub fn is_dim(&self) -> bool {
+        self.ty.as_ref().map(|x| x.is_dim()).unwrap_or_default()
     }
 
     pub fn is_node(&self) -> bool {
@@ -132,6 +134,10 @@ pub enum LetType {
     String,
     Node(Option<LetNodeType>),
     Dim,
+    // the dim which is not resolved until runtime
+    DynamicDim,
+    // assume that the variants are String
+    Enum(Vec<String>),
 
     List(Box<LetType>),
     // assume that key is String
@@ -142,6 +148,21 @@ impl LetType {
     pub fn is_node(&self) -> bool {
         matches!(self, Self::Node(_))
     }
+
+    pub fn is_dim(&self) -> bool {
+        matches!(self, Self::Dim | Self::DynamicDim)
+    }
+
+    pub fn is_enum(&self) -> bool {
+        matches!(self, Self::Enum(_))
+    }
+
+    pub fn unwrap_enum(&self) -> Option<&[String]> {
+        match self {
+            Self::Enum(variants) => Some(variants),
+            _ => None,
+        }
+    }
 }
 
 impl fmt::Debug for LetType {
@@ -153,6 +174,8 @@ impl fmt::Debug for LetType {
             Self::String => write!(f, "str"),
             Self::Node(ty) => write!(f, "{:?}node", &ty.or(Some(LetNodeType::Default)).unwrap()),
             Self::Dim => write!(f, "dim"),
+            Self::DynamicDim => write!(f, "dim dynamic"),
+            Self::Enum(variants) => write!(f, "enum {{ {} }}", variants.join(", ")),
             // TODO: [proposal] add the other types
             Self::List(ty) => write!(f, "{:?}*", &ty),
             Self::Map(ty) => write!(f, "{:?}#", &ty),
@@ -175,17 +198,88 @@ impl fmt::Debug for LetNodeType {
     }
 }
 
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct LetRange {
+    pub min: Option<LetBound>,
+    pub max: Option<LetBound>,
+}
+
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct LetBound {
+    pub value: Value,
+    pub inclusive: bool,
+}
+
+impl LetRange {
+    /// Test whether the value is in the range.
+    /// Returns `None` if the value or the bounds are not comparable yet (e.g. unresolved).
+    pub fn contains(&self, value: &Value) -> Option<bool> {
+        match value {
+            Value::List(values) => values.iter().try_fold(true, |acc, x| {
+                self.contains(x).map(|contained| acc && contained)
+            }),
+            _ => {
+                let value = value.unwrap_real()?;
+                let test = |bound: &Option<LetBound>, f: fn(f64, f64) -> bool| match bound {
+                    Some(bound) => {
+                        let b = bound.value.unwrap_real()?;
+                        Some(f(value, b) || (bound.inclusive && value == b))
+                    }
+                    None => Some(true),
+                };
+                Some(test(&self.min, |v, b| v > b)? && test(&self.max, |v, b| v < b)?)
+            }
+        }
+    }
+}
+
+impl fmt::Debug for LetRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (&self.min, &self.max) {
+            (Some(min), Some(max)) => write!(
+                f,
+                "in {}{:?}, {:?}{}",
+                if min.inclusive { '[' } else { '(' },
+                &min.value,
+                &max.value,
+                if max.inclusive { ']' } else { ')' },
+            ),
+            (Some(min), None) => {
+                write!(
+                    f,
+                    ">{} {:?}",
+                    if min.inclusive { "=" } else { "" },
+                    &min.value
+                )
+            }
+            (None, Some(max)) => {
+                write!(
+                    f,
+                    "<{} {:?}",
+                    if max.inclusive { "=" } else { "" },
+                    &max.value
+                )
+            }
+            (None, None) => Ok(()),
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
 pub struct NodeLet {
     pub name: String,
     pub shortcut: Option<String>,
+    pub description: Option<String>,
     pub ty: LetType,
     pub value: Option<Value>,
+    pub range: Option<LetRange>,
 }
 
 crate::impl_debug_no_guard!(NodeLet);
 impl<'a> fmt::Debug for FmtGuard<'a, NodeLet> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let indent = self.indent();
+        crate::fmt::fmt_doc_comment(f, &indent, self.description.as_deref())?;
         write!(f, "{}let ", indent)?;
 
         if let Some(shortcut) = &self.shortcut {
@@ -193,19 +287,24 @@ impl<'a> fmt::Debug for FmtGuard<'a, NodeLet> {
         }
         write!(f, "{} = {:?}", &self.name, &self.ty)?;
 
-        if self.ty != LetType::Dim {
+        if !self.ty.is_dim() {
             match &self.value {
+                // the enum variants are not quoted
+                Some(Value::String(value)) if self.ty.is_enum() => write!(f, " {}", value)?,
                 Some(value) => write!(f, " {:?}", value)?,
                 None => write!(f, " *")?,
             }
         }
+        if let Some(range) = &self.range {
+            write!(f, " {:?}", range)?;
+        }
         writeln!(f)
     }
 }
 
-pub type Keywords = BTreeMap<String, Value>;
+pub type Keywords = IndexMap<String, Value>;
 
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub enum Value {
     Bool(bool),
     UInt(u64),
@@ -398,7 +497,7 @@ impl Value {
     pub fn try_as_dim(&self) -> Option<&RefVariable> {
         match self {
             Self::Variable(var) => {
-                if var.borrow().ty == Some(LetType::Dim) {
+                if var.borrow().is_dim() {
                     Some(var)
                 } else {
                     None
@@ -449,7 +548,7 @@ impl fmt::Debug for Value {
     }
 }
 
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Expr {
     pub op: Operator,
     pub lhs: Value,
@@ -512,7 +611,7 @@ impl ops::Neg for Value {
     fn neg(self) -> Self::Output {
         match self {
             Self::Bool(value) => Self::Int(-(value as i64)),
-            Self::UInt(value) => Self::Int(value as i64),
+            Self::UInt(value) => Self::Int(-(value as i64)),
             Self::Int(value) => Self::Int(-value),
             Self::Real(value) => Self::Real(-value),
             _ => Expr {
@@ -649,15 +748,8 @@ impl PartialEq for Value {
             (Self::Variable(lhs), Self::Variable(rhs)) => lhs.eq(rhs),
             (Self::List(lhs), Self::List(rhs)) => lhs.eq(rhs),
             (Self::Map(lhs), Self::Map(rhs)) => lhs.eq(rhs),
-            _ => {
-                if self.is_numeric() && other.is_numeric() {
-                    // TODO: FUTURE: implement comparing hinted values
-                    println!("warning: comparing hinted values is not supported yet!");
-                    true
-                } else {
-                    false
-                }
-            }
+            // compare the hinted values
+            _ => self.eq_symbolic(other),
         }
     }
 }
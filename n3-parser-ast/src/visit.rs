@@ -0,0 +1,157 @@
+//! Traverse the AST by reference.
+//!
+//! Each method of `Visit` walks into the children by default.
+//! Override it to inspect the node, and call the free function of the same name
+//! to keep walking into the children.
+
+use super::file::File;
+use super::graph::{GraphCall, GraphInputs, GraphNode, Shape, Shapes};
+use super::node::{Node, With};
+use super::uses::Use;
+use super::variable::{Expr, NodeLet, RefVariable, Value};
+
+pub trait Visit {
+    fn visit_file(&mut self, file: &File) {
+        visit_file(self, file)
+    }
+
+    fn visit_use(&mut self, _use: &Use) {}
+
+    fn visit_node(&mut self, node: &Node) {
+        visit_node(self, node)
+    }
+
+    fn visit_node_let(&mut self, node_let: &NodeLet) {
+        visit_node_let(self, node_let)
+    }
+
+    fn visit_with(&mut self, with: &With) {
+        visit_with(self, with)
+    }
+
+    fn visit_graph_node(&mut self, graph_node: &GraphNode) {
+        visit_graph_node(self, graph_node)
+    }
+
+    fn visit_graph_call(&mut self, call: &GraphCall) {
+        visit_graph_call(self, call)
+    }
+
+    fn visit_graph_inputs(&mut self, _inputs: &GraphInputs) {}
+
+    fn visit_shapes(&mut self, shapes: &Shapes) {
+        visit_shapes(self, shapes)
+    }
+
+    fn visit_shape(&mut self, shape: &Shape) {
+        visit_shape(self, shape)
+    }
+
+    fn visit_value(&mut self, value: &Value) {
+        visit_value(self, value)
+    }
+
+    fn visit_expr(&mut self, expr: &Expr) {
+        visit_expr(self, expr)
+    }
+
+    /// The variables may be shared, so their values are not visited by default.
+    fn visit_variable(&mut self, _var: &RefVariable) {}
+}
+
+pub fn visit_file<V: Visit + ?Sized>(v: &mut V, file: &File) {
+    for u in file.uses.values() {
+        v.visit_use(u);
+    }
+    v.visit_node(&file.node);
+}
+
+pub fn visit_node<V: Visit + ?Sized>(v: &mut V, node: &Node) {
+    for node_let in node.graph.values() {
+        v.visit_node_let(node_let);
+    }
+    for value in node.cost.values() {
+        v.visit_value(value);
+    }
+    for with in node.withs.values() {
+        v.visit_with(with);
+    }
+    for child in node.children.values() {
+        v.visit_node(child);
+    }
+    for graph_node in node.tensor_graph.values() {
+        v.visit_graph_node(graph_node);
+    }
+}
+
+pub fn visit_node_let<V: Visit + ?Sized>(v: &mut V, node_let: &NodeLet) {
+    if let Some(value) = &node_let.value {
+        v.visit_value(value);
+    }
+}
+
+pub fn visit_with<V: Visit + ?Sized>(v: &mut V, with: &With) {
+    for value in with.graph.values() {
+        v.visit_value(value);
+    }
+}
+
+pub fn visit_graph_node<V: Visit + ?Sized>(v: &mut V, graph_node: &GraphNode) {
+    for call in &graph_node.calls {
+        v.visit_graph_call(call);
+    }
+    if let Some(shapes) = &graph_node.shapes {
+        v.visit_shapes(shapes);
+    }
+}
+
+pub fn visit_graph_call<V: Visit + ?Sized>(v: &mut V, call: &GraphCall) {
+    if let Some(inputs) = &call.inputs {
+        v.visit_graph_inputs(inputs);
+    }
+    if let Some(args) = &call.args {
+        for value in args.values() {
+            v.visit_value(value);
+        }
+    }
+    if let Some(repeat) = &call.repeat {
+        v.visit_value(repeat);
+    }
+}
+
+pub fn visit_shapes<V: Visit + ?Sized>(v: &mut V, shapes: &Shapes) {
+    for shape in shapes.0.borrow().values().flatten() {
+        v.visit_shape(shape);
+    }
+}
+
+pub fn visit_shape<V: Visit + ?Sized>(v: &mut V, shape: &Shape) {
+    for dim in &shape.0 {
+        v.visit_value(dim);
+    }
+}
+
+pub fn visit_value<V: Visit + ?Sized>(v: &mut V, value: &Value) {
+    match value {
+        Value::Variable(var) => v.visit_variable(var),
+        Value::Expr(expr) => v.visit_expr(expr),
+        Value::List(list) => {
+            for value in list {
+                v.visit_value(value);
+            }
+        }
+        Value::Map(map) => {
+            for value in map.values().flatten() {
+                v.visit_value(value);
+            }
+        }
+        _ => {}
+    }
+}
+
+pub fn visit_expr<V: Visit + ?Sized>(v: &mut V, expr: &Expr) {
+    v.visit_value(&expr.lhs);
+    if let Some(rhs) = &expr.rhs {
+        v.visit_value(rhs);
+    }
+}
@@ -0,0 +1,157 @@
+//! Traverse the AST by mutable reference.
+//!
+//! Each method of `VisitMut` walks into the children by default.
+//! Override it to modify the node in place, and call the free function of the same name
+//! to keep walking into the children.
+
+use super::file::File;
+use super::graph::{GraphCall, GraphInputs, GraphNode, Shape, Shapes};
+use super::node::{Node, With};
+use super::uses::Use;
+use super::variable::{Expr, NodeLet, RefVariable, Value};
+
+pub trait VisitMut {
+    fn visit_file_mut(&mut self, file: &mut File) {
+        visit_file_mut(self, file)
+    }
+
+    fn visit_use_mut(&mut self, _use: &mut Use) {}
+
+    fn visit_node_mut(&mut self, node: &mut Node) {
+        visit_node_mut(self, node)
+    }
+
+    fn visit_node_let_mut(&mut self, node_let: &mut NodeLet) {
+        visit_node_let_mut(self, node_let)
+    }
+
+    fn visit_with_mut(&mut self, with: &mut With) {
+        visit_with_mut(self, with)
+    }
+
+    fn visit_graph_node_mut(&mut self, graph_node: &mut GraphNode) {
+        visit_graph_node_mut(self, graph_node)
+    }
+
+    fn visit_graph_call_mut(&mut self, call: &mut GraphCall) {
+        visit_graph_call_mut(self, call)
+    }
+
+    fn visit_graph_inputs_mut(&mut self, _inputs: &mut GraphInputs) {}
+
+    fn visit_shapes_mut(&mut self, shapes: &mut Shapes) {
+        visit_shapes_mut(self, shapes)
+    }
+
+    fn visit_shape_mut(&mut self, shape: &mut Shape) {
+        visit_shape_mut(self, shape)
+    }
+
+    fn visit_value_mut(&mut self, value: &mut Value) {
+        visit_value_mut(self, value)
+    }
+
+    fn visit_expr_mut(&mut self, expr: &mut Expr) {
+        visit_expr_mut(self, expr)
+    }
+
+    /// The variables may be shared, so their values are not visited by default.
+    fn visit_variable_mut(&mut self, _var: &mut RefVariable) {}
+}
+
+pub fn visit_file_mut<V: VisitMut + ?Sized>(v: &mut V, file: &mut File) {
+    for u in file.uses.values_mut() {
+        v.visit_use_mut(u);
+    }
+    v.visit_node_mut(&mut file.node);
+}
+
+pub fn visit_node_mut<V: VisitMut + ?Sized>(v: &mut V, node: &mut Node) {
+    for node_let in node.graph.values_mut() {
+        v.visit_node_let_mut(node_let);
+    }
+    for value in node.cost.values_mut() {
+        v.visit_value_mut(value);
+    }
+    for with in node.withs.values_mut() {
+        v.visit_with_mut(with);
+    }
+    for child in node.children.values_mut() {
+        v.visit_node_mut(child);
+    }
+    for graph_node in node.tensor_graph.values_mut() {
+        v.visit_graph_node_mut(graph_node);
+    }
+}
+
+pub fn visit_node_let_mut<V: VisitMut + ?Sized>(v: &mut V, node_let: &mut NodeLet) {
+    if let Some(value) = &mut node_let.value {
+        v.visit_value_mut(value);
+    }
+}
+
+pub fn visit_with_mut<V: VisitMut + ?Sized>(v: &mut V, with: &mut With) {
+    for value in with.graph.values_mut() {
+        v.visit_value_mut(value);
+    }
+}
+
+pub fn visit_graph_node_mut<V: VisitMut + ?Sized>(v: &mut V, graph_node: &mut GraphNode) {
+    for call in &mut graph_node.calls {
+        v.visit_graph_call_mut(call);
+    }
+    if let Some(shapes) = &mut graph_node.shapes {
+        v.visit_shapes_mut(shapes);
+    }
+}
+
+pub fn visit_graph_call_mut<V: VisitMut + ?Sized>(v: &mut V, call: &mut GraphCall) {
+    if let Some(inputs) = &mut call.inputs {
+        v.visit_graph_inputs_mut(inputs);
+    }
+    if let Some(args) = &mut call.args {
+        for value in args.values_mut() {
+            v.visit_value_mut(value);
+        }
+    }
+    if let Some(repeat) = &mut call.repeat {
+        v.visit_value_mut(repeat);
+    }
+}
+
+pub fn visit_shapes_mut<V: VisitMut + ?Sized>(v: &mut V, shapes: &mut Shapes) {
+    for shape in shapes.0.get_mut().values_mut().flatten() {
+        v.visit_shape_mut(shape);
+    }
+}
+
+pub fn visit_shape_mut<V: VisitMut + ?Sized>(v: &mut V, shape: &mut Shape) {
+    for dim in &mut shape.0 {
+        v.visit_value_mut(dim);
+    }
+}
+
+pub fn visit_value_mut<V: VisitMut + ?Sized>(v: &mut V, value: &mut Value) {
+    match value {
+        Value::Variable(var) => v.visit_variable_mut(var),
+        Value::Expr(expr) => v.visit_expr_mut(expr),
+        Value::List(list) => {
+            for value in list {
+                v.visit_value_mut(value);
+            }
+        }
+        Value::Map(map) => {
+            for value in map.values_mut().flatten() {
+                v.visit_value_mut(value);
+            }
+        }
+        _ => {}
+    }
+}
+
+pub fn visit_expr_mut<V: VisitMut + ?Sized>(v: &mut V, expr: &mut Expr) {
+    v.visit_value_mut(&mut expr.lhs);
+    if let Some(rhs) = &mut expr.rhs {
+        v.visit_value_mut(rhs);
+    }
+}
@@ -0,0 +1,272 @@
+//! A concrete syntax tree, which is nested by the indented blocks.
+//! Unlike the AST, it keeps the comments and the order of the declarations.
+
+use crate::error::{LexicalErrorType, ParseError, ParseErrorType};
+use crate::location::Location;
+
+const TAB_SIZE: usize = 8;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Cst {
+    pub items: Vec<Item>,
+    /// The comments and the blank lines at the end of the file
+    pub trailing: Vec<Trivia>,
+}
+
+/// A line of code, with the comments above it and the indented block below it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Item {
+    pub leading: Vec<Trivia>,
+    pub line: Line,
+    pub children: Vec<Item>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Trivia {
+    Blank,
+    /// The whole-line comment, including '#' (or '##')
+    Comment(String),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Line {
+    pub kind: LineKind,
+    /// The trailing comment, including '#'
+    pub comment: Option<String>,
+    /// The following lines which are continued by the brackets
+    pub continuation: Vec<String>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum LineKind {
+    Let {
+        lhs: String,
+        rhs: String,
+    },
+    /// The graph nodes and their shapes
+    Graph {
+        lhs: String,
+        rhs: Option<String>,
+    },
+    Other(String),
+}
+
+impl Cst {
+    pub fn parse(source: &str) -> Result<Self, ParseError> {
+        // the code lines with their depths, and the comments above them
+        let mut lines: Vec<(usize, Item)> = vec![];
+        let mut trivia = vec![];
+        let mut indents: Vec<usize> = vec![];
+        let mut nesting = 0;
+        let mut continued = false;
+
+        for (row, raw) in source.lines().enumerate() {
+            let raw = raw.trim_end();
+            let content = raw.trim_start();
+
+            // continued lines
+            if nesting > 0 || continued {
+                scan(content, &mut nesting);
+                continued = content.ends_with('\\');
+                if let Some((_, last)) = lines.last_mut() {
+                    last.line.continuation.push(content.to_string());
+                }
+                continue;
+            }
+
+            if content.is_empty() {
+                trivia.push(Trivia::Blank);
+                continue;
+            }
+            if content.starts_with('#') {
+                trivia.push(Trivia::Comment(content.to_string()));
+                continue;
+            }
+
+            // Detect indentation levels
+            let width = raw[..raw.len() - content.len()]
+                .chars()
+                .map(|c| if c == '\t' { TAB_SIZE } else { 1 })
+                .sum();
+            let mut dedented = false;
+            while indents.last().is_some_and(|last| width < *last) {
+                indents.pop();
+                dedented = true;
+            }
+            match indents.last() {
+                Some(last) if *last == width => {}
+                // the dedented lines should match one of the outer levels
+                Some(_) if dedented => {
+                    return Err(ParseError {
+                        error: ParseErrorType::Lexical(LexicalErrorType::IndentationError),
+                        location: Location::new(row + 1, width + 1),
+                    });
+                }
+                _ => indents.push(width),
+            }
+
+            let (code, comment) = match scan(content, &mut nesting) {
+                Some(at) => (content[..at].trim_end(), Some(content[at..].to_string())),
+                None => (content, None),
+            };
+            continued = code.ends_with('\\');
+
+            let item = Item {
+                leading: std::mem::take(&mut trivia),
+                line: Line {
+                    kind: LineKind::parse(code),
+                    comment,
+                    continuation: vec![],
+                },
+                children: vec![],
+            };
+            lines.push((indents.len() - 1, item));
+        }
+
+        Ok(Self {
+            items: Item::nest(&mut lines.into_iter().peekable(), 0),
+            trailing: trivia,
+        })
+    }
+}
+
+impl Item {
+    /// Collect the items of the given depth, with their children.
+    fn nest<I>(lines: &mut std::iter::Peekable<I>, depth: usize) -> Vec<Self>
+    where
+        I: Iterator<Item = (usize, Self)>,
+    {
+        let mut items: Vec<Self> = vec![];
+        while let Some((_, item)) = lines.next_if(|(d, _)| *d == depth) {
+            items.push(item);
+            if lines.peek().is_some_and(|(d, _)| *d > depth) {
+                let children = Self::nest(lines, depth + 1);
+                items.last_mut().unwrap().children = children;
+            }
+        }
+        items
+    }
+}
+
+impl LineKind {
+    fn parse(code: &str) -> Self {
+        let assign = find_assign(code).map(|at| (&code[..at], &code[at + 1..]));
+
+        if code.starts_with("let ") {
+            if let Some((lhs, rhs)) = assign {
+                return Self::Let {
+                    lhs: collapse(lhs),
+                    rhs: collapse(rhs),
+                };
+            }
+        } else if is_graph_node(code) || (assign.is_some() && !code.starts_with("set ")) {
+            return match assign {
+                // the shapes are aligned by the users
+                Some((lhs, rhs)) => Self::Graph {
+                    lhs: collapse(lhs),
+                    rhs: Some(strip_one_space(rhs.trim_end()).to_string()),
+                },
+                None => Self::Graph {
+                    lhs: collapse(code),
+                    rhs: None,
+                },
+            };
+        }
+        Self::Other(collapse(code))
+    }
+}
+
+fn is_graph_node(code: &str) -> bool {
+    let digits = code.chars().take_while(|c| c.is_ascii_digit()).count();
+    digits > 0 && code[digits..].starts_with('.')
+}
+
+/// Find the top-level '=', which is not a part of the comparison operators.
+fn find_assign(code: &str) -> Option<usize> {
+    let mut nesting = 0;
+    let mut quote = None;
+    let mut last = None;
+    for (at, c) in code.char_indices() {
+        match quote {
+            Some(q) => {
+                if c == q {
+                    quote = None;
+                }
+            }
+            None => match c {
+                '"' | '\'' => quote = Some(c),
+                '(' | '[' | '{' => nesting += 1,
+                ')' | ']' | '}' => nesting -= 1,
+                '=' if nesting == 0 && !matches!(last, Some('<') | Some('>')) => return Some(at),
+                _ => {}
+            },
+        }
+        last = Some(c);
+    }
+    None
+}
+
+/// Update the nesting level, and return the position of the comment if exists.
+fn scan(code: &str, nesting: &mut usize) -> Option<usize> {
+    let mut quote = None;
+    let mut escaped = false;
+    for (at, c) in code.char_indices() {
+        match quote {
+            Some(q) => {
+                if escaped {
+                    escaped = false;
+                } else if c == '\\' {
+                    escaped = true;
+                } else if c == q {
+                    quote = None;
+                }
+            }
+            None => match c {
+                '"' | '\'' => quote = Some(c),
+                '(' | '[' | '{' => *nesting += 1,
+                ')' | ']' | '}' => *nesting = nesting.saturating_sub(1),
+                '#' => return Some(at),
+                _ => {}
+            },
+        }
+    }
+    None
+}
+
+/// Strip the separating whitespace only, so that the remaining alignment is preserved.
+fn strip_one_space(code: &str) -> &str {
+    let mut chars = code.chars();
+    match chars.next() {
+        Some(c) if c.is_whitespace() => chars.as_str(),
+        _ => code,
+    }
+}
+
+/// Collapse the whitespaces outside of the strings.
+fn collapse(code: &str) -> String {
+    let mut result = String::with_capacity(code.len());
+    let mut quote = None;
+    for c in code.trim().chars() {
+        match quote {
+            Some(q) => {
+                if c == q {
+                    quote = None;
+                }
+                result.push(c);
+            }
+            None => {
+                if c == '"' || c == '\'' {
+                    quote = Some(c);
+                }
+                if c.is_whitespace() {
+                    if !result.ends_with(' ') {
+                        result.push(' ');
+                    }
+                } else {
+                    result.push(c);
+                }
+            }
+        }
+    }
+    result
+}
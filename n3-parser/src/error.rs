@@ -205,8 +205,100 @@ impl fmt::Display for ParseErrorType {
     }
 }
 
+impl ParseError {
+    /// Get the stable code of the error, which is explained by [`explain`].
+    pub fn code(&self) -> &'static str {
+        self.error.code()
+    }
+}
+
+impl ParseErrorType {
+    pub fn code(&self) -> &'static str {
+        match self {
+            ParseErrorType::EOF => "N3-E0001",
+            ParseErrorType::ExtraToken(_) => "N3-E0002",
+            ParseErrorType::InvalidToken => "N3-E0003",
+            ParseErrorType::UnrecognizedToken(_, _) => "N3-E0004",
+            ParseErrorType::Lexical(error) => error.code(),
+            ParseErrorType::OtherError(_) => "N3-E0005",
+        }
+    }
+}
+
+impl LexicalErrorType {
+    pub fn code(&self) -> &'static str {
+        match self {
+            LexicalErrorType::StringError => "N3-E0011",
+            LexicalErrorType::UnicodeError => "N3-E0012",
+            LexicalErrorType::NestingError => "N3-E0013",
+            LexicalErrorType::IndentationError => "N3-E0014",
+            LexicalErrorType::TabError => "N3-E0015",
+            LexicalErrorType::DefaultArgumentError => "N3-E0016",
+            LexicalErrorType::PositionalArgumentError => "N3-E0017",
+            LexicalErrorType::DuplicateKeywordArgumentError => "N3-E0018",
+            LexicalErrorType::UnrecognizedToken { .. } => "N3-E0019",
+            LexicalErrorType::FStringError(_) => "N3-E0020",
+            LexicalErrorType::OtherError(_) => "N3-E0021",
+        }
+    }
+}
+
+/// The long-form explanations of the error codes, with the examples.
+pub static EXPLANATIONS: &[(&str, &str)] = &[
+    ("N3-E0001", include_str!("../errors/N3-E0001.md")),
+    ("N3-E0002", include_str!("../errors/N3-E0002.md")),
+    ("N3-E0003", include_str!("../errors/N3-E0003.md")),
+    ("N3-E0004", include_str!("../errors/N3-E0004.md")),
+    ("N3-E0005", include_str!("../errors/N3-E0005.md")),
+    ("N3-E0011", include_str!("../errors/N3-E0011.md")),
+    ("N3-E0012", include_str!("../errors/N3-E0012.md")),
+    ("N3-E0013", include_str!("../errors/N3-E0013.md")),
+    ("N3-E0014", include_str!("../errors/N3-E0014.md")),
+    ("N3-E0015", include_str!("../errors/N3-E0015.md")),
+    ("N3-E0016", include_str!("../errors/N3-E0016.md")),
+    ("N3-E0017", include_str!("../errors/N3-E0017.md")),
+    ("N3-E0018", include_str!("../errors/N3-E0018.md")),
+    ("N3-E0019", include_str!("../errors/N3-E0019.md")),
+    ("N3-E0020", include_str!("../errors/N3-E0020.md")),
+    ("N3-E0021", include_str!("../errors/N3-E0021.md")),
+];
+
+/// Get the long-form explanation of the error code.
+pub fn explain(code: &str) -> Option<&'static str> {
+    EXPLANATIONS
+        .iter()
+        .find(|(k, _)| *k == code)
+        .map(|(_, v)| *v)
+}
+
 impl Error for ParseError {
     fn source(&self) -> Option<&(dyn Error + 'static)> {
         None
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Parser;
+
+    #[test]
+    fn test_explanations() {
+        let codes: Vec<_> = EXPLANATIONS.iter().map(|(k, _)| *k).collect();
+        let mut sorted = codes.clone();
+        sorted.sort_unstable();
+        sorted.dedup();
+        assert_eq!(codes, sorted);
+
+        for source in &[
+            "node MyNode:\n",
+            "node MyNode\n    0. Input = 3\n",
+            "node MyNode:\n    let name = \"my node\n",
+            "node MyNode:\n    0. Input = 3\n  1. Linear = 10\n",
+            "node MyNode:\n    0. Input = 3 ?\n",
+        ] {
+            let error = Parser::default().parse_file(source).unwrap_err();
+            assert!(explain(error.code()).is_some(), "{}", error.code());
+        }
+    }
+}
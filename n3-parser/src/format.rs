@@ -0,0 +1,266 @@
+use std::fmt::Write;
+
+use crate::cst::{Cst, Item, Line, LineKind, Trivia};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct FormatConfig {
+    pub indent_width: usize,
+    /// Align the '=' columns of the graph nodes
+    pub align_graph: bool,
+    pub max_blank_lines: usize,
+}
+
+impl Default for FormatConfig {
+    fn default() -> Self {
+        Self {
+            indent_width: 4,
+            align_graph: true,
+            max_blank_lines: 1,
+        }
+    }
+}
+
+impl Cst {
+    pub fn format(&self, config: &FormatConfig) -> String {
+        let mut f = Formatter {
+            config,
+            source: String::new(),
+            blanks: 0,
+        };
+        f.fmt_items(&self.items, 0);
+        // the trailing comments are not indented
+        f.fmt_trivia(&self.trailing, 0);
+
+        // skip the trailing blank lines
+        let mut source = f.source;
+        while source.ends_with("\n\n") {
+            source.pop();
+        }
+        source
+    }
+}
+
+struct Formatter<'a> {
+    config: &'a FormatConfig,
+    source: String,
+    /// The number of the adjacent blank lines
+    blanks: usize,
+}
+
+impl<'a> Formatter<'a> {
+    fn fmt_items(&mut self, items: &[Item], depth: usize) {
+        let widths = get_graph_widths(items, self.config);
+
+        for (item, width) in items.iter().zip(widths) {
+            // the comments take the indentation of the following code
+            self.fmt_trivia(&item.leading, depth);
+            self.fmt_line(&item.line, depth, width);
+            self.fmt_items(&item.children, depth + 1);
+        }
+    }
+
+    fn fmt_trivia(&mut self, trivia: &[Trivia], depth: usize) {
+        for trivia in trivia {
+            match trivia {
+                Trivia::Blank => {
+                    // skip the leading blank lines
+                    if !self.source.is_empty() && self.blanks < self.config.max_blank_lines {
+                        self.source.push('\n');
+                    }
+                    self.blanks += 1;
+                }
+                Trivia::Comment(comment) => {
+                    self.push_indent(depth);
+                    self.source.push_str(comment);
+                    self.source.push('\n');
+                    self.blanks = 0;
+                }
+            }
+        }
+    }
+
+    fn fmt_line(&mut self, line: &Line, depth: usize, width: usize) {
+        self.push_indent(depth);
+        line.fmt_code(&mut self.source, width).unwrap();
+
+        if let Some(comment) = &line.comment {
+            self.source.push_str("  ");
+            self.source.push_str(comment);
+        }
+        self.source.push('\n');
+
+        for continuation in &line.continuation {
+            if !continuation.is_empty() {
+                self.push_indent(depth + 1);
+                self.source.push_str(continuation);
+            }
+            self.source.push('\n');
+        }
+        self.blanks = 0;
+    }
+
+    fn push_indent(&mut self, depth: usize) {
+        let width = depth * self.config.indent_width;
+        self.source.push_str(&" ".repeat(width));
+    }
+}
+
+/// Get the widths of the left sides of the graph nodes, grouped by the adjacent siblings.
+fn get_graph_widths(items: &[Item], config: &FormatConfig) -> Vec<usize> {
+    let mut widths = vec![0; items.len()];
+    if !config.align_graph {
+        return widths;
+    }
+
+    let mut begin = 0;
+    for end in 1..=items.len() {
+        let last = &items[end - 1];
+        let is_same_group = match items.get(end) {
+            Some(item) => {
+                last.line.is_graph_with_shapes()
+                    && item.line.is_graph_with_shapes()
+                    && last.line.continuation.is_empty()
+                    && last.children.is_empty()
+                    && item.leading.is_empty()
+            }
+            None => false,
+        };
+        if is_same_group {
+            continue;
+        }
+
+        let group = &items[begin..end];
+        let width = group.iter().map(|x| x.line.lhs_width()).max().unwrap_or(0);
+        widths[begin..end].iter_mut().for_each(|x| *x = width);
+        begin = end;
+    }
+    widths
+}
+
+impl Line {
+    fn is_graph_with_shapes(&self) -> bool {
+        matches!(&self.kind, LineKind::Graph { rhs: Some(_), .. })
+    }
+
+    fn lhs_width(&self) -> usize {
+        match &self.kind {
+            LineKind::Graph { lhs, .. } => lhs.chars().count(),
+            _ => 0,
+        }
+    }
+
+    fn fmt_code(&self, f: &mut String, width: usize) -> std::fmt::Result {
+        match &self.kind {
+            LineKind::Let { lhs, rhs } => write!(f, "{} = {}", lhs, rhs),
+            LineKind::Graph {
+                lhs,
+                rhs: Some(rhs),
+            } => write!(f, "{:width$} = {}", lhs, rhs, width = width),
+            LineKind::Graph { lhs, rhs: None } => f.write_str(lhs),
+            LineKind::Other(code) => f.write_str(code),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Parser;
+
+    #[test]
+    fn test_format() {
+        let source = "
+
+use MyModel   by author
+## My own node.
+node  MyNode:
+  let K:  kernel size = int  3   # the kernel size
+  let W: width = dim
+  let H: height = dim
+
+
+  # the inner node
+  node MyConv:
+      1.  Conv2D (K=K) + Relu
+  0. Input = 3, W  , H
+  1. MyConv    = 32, W/2, H/2
+  2. Linear(Oc=1024) + Concat[x$0, x$]    = 2048
+";
+        let expected = "use MyModel by author
+## My own node.
+node MyNode:
+    let K: kernel size = int 3  # the kernel size
+    let W: width = dim
+    let H: height = dim
+
+    # the inner node
+    node MyConv:
+        1. Conv2D (K=K) + Relu
+    0. Input                             = 3, W  , H
+    1. MyConv                            = 32, W/2, H/2
+    2. Linear(Oc=1024) + Concat[x$0, x$] = 2048
+";
+        let parser = Parser::default();
+        let config = FormatConfig::default();
+
+        let formatted = parser.format_file(source, &config).unwrap();
+        assert_eq!(formatted, expected);
+
+        // the formatter should be stable
+        let formatted = parser.format_file(&formatted, &config).unwrap();
+        assert_eq!(formatted, expected);
+    }
+
+    #[test]
+    fn test_format_config() {
+        let source = "node MyNode:
+    let W: width = dim
+
+
+    # the graph
+    0. Input = 3, W
+    1. Linear(Oc=8) = 8
+";
+        let expected = "node MyNode:
+  let W: width = dim
+
+
+  # the graph
+  0. Input = 3, W
+  1. Linear(Oc=8) = 8
+";
+        let parser = Parser::default();
+        let config = FormatConfig {
+            indent_width: 2,
+            align_graph: false,
+            max_blank_lines: 2,
+        };
+
+        let formatted = parser.format_file(source, &config).unwrap();
+        assert_eq!(formatted, expected);
+    }
+
+    #[test]
+    fn test_cst_blocks() {
+        let source = "node MyNode:
+    node MyConv:
+        1. Conv2D + Relu
+    # the graph
+    0. Input = 3, W
+  1. Linear = 8
+";
+        assert!(Cst::parse(source).is_err());
+
+        let source = &source.replace("  1.", "    1.");
+        let cst = Cst::parse(source).unwrap();
+        assert_eq!(cst.items.len(), 1);
+
+        let children = &cst.items[0].children;
+        assert_eq!(children.len(), 3);
+        assert_eq!(children[0].children.len(), 1);
+        assert_eq!(
+            children[1].leading,
+            [Trivia::Comment("# the graph".to_string())],
+        );
+    }
+}
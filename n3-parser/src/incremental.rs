@@ -0,0 +1,388 @@
+//! An editable source, which re-parses the edited node blocks only.
+//!
+//! The source is split into the blocks by the indentation:
+//! each direct child node of the root is a block, and the others are the head.
+//! The parsed blocks are reused while their texts are not changed.
+
+use crate::ast::fold::DeepCopy;
+use crate::ast::{self, Fold};
+use crate::error::ParseError;
+use crate::location::Location;
+use crate::parser::Parser;
+
+/// A position of the source, by the 0-based line and character indices.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Position {
+    pub line: usize,
+    pub column: usize,
+}
+
+/// Replace the text between the positions.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TextEdit {
+    pub start: Position,
+    pub end: Position,
+    pub text: String,
+}
+
+pub struct Document {
+    parser: Parser,
+    text: String,
+    head: Block<ast::File>,
+    children: Vec<Block<ast::Node>>,
+}
+
+struct Block<T> {
+    /// The dedented source
+    source: String,
+    /// The original row of each line
+    rows: Vec<usize>,
+    /// The number of the removed leading characters
+    indent: usize,
+    /// The parsed block, whose locations are relative to the block
+    parsed: Result<T, ParseError>,
+}
+
+impl Document {
+    pub fn new(text: String) -> Self {
+        let parser = Parser::default();
+        let (head, children) = split(&text);
+
+        Self {
+            head: head.parse(&parser, |x| x),
+            children: children
+                .into_iter()
+                .map(|x| x.parse(&parser, |x| x.node))
+                .collect(),
+            parser,
+            text,
+        }
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Apply the edits in order, and re-parse the changed blocks.
+    /// Returns the number of the re-parsed blocks.
+    pub fn apply_edits(&mut self, edits: &[TextEdit]) -> usize {
+        for edit in edits {
+            let start = offset_of(&self.text, edit.start);
+            let end = offset_of(&self.text, edit.end).max(start);
+            self.text.replace_range(start..end, &edit.text);
+        }
+        self.update()
+    }
+
+    /// Replace the whole text, and re-parse the changed blocks.
+    /// Returns the number of the re-parsed blocks.
+    pub fn set_text(&mut self, text: String) -> usize {
+        self.text = text;
+        self.update()
+    }
+
+    fn update(&mut self) -> usize {
+        let (head, children) = split(&self.text);
+        let mut num_parsed = 0;
+
+        if head.source == self.head.source {
+            self.head.rows = head.rows;
+        } else {
+            self.head = head.parse(&self.parser, |x| x);
+            num_parsed += 1;
+        }
+
+        let mut old_children = std::mem::take(&mut self.children);
+        for child in children {
+            match old_children.iter().position(|x| x.source == child.source) {
+                Some(at) => {
+                    let old = old_children.remove(at);
+                    self.children.push(Block {
+                        source: child.source,
+                        rows: child.rows,
+                        indent: child.indent,
+                        parsed: old.parsed,
+                    });
+                }
+                None => {
+                    self.children.push(child.parse(&self.parser, |x| x.node));
+                    num_parsed += 1;
+                }
+            }
+        }
+        num_parsed
+    }
+
+    /// Get the parsed file, or the first error.
+    ///
+    /// Each call returns the new AST, so that the variables are not shared.
+    pub fn file(&self) -> Result<ast::File, ParseError> {
+        let errors = self
+            .children
+            .iter()
+            .filter_map(|x| x.error())
+            .chain(self.head.error());
+        if let Some(error) = errors.min_by_key(|x| x.location.row()) {
+            return Err(error);
+        }
+
+        let mut file = DeepCopy.fold_file(self.head.parsed.clone().unwrap());
+        for child in &self.children {
+            let node = DeepCopy.fold_node(child.parsed.clone().unwrap());
+            file.node.children.insert(node.name.clone(), node);
+        }
+        Ok(file)
+    }
+}
+
+impl Block<()> {
+    fn parse<T>(self, parser: &Parser, f: impl FnOnce(ast::File) -> T) -> Block<T> {
+        Block {
+            parsed: parser.parse_file(&self.source).map(f),
+            source: self.source,
+            rows: self.rows,
+            indent: self.indent,
+        }
+    }
+}
+
+impl<T> Block<T> {
+    /// Get the error, whose location is moved to the original source.
+    fn error(&self) -> Option<ParseError> {
+        let error = self.parsed.as_ref().err()?;
+        let location = &error.location;
+
+        // note: the location of EOF may be the next line
+        let row = match location.row().checked_sub(1) {
+            Some(row) => self
+                .rows
+                .get(row)
+                .or_else(|| self.rows.last())
+                .map(|x| x + 1)
+                .unwrap_or_default(),
+            None => 0,
+        };
+        let column = match location.column() {
+            0 => 0,
+            column => column + self.indent,
+        };
+
+        Some(ParseError {
+            error: error.error.clone(),
+            location: Location::new(row, column),
+        })
+    }
+}
+
+/// Split the text into the head and the direct child nodes of the root.
+fn split(text: &str) -> (Block<()>, Vec<Block<()>>) {
+    let lines: Vec<_> = text.lines().collect();
+    let is_code = |line: &str| {
+        let line = line.trim_start();
+        !line.is_empty() && !line.starts_with('#')
+    };
+
+    // the indentation of the root's body
+    let body = lines
+        .iter()
+        .filter(|x| is_code(x))
+        .skip_while(|x| x.starts_with("use "))
+        .nth(1)
+        .map(|x| prefix_of(x))
+        .filter(|x| !x.is_empty());
+
+    let mut owned = vec![false; lines.len()];
+    let mut children = vec![];
+    if let Some(body) = body {
+        let mut row = 0;
+        while row < lines.len() {
+            let line = lines[row];
+
+            // the graph should follow the child nodes
+            let code = line.trim_start();
+            if prefix_of(line) == body && code.starts_with(|c: char| c.is_ascii_digit()) {
+                break;
+            }
+            if !(prefix_of(line) == body && code.starts_with("node ")) {
+                row += 1;
+                continue;
+            }
+
+            // the preceding comments, including the doc comments
+            let mut start = row;
+            while start > 0
+                && prefix_of(lines[start - 1]) == body
+                && lines[start - 1].trim_start().starts_with('#')
+                && !owned[start - 1]
+            {
+                start -= 1;
+            }
+
+            let mut end = row + 1;
+            let mut last = row + 1;
+            while end < lines.len() {
+                let line = lines[end];
+                if is_code(line) {
+                    if prefix_of(line).len() <= body.len() {
+                        break;
+                    }
+                    last = end + 1;
+                }
+                end += 1;
+            }
+
+            for owned in &mut owned[start..last] {
+                *owned = true;
+            }
+            children.push(new_block(&lines, start..last, body));
+            row = last;
+        }
+    }
+
+    let head_rows: Vec<_> = (0..lines.len()).filter(|&x| !owned[x]).collect();
+    let head = Block {
+        source: join(head_rows.iter().map(|&x| lines[x])),
+        rows: head_rows,
+        indent: 0,
+        parsed: Ok(()),
+    };
+    (head, children)
+}
+
+fn prefix_of(line: &str) -> &str {
+    &line[..line.len() - line.trim_start().len()]
+}
+
+fn new_block(lines: &[&str], rows: std::ops::Range<usize>, prefix: &str) -> Block<()> {
+    Block {
+        source: join(
+            lines[rows.clone()]
+                .iter()
+                .map(|x| x.strip_prefix(prefix).unwrap_or_else(|| x.trim_start())),
+        ),
+        rows: rows.collect(),
+        indent: prefix.chars().count(),
+        parsed: Ok(()),
+    }
+}
+
+fn join<'a>(lines: impl Iterator<Item = &'a str>) -> String {
+    let mut source = String::new();
+    for line in lines {
+        source.push_str(line);
+        source.push('\n');
+    }
+    source
+}
+
+/// Get the byte offset of the position, which is clamped into the text.
+fn offset_of(text: &str, position: Position) -> usize {
+    let mut offset = 0;
+    for (line, rest) in text.split('\n').enumerate() {
+        if line == position.line {
+            return offset
+                + rest
+                    .char_indices()
+                    .nth(position.column)
+                    .map(|(at, _)| at)
+                    .unwrap_or_else(|| rest.len());
+        }
+        offset += rest.len() + 1;
+    }
+    text.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = "use Conv2D
+
+node MyNode:
+    let K: kernel size = int 5
+
+    with Conv2D:
+        set kernel size = K
+
+    ## My convolution
+    node MyConv:
+        let K: kernel size = int 3
+
+        1. Conv2D(K=K)
+
+    node MyRelu:
+        1. Relu
+
+    0. Input  = 3, 32, 32
+    1. MyConv = 16, 32, 32
+    2. MyRelu
+";
+
+    fn assert_same(doc: &Document) {
+        let parser = Parser::default();
+        let expected = parser.parse_file(doc.text());
+        let given = doc.file();
+        match (expected, given) {
+            (Ok(expected), Ok(given)) => {
+                assert_eq!(format!("{:?}", expected), format!("{:?}", given))
+            }
+            (Err(expected), Err(given)) => {
+                assert_eq!(expected.location.row(), given.location.row())
+            }
+            (expected, given) => panic!("expected {:?}, but given {:?}", expected, given),
+        }
+    }
+
+    fn edit(line: usize, start: usize, end: usize, text: &str) -> TextEdit {
+        TextEdit {
+            start: Position {
+                line,
+                column: start,
+            },
+            end: Position { line, column: end },
+            text: text.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_split() {
+        let doc = Document::new(SOURCE.to_string());
+        assert_eq!(doc.children.len(), 2);
+        assert_eq!(doc.children[0].rows, (8..13).collect::<Vec<_>>());
+        assert_eq!(doc.children[1].source, "node MyRelu:\n    1. Relu\n");
+        assert_same(&doc);
+
+        let file = doc.file().unwrap();
+        let child = &file.node.children["MyConv"];
+        assert_eq!(child.description.as_deref(), Some("My convolution"));
+    }
+
+    #[test]
+    fn test_apply_edits() {
+        let mut doc = Document::new(SOURCE.to_string());
+
+        // edit a child node
+        assert_eq!(doc.apply_edits(&[edit(10, 33, 34, "7")]), 1);
+        assert_same(&doc);
+        let file = doc.file().unwrap();
+        let child = &file.node.children["MyConv"];
+        assert_eq!(format!("{:?}", child.graph["kernel size"].value), "Some(7)");
+
+        // insert the lines
+        assert_eq!(doc.apply_edits(&[edit(14, 0, 0, "\n# comment\n")]), 1);
+        assert_same(&doc);
+
+        // edit the root node
+        assert_eq!(doc.apply_edits(&[edit(3, 29, 30, "3")]), 1);
+        assert_same(&doc);
+
+        // break a child node
+        assert_eq!(doc.apply_edits(&[edit(17, 8, 12, "")]), 1);
+        assert_same(&doc);
+        assert_eq!(doc.file().unwrap_err().location.row(), 18);
+
+        // restore it
+        assert_eq!(doc.set_text(SOURCE.to_string()), 3);
+        assert_same(&doc);
+    }
+}
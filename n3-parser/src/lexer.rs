@@ -62,6 +62,7 @@ pub struct Lexer<T: Iterator<Item = char>> {
     nesting: usize, // Amount of parenthesis
     indentation_stack: Vec<IndentationLevel>,
     pending: Vec<Spanned>,
+    doc_comment: Option<(Location, Vec<String>, Location)>,
     chr0: Option<char>,
     chr1: Option<char>,
     chr2: Option<char>,
@@ -77,11 +78,15 @@ pub fn get_keywords() -> HashMap<String, Tok> {
 
     keywords.insert(String::from("with"), Tok::WithDef);
     keywords.insert(String::from("set"), Tok::WithSet);
+    keywords.insert(String::from("cost"), Tok::CostDef);
     keywords.insert(String::from("let"), Tok::LetDef);
     keywords.insert(String::from("bool"), Tok::LetBool);
     keywords.insert(String::from("int"), Tok::LetInt);
     keywords.insert(String::from("real"), Tok::LetReal);
     keywords.insert(String::from("dim"), Tok::LetDim);
+    keywords.insert(String::from("dynamic"), Tok::LetDynamic);
+    keywords.insert(String::from("enum"), Tok::LetEnum);
+    keywords.insert(String::from("in"), Tok::LetIn);
     keywords.insert(String::from("node"), Tok::NodeDef);
     keywords.insert(String::from("extern"), Tok::NodeExtern);
     keywords.insert(String::from("data"), Tok::NodeData);
@@ -224,6 +229,7 @@ where
             nesting: 0,
             indentation_stack: vec![Default::default()],
             pending: Vec::new(),
+            doc_comment: None,
             chr0: None,
             location: Location::new(0, 0),
             chr1: None,
@@ -435,6 +441,32 @@ where
         }
     }
 
+    /// Collect the doc comment line, which is attached to the next declaration
+    fn lex_doc_comment(&mut self) {
+        let tok_start = self.get_pos();
+        self.next_char();
+        self.next_char();
+
+        let mut line = String::new();
+        loop {
+            match self.chr0 {
+                Some('\n') | None => break,
+                Some(c) => line.push(c),
+            }
+            self.next_char();
+        }
+        let line = line.trim().to_string();
+        let tok_end = self.get_pos();
+
+        match &mut self.doc_comment {
+            Some((_, lines, end)) => {
+                lines.push(line);
+                *end = tok_end;
+            }
+            None => self.doc_comment = Some((tok_start, vec![line], tok_end)),
+        }
+    }
+
     fn unicode_literal(&mut self, literal_number: usize) -> Result<char, LexicalError> {
         let mut p: u32 = 0u32;
         let unicode_error = Err(LexicalError {
@@ -654,7 +686,11 @@ where
                     tabs += 1;
                 }
                 Some('#') => {
-                    self.lex_comment();
+                    if self.chr1 == Some('#') {
+                        self.lex_doc_comment();
+                    } else {
+                        self.lex_comment();
+                    }
                     spaces = 0;
                     tabs = 0;
                 }
@@ -919,6 +955,12 @@ where
             '|' => {
                 self.eat_single_char(Tok::Or);
             }
+            '<' => {
+                self.eat_with_equal(Tok::Less, Tok::LessEqual);
+            }
+            '>' => {
+                self.eat_with_equal(Tok::Greater, Tok::GreaterEqual);
+            }
             _ => {
                 let c = self.next_char();
                 return Err(LexicalError {
@@ -938,6 +980,19 @@ where
         self.emit((tok_start, ty, tok_end));
     }
 
+    fn eat_with_equal(&mut self, ty: Tok, ty_equal: Tok) {
+        let tok_start = self.get_pos();
+        self.next_char().unwrap();
+        if let Some('=') = self.chr0 {
+            self.next_char();
+            let tok_end = self.get_pos();
+            self.emit((tok_start, ty_equal, tok_end));
+        } else {
+            let tok_end = self.get_pos();
+            self.emit((tok_start, ty, tok_end));
+        }
+    }
+
     /// Helper function to go to the next character coming up.
     fn next_char(&mut self) -> Option<char> {
         let c = self.chr0;
@@ -960,7 +1015,28 @@ where
 
     /// Helper function to emit a lexed token to the queue of tokens.
     fn emit(&mut self, spanned: Spanned) {
-        self.pending.push(spanned);
+        match &spanned.1 {
+            // the doc comment follows the declaring keyword
+            Tok::LetDef | Tok::NodeDef => {
+                self.pending.push(spanned);
+                if let Some((start, lines, end)) = self.doc_comment.take() {
+                    let value = lines.join("\n");
+                    self.pending.push((start, Tok::DocComment { value }, end));
+                }
+            }
+            // the node types are placed between the doc comment and the keyword
+            Tok::NodeExtern
+            | Tok::NodeData
+            | Tok::NodeOptim
+            | Tok::NodeExec
+            | Tok::Indent
+            | Tok::Dedent => self.pending.push(spanned),
+            _ => {
+                // the dangling doc comment is discarded
+                self.doc_comment = None;
+                self.pending.push(spanned);
+            }
+        }
     }
 }
 
@@ -7,11 +7,16 @@ extern crate lalrpop_util;
 
 lalrpop_mod!(#[allow(clippy::all)] pub grammar); // synthesized by LALRPOP
 
+pub mod cst;
 pub mod error;
 
+mod format;
+mod incremental;
 mod lexer;
 mod location;
 mod parser;
 mod token;
 
+pub use self::format::FormatConfig;
+pub use self::incremental::{Document, Position, TextEdit};
 pub use self::parser::Parser;
@@ -1,7 +1,9 @@
 use std::iter;
 
 use crate::ast;
+use crate::cst::Cst;
 use crate::error::ParseError;
+use crate::format::FormatConfig;
 use crate::grammar;
 use crate::lexer;
 use crate::token;
@@ -30,4 +32,19 @@ impl Parser {
 
         self.inner.parse(tokenizer).map_err(ParseError::from)
     }
+
+    pub fn format_file(&self, source: &str, config: &FormatConfig) -> Result<String, ParseError> {
+        let file = self.parse_file(source)?;
+        let formatted = Cst::parse(source)?.format(config);
+
+        // the formatter should not change the program
+        let file_formatted = self.parse_file(&formatted)?;
+        if file == file_formatted {
+            Ok(formatted)
+        } else {
+            Err("the formatted source is not equivalent to the original"
+                .to_string()
+                .into())
+        }
+    }
 }
@@ -12,6 +12,7 @@ pub enum Tok {
     Float { value: f64 },
     String { value: String },
     Bytes { value: Vec<u8> },
+    DocComment { value: String },
     StartFile,
     Newline,
     Indent,
@@ -37,15 +38,23 @@ pub enum Tok {
     And,
     Xor,
     Or,
+    Less,
+    LessEqual,
+    Greater,
+    GreaterEqual,
     BoolYes,
     BoolNo,
     WithDef,
     WithSet,
+    CostDef,
     LetDef,
     LetBool,
     LetInt,
     LetReal,
     LetDim,
+    LetDynamic,
+    LetEnum,
+    LetIn,
     NodeDef,
     NodeExtern,
     NodeData,
@@ -64,6 +73,7 @@ impl fmt::Display for Tok {
             Int { value } => write!(f, "'{}'", value),
             Float { value } => write!(f, "'{}'", value),
             String { value } => write!(f, "{:?}", value),
+            DocComment { value } => write!(f, "'## {}'", value),
             Bytes { value } => {
                 write!(f, "b\"")?;
                 for i in value {
@@ -104,15 +114,23 @@ impl fmt::Display for Tok {
             And => f.write_str("'&'"),
             Xor => f.write_str("'^'"),
             Or => f.write_str("'|'"),
+            Less => f.write_str("'<'"),
+            LessEqual => f.write_str("'<='"),
+            Greater => f.write_str("'>'"),
+            GreaterEqual => f.write_str("'>='"),
             BoolYes => f.write_str("'yes'"),
             BoolNo => f.write_str("'no'"),
             WithDef => f.write_str("'with'"),
             WithSet => f.write_str("'set'"),
+            CostDef => f.write_str("'cost'"),
             LetDef => f.write_str("'let'"),
             LetBool => f.write_str("'bool'"),
             LetInt => f.write_str("'int'"),
             LetReal => f.write_str("'real'"),
             LetDim => f.write_str("'dim'"),
+            LetDynamic => f.write_str("'dynamic'"),
+            LetEnum => f.write_str("'enum'"),
+            LetIn => f.write_str("'in'"),
             NodeDef => f.write_str("'node'"),
             NodeExtern => f.write_str("'extern'"),
             NodeData => f.write_str("'data'"),
@@ -46,6 +46,13 @@ impl Code {
         }
     }
 
+    pub fn data_mut(&mut self) -> &mut CodeData {
+        match self {
+            Self::Node(node) => &mut node.data,
+            Self::Extern(node) => &mut node.data,
+        }
+    }
+
     pub fn as_node(&self) -> &NodeCode {
         match self {
             Self::Node(node) => node,
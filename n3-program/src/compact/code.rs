@@ -5,6 +5,7 @@ use serde::{Deserialize, Serialize};
 use super::code_extern::ExternCode;
 use super::code_node::NodeCode;
 use super::graph::Graphs;
+use super::share::Instance;
 use super::{ArrangeId, Compact, CompactContext, Decompact, DecompactContext};
 use crate::ast;
 
@@ -14,6 +15,8 @@ pub type Codes = BTreeMap<String, Code>;
 pub enum Code {
     Node(NodeCode),
     Extern(ExternCode),
+    /// The shared code, which is defined once in the program
+    Instance(Instance),
 }
 
 impl Compact for crate::code::Code {
@@ -28,10 +31,11 @@ impl Compact for crate::code::Code {
 }
 
 impl ArrangeId for Code {
-    fn arrange_id(&mut self, ids: &Graphs<u64>) {
+    fn arrange_id(&mut self, ids: &mut Graphs<u64>) {
         match self {
             Self::Node(x) => x.arrange_id(ids),
             Self::Extern(x) => x.arrange_id(ids),
+            Self::Instance(x) => x.arrange_id(ids),
         }
     }
 }
@@ -44,6 +48,7 @@ impl Decompact for Code {
         match self {
             Self::Node(x) => Self::Output::Node(x.decompact(ctx, ())),
             Self::Extern(x) => Self::Output::Extern(x.decompact(ctx, ())),
+            Self::Instance(x) => ctx.instantiate(x).decompact(ctx, ()),
         }
     }
 }
@@ -70,8 +75,8 @@ impl Compact for crate::code::CodeData {
 }
 
 impl ArrangeId for CodeData {
-    fn arrange_id(&mut self, ids: &Graphs<u64>) {
-        self.graph = ids[&self.graph];
+    fn arrange_id(&mut self, ids: &mut Graphs<u64>) {
+        self.graph = ids.arrange(self.graph);
     }
 }
 
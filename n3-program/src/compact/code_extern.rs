@@ -2,6 +2,7 @@ use serde::{Deserialize, Serialize};
 
 use super::code::CodeData;
 use super::graph::Graphs;
+use super::value::Shapes;
 use super::{ArrangeId, Compact, CompactContext, Decompact, DecompactContext};
 use crate::ast;
 
@@ -9,6 +10,7 @@ use crate::ast;
 pub struct ExternCode {
     ty: ast::ExternNodeType,
     data: CodeData,
+    shapes: ExternCodeShapes,
 }
 
 impl Compact for crate::externs::ExternCode {
@@ -18,13 +20,15 @@ impl Compact for crate::externs::ExternCode {
         Self::Output {
             ty: self.ty,
             data: self.data.compact(ctx),
+            shapes: self.shapes.compact(ctx),
         }
     }
 }
 
 impl ArrangeId for ExternCode {
-    fn arrange_id(&mut self, ids: &Graphs<u64>) {
+    fn arrange_id(&mut self, ids: &mut Graphs<u64>) {
         self.data.arrange_id(ids);
+        self.shapes.arrange_id(ids);
     }
 }
 
@@ -36,6 +40,43 @@ impl Decompact for ExternCode {
         Self::Output {
             ty: self.ty,
             data: self.data.decompact(ctx, ()),
+            shapes: self.shapes.decompact(ctx, ()),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ExternCodeShapes {
+    input: Option<Shapes>,
+    output: Option<Shapes>,
+}
+
+impl Compact for crate::externs::ExternCodeShapes {
+    type Output = ExternCodeShapes;
+
+    fn compact(&self, ctx: &mut CompactContext) -> Self::Output {
+        Self::Output {
+            input: self.input.compact(ctx),
+            output: self.output.compact(ctx),
+        }
+    }
+}
+
+impl ArrangeId for ExternCodeShapes {
+    fn arrange_id(&mut self, ids: &mut Graphs<u64>) {
+        self.input.arrange_id(ids);
+        self.output.arrange_id(ids);
+    }
+}
+
+impl Decompact for ExternCodeShapes {
+    type Args = ();
+    type Output = crate::externs::ExternCodeShapes;
+
+    fn decompact(self, ctx: &mut DecompactContext, (): Self::Args) -> Self::Output {
+        Self::Output {
+            input: self.input.decompact(ctx, ()),
+            output: self.output.decompact(ctx, ()),
         }
     }
 }
@@ -7,7 +7,7 @@ use super::{ArrangeId, Compact, CompactContext, Decompact, DecompactContext};
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct NodeCode {
     data: CodeData,
-    tensor_graph: Vec<Code>,
+    pub(super) tensor_graph: Vec<Code>,
 }
 
 impl Compact for crate::nodes::NodeCode {
@@ -22,7 +22,7 @@ impl Compact for crate::nodes::NodeCode {
 }
 
 impl ArrangeId for NodeCode {
-    fn arrange_id(&mut self, ids: &Graphs<u64>) {
+    fn arrange_id(&mut self, ids: &mut Graphs<u64>) {
         self.data.arrange_id(ids);
         self.tensor_graph.arrange_id(ids);
     }
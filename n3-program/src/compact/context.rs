@@ -1,20 +1,21 @@
-use super::code::Codes;
+use super::code::{Code, Codes};
 use super::exec::Program;
 use super::graph::{Env, Graphs, Table};
+use super::share::{Instance, SharedCodes};
 use super::value::Values;
 use super::{ArrangeId, Decompact};
 use crate::ast;
-use crate::externs::PythonScripts;
+use crate::externs::ExternScripts;
 
 pub struct CompactContext {
     pub(super) env: Option<Env>,
     graphs: Graphs<Table>,
     pub nodes: Codes,
-    scripts: PythonScripts,
+    scripts: ExternScripts,
 }
 
 impl CompactContext {
-    pub fn new(scripts: PythonScripts) -> Self {
+    pub fn new(scripts: ExternScripts) -> Self {
         Self {
             env: None,
             graphs: Graphs::new(),
@@ -32,13 +33,19 @@ impl CompactContext {
     }
 
     pub fn build(mut self) -> Program {
-        let (ids, graphs) = self.graphs.arrange_id();
-        self.nodes.arrange_id(&ids);
+        let (mut ids, graphs) = self.graphs.arrange_id();
+        self.nodes.arrange_id(&mut ids);
+
+        let mut defs = SharedCodes::default();
+        let nodes = defs.share_all(self.nodes);
 
         Program {
             env: self.env,
             graphs,
-            nodes: self.nodes,
+            defs: defs.build(),
+            nodes,
+            links: Default::default(),
+            backend: Default::default(),
             scripts: self.scripts,
         }
     }
@@ -46,17 +53,27 @@ impl CompactContext {
 
 pub struct DecompactContext {
     graphs: Graphs<crate::graph::Table>,
+    defs: Vec<Code>,
 }
 
 impl Default for DecompactContext {
     fn default() -> Self {
+        Self::new(vec![])
+    }
+}
+
+impl DecompactContext {
+    pub fn new(defs: Vec<Code>) -> Self {
         Self {
             graphs: Graphs::new(),
+            defs,
         }
     }
-}
 
-impl DecompactContext {
+    pub fn instantiate(&self, instance: Instance) -> Code {
+        instance.instantiate(&self.defs)
+    }
+
     pub fn insert_graph(&mut self, id: u64, graph: crate::graph::Table) {
         self.graphs.insert(id, graph);
     }
@@ -2,18 +2,24 @@ use std::io;
 
 use serde::{Deserialize, Serialize};
 
-use super::code::Codes;
-use super::graph::{Env, Table, UncompactedEnv};
-use super::{Compact, CompactContext, Decompact, DecompactContext};
+use super::code::{Code, Codes};
+use super::graph::{self, Env, Graphs, Table, UncompactedEnv};
+use super::header::{self, ContentHash};
+use super::{ArrangeId, Compact, CompactContext, Decompact, DecompactContext};
 use crate::error::Result;
-use crate::externs::PythonScripts;
+use crate::execs::ProgramFormat;
+use crate::externs::{Backend, ExternScripts};
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Program {
     pub(super) env: Option<Env>,
     pub(super) graphs: Vec<Table>,
+    /// The shared codes, whose graph ids are local to each instance
+    pub(super) defs: Vec<Code>,
     pub(super) nodes: Codes,
-    pub(super) scripts: PythonScripts,
+    pub(super) links: Vec<Vec<String>>,
+    pub(super) backend: Backend,
+    pub(super) scripts: ExternScripts,
 }
 
 impl Program {
@@ -24,11 +30,16 @@ impl Program {
         UncompactedEnv(&program.env).compact(&mut ctx);
         program.graph.compact(&mut ctx);
         ctx.nodes = program.nodes.compact(&mut ctx);
-        ctx.build()
+
+        Self {
+            links: program.links.clone(),
+            backend: program.backend,
+            ..ctx.build()
+        }
     }
 
     pub fn decompact(self) -> crate::execs::Program {
-        let mut ctx = DecompactContext::default();
+        let mut ctx = DecompactContext::new(self.defs);
 
         let env = self.env.decompact(&mut ctx, ());
 
@@ -44,22 +55,77 @@ impl Program {
             env,
             graph,
             nodes,
+            links: self.links,
+            backend: self.backend,
             scripts: self.scripts,
         }
     }
 
+    /// The number of the shared codes, which are defined once in the program.
+    pub fn num_defs(&self) -> usize {
+        self.defs.len()
+    }
+
+    /// Hash the canonical form of the program, which does not depend on how the ids are generated.
+    pub fn hash(&self) -> Result<ContentHash> {
+        let body = bincode::serialize(&self.clone().canonicalize())?;
+        Ok(header::hash(&body))
+    }
+
+    /// Rearrange the graph ids in order of appearance, visiting the nodes by their names.
+    fn canonicalize(mut self) -> Self {
+        // note: the env depends on the machines, not on the model and code
+        self.env = None;
+
+        let mut ids = Graphs::new();
+        // note: the main graph always comes first
+        ids.arrange(0);
+
+        self.nodes.arrange_id(&mut ids);
+        self.graphs = graph::arrange_tables(self.graphs, &mut ids);
+        self
+    }
+
     pub fn save<W>(&self, writer: W) -> Result<()>
     where
         W: io::Write,
     {
-        bincode::serialize_into(writer, self).map_err(|e| e.into())
+        let body = bincode::serialize(self)?;
+        header::write(writer, &body)
+    }
+
+    pub fn save_as<W>(&self, mut writer: W, format: ProgramFormat) -> Result<()>
+    where
+        W: io::Write,
+    {
+        match format {
+            ProgramFormat::Binary => self.save(writer),
+            ProgramFormat::Json => {
+                serde_json::to_writer_pretty(&mut writer, self)?;
+                // note: end with a newline, to be friendly to the diff tools
+                writeln!(writer).map_err(|e| e.into())
+            }
+            ProgramFormat::Yaml => serde_yaml::to_writer(writer, self).map_err(|e| e.into()),
+        }
     }
 
-    pub(crate) fn load<R>(reader: R) -> Result<Program>
+    pub fn load<R>(reader: R) -> Result<Program>
     where
         R: io::Read,
     {
-        let program: Self = bincode::deserialize_from(reader)?;
+        let body = header::read(reader)?;
+        let program: Self = bincode::deserialize(&body)?;
         Ok(program)
     }
+
+    pub fn load_as<R>(reader: R, format: ProgramFormat) -> Result<Program>
+    where
+        R: io::Read,
+    {
+        match format {
+            ProgramFormat::Binary => Self::load(reader),
+            ProgramFormat::Json => serde_json::from_reader(reader).map_err(|e| e.into()),
+            ProgramFormat::Yaml => serde_yaml::from_reader(reader).map_err(|e| e.into()),
+        }
+    }
 }
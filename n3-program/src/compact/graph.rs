@@ -6,6 +6,7 @@ use serde::{Deserialize, Serialize};
 use super::value::Values;
 use super::variable::{VarAsKey, VariableKey};
 use super::{ArrangeId, Compact, CompactContext, Decompact, DecompactContext};
+use crate::ast;
 
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct Graphs<T>(pub BTreeMap<u64, T>);
@@ -52,25 +53,57 @@ impl Decompact for Vec<Table> {
 
 impl Graphs<Table> {
     pub fn arrange_id(mut self) -> (Graphs<u64>, Vec<Table>) {
-        let ids = Graphs(
+        let mut ids = Graphs(
             self.0
                 .keys()
                 .enumerate()
                 .map(|(v, k)| (*k, v as u64))
                 .collect(),
         );
-        self.0.arrange_id(&ids);
+        self.0.arrange_id(&mut ids);
 
         let graphs = self.0.into_iter().map(|(_, x)| x).collect();
         (ids, graphs)
     }
 }
 
+impl Graphs<u64> {
+    /// Get the arranged id, or give the next one if the id appears first.
+    pub fn arrange(&mut self, id: u64) -> u64 {
+        let next = self.len() as u64;
+        *self.entry(id).or_insert(next)
+    }
+}
+
+/// Reorder the tables by the arranged ids, visiting the newly appeared ones in turn.
+pub fn arrange_tables(graphs: Vec<Table>, ids: &mut Graphs<u64>) -> Vec<Table> {
+    let mut graphs: Vec<_> = graphs.into_iter().map(Some).collect();
+    let mut arranged = Vec::with_capacity(graphs.len());
+
+    while arranged.len() < graphs.len() {
+        let next = arranged.len() as u64;
+        let id = match ids.iter().find(|(_, v)| **v == next).map(|(k, _)| *k) {
+            Some(id) => id,
+            // note: the unreferenced tables follow their original order
+            None => {
+                let id = graphs.iter().position(Option::is_some).unwrap() as u64;
+                ids.arrange(id);
+                id
+            }
+        };
+
+        let mut graph = graphs[id as usize].take().unwrap();
+        graph.arrange_id(ids);
+        arranged.push(graph);
+    }
+    arranged
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
-pub struct Table(BTreeMap<String, VariableKey>);
+pub struct Table(ast::IndexMap<String, VariableKey>);
 
 impl Deref for Table {
-    type Target = BTreeMap<String, VariableKey>;
+    type Target = ast::IndexMap<String, VariableKey>;
 
     fn deref(&self) -> &Self::Target {
         &self.0
@@ -84,7 +117,7 @@ impl DerefMut for Table {
 }
 
 impl ArrangeId for Table {
-    fn arrange_id(&mut self, ids: &Graphs<u64>) {
+    fn arrange_id(&mut self, ids: &mut Graphs<u64>) {
         self.0.arrange_id(ids)
     }
 }
@@ -94,8 +127,8 @@ impl Decompact for Table {
     type Output = (crate::graph::Table, Values);
 
     fn decompact(self, ctx: &mut DecompactContext, id: Self::Args) -> Self::Output {
-        let mut variables = BTreeMap::new();
-        let mut values = BTreeMap::new();
+        let mut variables = ast::IndexMap::new();
+        let mut values = ast::IndexMap::new();
 
         for (name, data) in self.0 {
             let (var, value) = data.decompact(ctx, (id, name.clone()));
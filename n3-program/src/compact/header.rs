@@ -0,0 +1,87 @@
+use std::io::{self, Read};
+
+use sha2::{Digest, Sha256};
+
+use crate::error::{Error, Result};
+
+/// The magic bytes at the beginning of the program binaries.
+pub const MAGIC: &[u8; 4] = b"N3PG";
+
+/// The version of the binary format, which should be increased on every incompatible change.
+///
+/// - 1: the extern codes keep their resolved shapes, and the programs keep the links of the nodes
+/// - 2: the identical subgraphs are shared as the code definitions
+/// - 3: the programs record the backend of their scripts
+pub const FORMAT_VERSION: u32 = 3;
+
+/// The SHA-256 hash of the body.
+pub type ContentHash = [u8; 32];
+
+/// Write the body after the header: `magic | version | length | hash`.
+pub fn write<W>(mut writer: W, body: &[u8]) -> Result<()>
+where
+    W: io::Write,
+{
+    writer.write_all(MAGIC)?;
+    writer.write_all(&FORMAT_VERSION.to_le_bytes())?;
+    writer.write_all(&(body.len() as u64).to_le_bytes())?;
+    writer.write_all(&hash(body))?;
+    writer.write_all(body)?;
+    Ok(())
+}
+
+/// Read the body, testing whether the header is compatible and the body is not corrupted.
+pub fn read<R>(mut reader: R) -> Result<Vec<u8>>
+where
+    R: io::Read,
+{
+    let mut magic = [0; 4];
+    read_exact(&mut reader, &mut magic).map_err(|_| Error::NotProgram)?;
+    if &magic != MAGIC {
+        return Err(Error::NotProgram);
+    }
+
+    let mut version = [0; 4];
+    read_exact(&mut reader, &mut version)?;
+    let version = u32::from_le_bytes(version);
+    if version != FORMAT_VERSION {
+        return Err(Error::MismatchedVersion {
+            expected: FORMAT_VERSION,
+            given: version,
+        });
+    }
+
+    let mut len = [0; 8];
+    read_exact(&mut reader, &mut len)?;
+    let len = u64::from_le_bytes(len);
+
+    let mut expected = ContentHash::default();
+    read_exact(&mut reader, &mut expected)?;
+
+    let mut body = vec![];
+    reader.take(len).read_to_end(&mut body)?;
+    if body.len() as u64 != len || hash(&body) != expected {
+        return Err(Error::CorruptedProgram);
+    }
+    Ok(body)
+}
+
+pub fn hash(body: &[u8]) -> ContentHash {
+    Sha256::digest(body).into()
+}
+
+/// Format the hash in lowercase hexadecimal, to be printed on the logs.
+pub fn to_hex(hash: &ContentHash) -> String {
+    hash.iter().map(|x| format!("{:02x}", x)).collect()
+}
+
+fn read_exact<R>(reader: &mut R, buf: &mut [u8]) -> Result<()>
+where
+    R: io::Read,
+{
+    reader.read_exact(buf).map_err(|e| match e.kind() {
+        // note: the header is truncated
+        io::ErrorKind::UnexpectedEof => Error::CorruptedProgram,
+        _ => e.into(),
+    })
+}
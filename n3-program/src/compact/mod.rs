@@ -4,15 +4,20 @@ mod code_node;
 mod context;
 mod exec;
 mod graph;
+mod header;
+mod share;
 mod value;
 mod variable;
 
 use std::collections::BTreeMap;
+use std::hash::Hash;
 
 use self::graph::Graphs;
+use crate::ast;
 
 pub use self::context::{CompactContext, DecompactContext};
 pub use self::exec::Program;
+pub use self::header::{to_hex, ContentHash, FORMAT_VERSION, MAGIC};
 
 pub trait Compact {
     type Output;
@@ -21,7 +26,7 @@ pub trait Compact {
 }
 
 pub trait ArrangeId {
-    fn arrange_id(&mut self, ids: &Graphs<u64>);
+    fn arrange_id(&mut self, ids: &mut Graphs<u64>);
 }
 
 pub trait Decompact {
@@ -50,7 +55,7 @@ where
     K: Clone + Ord,
     V: ArrangeId,
 {
-    fn arrange_id(&mut self, ids: &Graphs<u64>) {
+    fn arrange_id(&mut self, ids: &mut Graphs<u64>) {
         self.values_mut().for_each(|x| x.arrange_id(ids))
     }
 }
@@ -70,6 +75,45 @@ where
     }
 }
 
+impl<K, V> Compact for ast::IndexMap<K, V>
+where
+    K: Clone + Eq + Hash,
+    V: Compact,
+{
+    type Output = ast::IndexMap<K, V::Output>;
+
+    fn compact(&self, ctx: &mut CompactContext) -> Self::Output {
+        self.iter()
+            .map(|(k, v)| (k.clone(), v.compact(ctx)))
+            .collect()
+    }
+}
+
+impl<K, V> ArrangeId for ast::IndexMap<K, V>
+where
+    K: Clone + Eq + Hash,
+    V: ArrangeId,
+{
+    fn arrange_id(&mut self, ids: &mut Graphs<u64>) {
+        self.values_mut().for_each(|x| x.arrange_id(ids))
+    }
+}
+
+impl<K, V> Decompact for ast::IndexMap<K, V>
+where
+    K: Eq + Hash,
+    V: Decompact<Args = ()>,
+{
+    type Args = V::Args;
+    type Output = ast::IndexMap<K, V::Output>;
+
+    fn decompact(self, ctx: &mut DecompactContext, (): Self::Args) -> Self::Output {
+        self.into_iter()
+            .map(|(k, v)| (k, v.decompact(ctx, ())))
+            .collect()
+    }
+}
+
 impl<T> Compact for Vec<T>
 where
     T: Compact,
@@ -85,7 +129,7 @@ impl<T> ArrangeId for Vec<T>
 where
     T: ArrangeId,
 {
-    fn arrange_id(&mut self, ids: &Graphs<u64>) {
+    fn arrange_id(&mut self, ids: &mut Graphs<u64>) {
         self.iter_mut().for_each(|x| x.arrange_id(ids))
     }
 }
@@ -117,7 +161,7 @@ impl<T> ArrangeId for Option<T>
 where
     T: ArrangeId,
 {
-    fn arrange_id(&mut self, ids: &Graphs<u64>) {
+    fn arrange_id(&mut self, ids: &mut Graphs<u64>) {
         if let Some(x) = self.as_mut() {
             x.arrange_id(ids);
         }
@@ -151,7 +195,7 @@ impl<T> ArrangeId for Box<T>
 where
     T: ArrangeId,
 {
-    fn arrange_id(&mut self, ids: &Graphs<u64>) {
+    fn arrange_id(&mut self, ids: &mut Graphs<u64>) {
         (**self).arrange_id(ids)
     }
 }
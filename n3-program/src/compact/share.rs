@@ -0,0 +1,93 @@
+use std::collections::HashMap;
+use std::mem;
+
+use serde::{Deserialize, Serialize};
+
+use super::code::{Code, Codes};
+use super::graph::Graphs;
+use super::ArrangeId;
+
+/// An instance of the shared code, with its own tables.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Instance {
+    def: u64,
+    /// The actual graph ids, indexed by the local ids of the definition
+    graphs: Vec<u64>,
+}
+
+impl ArrangeId for Instance {
+    fn arrange_id(&mut self, ids: &mut Graphs<u64>) {
+        self.graphs.iter_mut().for_each(|x| *x = ids.arrange(*x))
+    }
+}
+
+impl Instance {
+    /// Restore the code, with the actual graph ids.
+    pub fn instantiate(self, defs: &[Code]) -> Code {
+        let mut code = defs[self.def as usize].clone();
+
+        let mut ids = Graphs(
+            self.graphs
+                .into_iter()
+                .enumerate()
+                .map(|(local, id)| (local as u64, id))
+                .collect(),
+        );
+        code.arrange_id(&mut ids);
+        code
+    }
+}
+
+/// The identical codes, which differ only in their tables, are defined once.
+///
+/// It only shrinks the programs: the instances are expanded on decompaction,
+/// as each of them owns its parameters on the runtime.
+#[derive(Default)]
+pub struct SharedCodes {
+    defs: Vec<Code>,
+    indices: HashMap<Vec<u8>, u64>,
+}
+
+impl SharedCodes {
+    pub fn share_all(&mut self, nodes: Codes) -> Codes {
+        nodes
+            .into_iter()
+            .map(|(name, code)| (name, self.share(code)))
+            .collect()
+    }
+
+    /// Replace the code into the instance, sharing the tensor graphs ahead.
+    pub fn share(&mut self, mut code: Code) -> Code {
+        if let Code::Node(node) = &mut code {
+            for child in node.tensor_graph.iter_mut() {
+                let instance = Code::Instance(Instance {
+                    def: 0,
+                    graphs: vec![],
+                });
+                *child = self.share(mem::replace(child, instance));
+            }
+        }
+
+        // note: the graph ids are localized in order of appearance
+        let mut ids = Graphs::new();
+        code.arrange_id(&mut ids);
+
+        let mut graphs: Vec<_> = ids.0.into_iter().collect();
+        graphs.sort_by_key(|(_, local)| *local);
+        let graphs = graphs.into_iter().map(|(id, _)| id).collect();
+
+        // note: the codes are always serializable
+        let key = bincode::serialize(&code).unwrap();
+        let defs = &mut self.defs;
+        let def = *self.indices.entry(key).or_insert_with(|| {
+            defs.push(code);
+            defs.len() as u64 - 1
+        });
+
+        Code::Instance(Instance { def, graphs })
+    }
+
+    pub fn build(self) -> Vec<Code> {
+        self.defs
+    }
+}
@@ -7,7 +7,7 @@ use super::variable::{VarAsValue, VariableValue};
 use super::{ArrangeId, Compact, CompactContext, Decompact, DecompactContext};
 use crate::ast;
 
-pub type Values = BTreeMap<String, Option<Value>>;
+pub type Values = ast::IndexMap<String, Option<Value>>;
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum Value {
@@ -28,7 +28,10 @@ pub enum Value {
 pub struct ValueList(Vec<Value>);
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
-pub struct ValueMap(Values);
+pub struct ValueMap(BTreeMap<String, Option<Value>>);
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Shapes(BTreeMap<String, Option<ValueList>>);
 
 impl Compact for ast::Value {
     type Output = Value;
@@ -51,7 +54,7 @@ impl Compact for ast::Value {
 }
 
 impl ArrangeId for Value {
-    fn arrange_id(&mut self, ids: &Graphs<u64>) {
+    fn arrange_id(&mut self, ids: &mut Graphs<u64>) {
         match self {
             Self::Variable(x) => x.arrange_id(ids),
             Self::Expr(x) => x.arrange_id(ids),
@@ -83,7 +86,7 @@ impl Decompact for Value {
 }
 
 impl ArrangeId for ValueList {
-    fn arrange_id(&mut self, ids: &Graphs<u64>) {
+    fn arrange_id(&mut self, ids: &mut Graphs<u64>) {
         self.0.arrange_id(ids)
     }
 }
@@ -98,20 +101,57 @@ impl Decompact for ValueList {
 }
 
 impl ArrangeId for ValueMap {
-    fn arrange_id(&mut self, ids: &Graphs<u64>) {
+    fn arrange_id(&mut self, ids: &mut Graphs<u64>) {
         self.0.arrange_id(ids)
     }
 }
 
 impl Decompact for ValueMap {
     type Args = ();
-    type Output = crate::graph::Values;
+    type Output = BTreeMap<String, Option<ast::Value>>;
 
     fn decompact(self, ctx: &mut DecompactContext, (): Self::Args) -> Self::Output {
         self.0.decompact(ctx, ())
     }
 }
 
+impl Compact for ast::Shapes {
+    type Output = Shapes;
+
+    fn compact(&self, ctx: &mut CompactContext) -> Self::Output {
+        Shapes(
+            self.0
+                .borrow()
+                .iter()
+                .map(|(k, v)| {
+                    let v = v.as_ref().map(|x| ValueList(x.0.compact(ctx)));
+                    (k.clone(), v)
+                })
+                .collect(),
+        )
+    }
+}
+
+impl ArrangeId for Shapes {
+    fn arrange_id(&mut self, ids: &mut Graphs<u64>) {
+        self.0.arrange_id(ids)
+    }
+}
+
+impl Decompact for Shapes {
+    type Args = ();
+    type Output = ast::Shapes;
+
+    fn decompact(self, ctx: &mut DecompactContext, (): Self::Args) -> Self::Output {
+        Self::Output::new(
+            self.0
+                .into_iter()
+                .map(|(k, v)| (k, v.map(|x| ast::Shape(x.decompact(ctx, ())))))
+                .collect(),
+        )
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Expr {
     op: ast::Operator,
@@ -132,7 +172,7 @@ impl Compact for ast::Expr {
 }
 
 impl ArrangeId for Expr {
-    fn arrange_id(&mut self, ids: &Graphs<u64>) {
+    fn arrange_id(&mut self, ids: &mut Graphs<u64>) {
         self.lhs.arrange_id(ids);
         self.rhs.arrange_id(ids);
     }
@@ -42,7 +42,7 @@ impl<'a> Compact for VarAsKey<'a> {
 }
 
 impl ArrangeId for VariableKey {
-    fn arrange_id(&mut self, ids: &Graphs<u64>) {
+    fn arrange_id(&mut self, ids: &mut Graphs<u64>) {
         self.value.arrange_id(ids)
     }
 }
@@ -75,8 +75,8 @@ impl<'a> Compact for VarAsValue<'a> {
 }
 
 impl ArrangeId for VariableValue {
-    fn arrange_id(&mut self, ids: &Graphs<u64>) {
-        self.id = ids[&self.id];
+    fn arrange_id(&mut self, ids: &mut Graphs<u64>) {
+        self.id = ids.arrange(self.id);
     }
 }
 
@@ -4,17 +4,37 @@ pub type Result<T> = std::result::Result<T, Error>;
 
 #[derive(Debug)]
 pub enum Error {
+    /// The binary does not begin with the magic bytes
+    NotProgram,
+    /// The binary is built by an incompatible version of the format
+    MismatchedVersion {
+        expected: u32,
+        given: u32,
+    },
+    /// The binary is truncated, or its content hash does not match
+    CorruptedProgram,
     IOError(std::io::Error),
     BincodeError(BincodeError),
+    JsonError(serde_json::Error),
+    YamlError(serde_yaml::Error),
 }
 
 impl PartialEq for Error {
     fn eq(&self, other: &Self) -> bool {
-        // note: only test the types
-        matches!(
-            (self, other),
-            (Self::IOError(_), Self::IOError(_)) | (Self::BincodeError(_), Self::BincodeError(_)),
-        )
+        match (self, other) {
+            (
+                Self::MismatchedVersion {
+                    expected: e1,
+                    given: g1,
+                },
+                Self::MismatchedVersion {
+                    expected: e2,
+                    given: g2,
+                },
+            ) => e1 == e2 && g1 == g2,
+            // note: only test the types
+            _ => std::mem::discriminant(self) == std::mem::discriminant(other),
+        }
     }
 }
 
@@ -38,3 +58,15 @@ impl From<BincodeError> for Error {
         Self::BincodeError(error)
     }
 }
+
+impl From<serde_json::Error> for Error {
+    fn from(error: serde_json::Error) -> Self {
+        Self::JsonError(error)
+    }
+}
+
+impl From<serde_yaml::Error> for Error {
+    fn from(error: serde_yaml::Error) -> Self {
+        Self::YamlError(error)
+    }
+}
@@ -1,4 +1,4 @@
 pub mod dirs;
 mod program;
 
-pub use self::program::{Program, PROGRAM_MAIN};
+pub use self::program::{Program, ProgramFormat, PROGRAM_FORMATS, PROGRAM_MAIN};
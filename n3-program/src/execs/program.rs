@@ -1,19 +1,60 @@
 use std::io;
+use std::path::Path;
+use std::str::FromStr;
 
 use crate::code::Codes;
-use crate::compact::Program as CompactedProgram;
+use crate::compact::{ContentHash, Program as CompactedProgram};
 use crate::error::Result;
-use crate::externs::PythonScripts;
+use crate::externs::{Backend, ExternScripts};
 use crate::graph::{Values, Variables};
 
 pub const PROGRAM_MAIN: &str = "__main__";
 
+/// The formats which the programs can be saved into.
+pub const PROGRAM_FORMATS: &[&str] = &["bin", "json", "yaml"];
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ProgramFormat {
+    /// The compact binary, which is sent to the machines
+    Binary,
+    /// The human-readable formats, to review the programs
+    Json,
+    Yaml,
+}
+
+impl FromStr for ProgramFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "bin" => Ok(Self::Binary),
+            "json" => Ok(Self::Json),
+            "yaml" | "yml" => Ok(Self::Yaml),
+            _ => Err(format!("no such program format: {:?}", s)),
+        }
+    }
+}
+
+impl ProgramFormat {
+    /// Guess the format by the extension of the path, or the binary if unknown.
+    pub fn from_path(path: &Path) -> Self {
+        path.extension()
+            .and_then(|x| x.to_str())
+            .and_then(|x| x.parse().ok())
+            .unwrap_or(Self::Binary)
+    }
+}
+
 #[derive(Debug)]
 pub struct Program {
     pub env: Option<Values>,
     pub graph: Variables,
     pub nodes: Codes,
-    pub scripts: PythonScripts,
+    /// The chains of the nodes, like `data + model + loss + optimizer`
+    pub links: Vec<Vec<String>>,
+    /// The backend which the scripts are implemented for
+    pub backend: Backend,
+    pub scripts: ExternScripts,
 }
 
 impl PartialEq for Program {
@@ -21,6 +62,8 @@ impl PartialEq for Program {
         // ignore env
         self.graph.eq(&other.graph)
             && self.nodes.eq(&other.nodes)
+            && self.links.eq(&other.links)
+            && self.backend.eq(&other.backend)
             && self.scripts.eq(&other.scripts)
     }
 }
@@ -30,6 +73,10 @@ impl Program {
         CompactedProgram::compact(self)
     }
 
+    pub fn hash(&self) -> Result<ContentHash> {
+        self.compact().hash()
+    }
+
     pub fn save<W>(&self, writer: W) -> Result<()>
     where
         W: io::Write,
@@ -37,6 +84,13 @@ impl Program {
         self.compact().save(writer)
     }
 
+    pub fn save_as<W>(&self, writer: W, format: ProgramFormat) -> Result<()>
+    where
+        W: io::Write,
+    {
+        self.compact().save_as(writer, format)
+    }
+
     pub fn save_to_binary(&self) -> Result<Vec<u8>> {
         let mut binary = vec![];
         self.save(&mut binary)?;
@@ -49,4 +103,11 @@ impl Program {
     {
         Ok(CompactedProgram::load(reader)?.decompact())
     }
+
+    pub fn load_as<R>(reader: R, format: ProgramFormat) -> Result<Self>
+    where
+        R: io::Read,
+    {
+        Ok(CompactedProgram::load_as(reader, format)?.decompact())
+    }
 }
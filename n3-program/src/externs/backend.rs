@@ -0,0 +1,54 @@
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+/// The backends which the programs can be built for.
+pub const BACKENDS: &[&str] = &["torch", "ndarray"];
+
+/// The runtime which implements the extern nodes.
+#[derive(
+    Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+pub enum Backend {
+    /// The Python scripts, which run on PyTorch
+    #[default]
+    Torch,
+    /// The pure-Rust implementations, which are named by the extern nodes
+    NdArray,
+}
+
+impl FromStr for Backend {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "torch" => Ok(Self::Torch),
+            "ndarray" => Ok(Self::NdArray),
+            _ => Err(format!("no such backend: {:?}", s)),
+        }
+    }
+}
+
+impl fmt::Display for Backend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl Backend {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Torch => "torch",
+            Self::NdArray => "ndarray",
+        }
+    }
+
+    /// The extension of the source files, if the implementations are loaded from the files.
+    pub fn extension(&self) -> Option<&'static str> {
+        match self {
+            Self::Torch => Some("py"),
+            Self::NdArray => None,
+        }
+    }
+}
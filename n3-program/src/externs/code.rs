@@ -1,8 +1,16 @@
-use crate::ast::ExternNodeType;
+use crate::ast::{self, ExternNodeType};
 use crate::code::CodeData;
 
 #[derive(Debug, PartialEq)]
 pub struct ExternCode {
     pub ty: ExternNodeType,
     pub data: CodeData,
+    pub shapes: ExternCodeShapes,
+}
+
+/// The resolved shapes of the extern node, which are `None` if not sized.
+#[derive(Debug, Default, PartialEq)]
+pub struct ExternCodeShapes {
+    pub input: Option<ast::Shapes>,
+    pub output: Option<ast::Shapes>,
 }
@@ -1,5 +1,7 @@
+mod backend;
 mod code;
 mod script;
 
-pub use self::code::ExternCode;
-pub use self::script::{PythonScript, PythonScripts};
+pub use self::backend::{Backend, BACKENDS};
+pub use self::code::{ExternCode, ExternCodeShapes};
+pub use self::script::{ExternScript, ExternScripts};
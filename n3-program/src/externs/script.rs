@@ -2,15 +2,16 @@ use std::collections::BTreeMap;
 
 use serde::{Deserialize, Serialize};
 
-pub type PythonScripts = BTreeMap<String, PythonScript>;
+pub type ExternScripts = BTreeMap<String, ExternScript>;
 
+/// The implementation of the extern node, for the backend of the program.
 #[derive(Clone, Debug, Serialize, Deserialize)]
-pub struct PythonScript {
+pub struct ExternScript {
     pub name: String,
     pub source: String,
 }
 
-impl PartialEq for PythonScript {
+impl PartialEq for ExternScript {
     fn eq(&self, other: &Self) -> bool {
         self.name.eq(&other.name)
     }
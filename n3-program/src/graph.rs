@@ -1,5 +1,3 @@
-use std::collections::BTreeMap;
-
 use crate::ast;
 
 #[derive(Clone, Debug)]
@@ -18,8 +16,8 @@ impl PartialEq for Table {
     }
 }
 
-pub type Variables = BTreeMap<String, ast::RefVariable>;
-pub type Values = BTreeMap<String, Option<ast::Value>>;
+pub type Variables = ast::IndexMap<String, ast::RefVariable>;
+pub type Values = ast::IndexMap<String, Option<ast::Value>>;
 
 impl ToValues for Table {
     fn to_values(&self) -> Values {
@@ -7,11 +7,15 @@ pub mod execs;
 pub mod externs;
 pub mod graph;
 pub mod nodes;
+pub mod render;
 pub mod variable;
 
 pub use self::code::{Code, CodeData};
-pub use self::execs::{dirs, Program, PROGRAM_MAIN};
-pub use self::externs::{ExternCode, PythonScripts};
+pub use self::execs::{dirs, Program, ProgramFormat, PROGRAM_FORMATS, PROGRAM_MAIN};
+pub use self::externs::{
+    Backend, ExternCode, ExternCodeShapes, ExternScript, ExternScripts, BACKENDS,
+};
 pub use self::graph::ToValues;
 pub use self::nodes::NodeCode;
+pub use self::render::{GraphFormat, RenderGraph, GRAPH_FORMATS};
 pub use self::variable::BuildValue;
@@ -0,0 +1,291 @@
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+use crate::ast;
+use crate::code::Code;
+use crate::execs::{Program, PROGRAM_MAIN};
+
+/// The formats which the graphs can be rendered into.
+pub const GRAPH_FORMATS: &[&str] = &["dot", "mermaid"];
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum GraphFormat {
+    /// Graphviz DOT
+    Dot,
+    /// Mermaid flowchart
+    Mermaid,
+}
+
+impl FromStr for GraphFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "dot" => Ok(Self::Dot),
+            "mermaid" => Ok(Self::Mermaid),
+            _ => Err(format!("no such graph format: {:?}", s)),
+        }
+    }
+}
+
+pub trait RenderGraph {
+    /// Render the extern nodes as the vertices labelled with their output shapes,
+    /// and the other nodes as the clusters of their children.
+    fn render_graph(&self, format: GraphFormat) -> String;
+}
+
+impl RenderGraph for Code {
+    fn render_graph(&self, format: GraphFormat) -> String {
+        let mut graph = Graph::new(format);
+
+        let input = graph.add_vertex(&["Input".to_string()]);
+        let inputs = self
+            .data()
+            .input
+            .keys()
+            .map(|k| (k.clone(), input.clone()))
+            .collect();
+
+        match self {
+            Self::Node(node) => graph.add_scope(&node.tensor_graph, &inputs),
+            Self::Extern(_) => graph.add_code(self, &inputs),
+        };
+        graph.finish(&self.data().name)
+    }
+}
+
+impl RenderGraph for Program {
+    fn render_graph(&self, format: GraphFormat) -> String {
+        let mut graph = Graph::new(format);
+
+        // note: the outputs are shared along the links, like `data + model + loss`
+        for links in &self.links {
+            let mut outputs = Outputs::new();
+            for name in links {
+                let code = &self.nodes[name];
+                let label = format!("{}: {}", name, code.data().name);
+                outputs.extend(graph.add_cluster(&label, code, &outputs));
+            }
+        }
+
+        // the standalone nodes
+        for (name, code) in &self.nodes {
+            if !self.links.iter().flatten().any(|x| x == name) {
+                let label = format!("{}: {}", name, code.data().name);
+                graph.add_cluster(&label, code, &Outputs::new());
+            }
+        }
+
+        let name = self
+            .scripts
+            .get(PROGRAM_MAIN)
+            .map(|x| x.name.as_str())
+            .unwrap_or(PROGRAM_MAIN);
+        graph.finish(name)
+    }
+}
+
+/// The vertices which have produced the tensors, by the tensor names.
+type Outputs = BTreeMap<String, String>;
+
+struct Edge {
+    from: String,
+    to: String,
+    label: Option<String>,
+}
+
+struct Graph {
+    format: GraphFormat,
+    body: String,
+    edges: Vec<Edge>,
+    depth: usize,
+    num_vertices: usize,
+    num_clusters: usize,
+}
+
+impl Graph {
+    fn new(format: GraphFormat) -> Self {
+        Self {
+            format,
+            body: String::new(),
+            edges: vec![],
+            depth: 1,
+            num_vertices: 0,
+            num_clusters: 0,
+        }
+    }
+
+    fn add_code(&mut self, code: &Code, inputs: &Outputs) -> Outputs {
+        match code {
+            Code::Node(node) => self.add_cluster(&node.data.name, code, inputs),
+            Code::Extern(node) => {
+                let mut label = vec![node.data.name.clone()];
+                if let Some(shapes) = &node.shapes.output {
+                    label.push(fmt_shapes(shapes));
+                }
+                let vertex = self.add_vertex(&label);
+
+                // note: the inputs are labelled only if there are many, like `Concat`
+                let is_labelled = node.data.input.len() > 1;
+                for key in node.data.input.keys() {
+                    if let Some(from) = inputs.get(key) {
+                        let label = if is_labelled { Some(key.clone()) } else { None };
+                        self.add_edge(from, &vertex, label);
+                    }
+                }
+
+                node.data
+                    .output
+                    .keys()
+                    .map(|k| (k.clone(), vertex.clone()))
+                    .collect()
+            }
+        }
+    }
+
+    fn add_cluster(&mut self, label: &str, code: &Code, inputs: &Outputs) -> Outputs {
+        let id = self.num_clusters;
+        self.num_clusters += 1;
+
+        let label = self.escape(label);
+        let line = match self.format {
+            GraphFormat::Dot => format!("subgraph cluster_{} {{", id),
+            GraphFormat::Mermaid => format!("subgraph c{} [\"{}\"]", id, label),
+        };
+        self.push_line(&line);
+
+        self.depth += 1;
+        if let GraphFormat::Dot = self.format {
+            self.push_line(&format!("label=\"{}\";", label));
+        }
+        let outputs = match code {
+            Code::Node(node) => self.add_scope(&node.tensor_graph, inputs),
+            Code::Extern(_) => self.add_code(code, inputs),
+        };
+        self.depth -= 1;
+
+        let line = match self.format {
+            GraphFormat::Dot => "}",
+            GraphFormat::Mermaid => "end",
+        };
+        self.push_line(line);
+        outputs
+    }
+
+    /// Add the tensor graph, whose inputs are given as the outputs of the first line.
+    fn add_scope(&mut self, tensor_graph: &[Code], inputs: &Outputs) -> Outputs {
+        let mut tensors: BTreeMap<_, _> = inputs
+            .iter()
+            .map(|(k, v)| ((Some(1), k.clone()), v.clone()))
+            .collect();
+
+        let mut outputs = inputs.clone();
+        for code in tensor_graph {
+            let data = code.data();
+            let inputs = data
+                .input
+                .iter()
+                .filter_map(|(k, x)| {
+                    let vertex = tensors.get(&(x.id, x.name.clone()))?;
+                    Some((k.clone(), vertex.clone()))
+                })
+                .collect();
+
+            outputs = self.add_code(code, &inputs);
+            for (k, x) in &data.output {
+                if let Some(vertex) = outputs.get(k) {
+                    tensors.insert((x.id, x.name.clone()), vertex.clone());
+                }
+            }
+        }
+        outputs
+    }
+
+    fn add_vertex(&mut self, label: &[String]) -> String {
+        let id = format!("n{}", self.num_vertices);
+        self.num_vertices += 1;
+
+        let label: Vec<_> = label.iter().map(|x| self.escape(x)).collect();
+        let line = match self.format {
+            GraphFormat::Dot => format!("{} [label=\"{}\"];", &id, label.join("\\n")),
+            GraphFormat::Mermaid => format!("{}[\"{}\"]", &id, label.join("<br/>")),
+        };
+        self.push_line(&line);
+        id
+    }
+
+    fn add_edge(&mut self, from: &str, to: &str, label: Option<String>) {
+        if !self.edges.iter().any(|x| x.from == from && x.to == to) {
+            self.edges.push(Edge {
+                from: from.to_string(),
+                to: to.to_string(),
+                label,
+            });
+        }
+    }
+
+    fn push_line(&mut self, line: &str) {
+        for _ in 0..self.depth {
+            self.body.push_str("    ");
+        }
+        self.body.push_str(line);
+        self.body.push('\n');
+    }
+
+    fn escape(&self, label: &str) -> String {
+        match self.format {
+            GraphFormat::Dot => label.replace('\\', "\\\\").replace('"', "\\\""),
+            GraphFormat::Mermaid => label.replace('"', "#quot;"),
+        }
+    }
+
+    fn finish(mut self, name: &str) -> String {
+        // note: the edges are placed after the vertices, so that they don't move the vertices
+        for edge in std::mem::take(&mut self.edges) {
+            let line = match (self.format, edge.label) {
+                (GraphFormat::Dot, None) => format!("{} -> {};", edge.from, edge.to),
+                (GraphFormat::Dot, Some(label)) => {
+                    format!("{} -> {} [label=\"{}\"];", edge.from, edge.to, label)
+                }
+                (GraphFormat::Mermaid, None) => format!("{} --> {}", edge.from, edge.to),
+                (GraphFormat::Mermaid, Some(label)) => {
+                    format!("{} -->|{}| {}", edge.from, label, edge.to)
+                }
+            };
+            self.push_line(&line);
+        }
+
+        let name = self.escape(name);
+        match self.format {
+            GraphFormat::Dot => format!(
+                "digraph \"{}\" {{\n    node [shape=box];\n{}}}\n",
+                name, self.body
+            ),
+            GraphFormat::Mermaid => format!("%% {}\nflowchart TB\n{}", name, self.body),
+        }
+    }
+}
+
+/// Format the shapes, like `[1, 28, 28]` or `x=[1, 28, 28] y=[10]`.
+fn fmt_shapes(shapes: &ast::Shapes) -> String {
+    let shapes = shapes.0.borrow();
+
+    let fmt_shape = |shape: &Option<ast::Shape>| match shape {
+        Some(shape) => {
+            let dims: Vec<_> = shape.0.iter().map(|x| format!("{:?}", x)).collect();
+            format!("[{}]", dims.join(", "))
+        }
+        None => "*".to_string(),
+    };
+
+    match shapes.iter().collect::<Vec<_>>().as_slice() {
+        [(name, shape)] if name.as_str() == "x" => fmt_shape(shape),
+        shapes => {
+            let shapes: Vec<_> = shapes
+                .iter()
+                .map(|(name, shape)| format!("{}={}", name, fmt_shape(shape)))
+                .collect();
+            shapes.join(" ")
+        }
+    }
+}
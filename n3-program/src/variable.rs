@@ -86,6 +86,23 @@ where
     }
 }
 
+/// Resolve each dimension of the shapes.
+pub fn build_shapes(shapes: &ast::Shapes) -> ast::Shapes {
+    ast::Shapes::new(
+        shapes
+            .0
+            .borrow()
+            .iter()
+            .map(|(name, shape)| {
+                let shape = shape
+                    .as_ref()
+                    .map(|x| ast::Shape(x.0.iter().map(|x| x.build()).collect()));
+                (name.clone(), shape)
+            })
+            .collect(),
+    )
+}
+
 pub(crate) fn node_variable_should_be_pruned() -> ! {
     unreachable!("node variable should be pruned.")
 }
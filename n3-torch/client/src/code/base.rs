@@ -1,10 +1,15 @@
+use std::cell::RefCell;
+use std::collections::BTreeSet;
+
 use pyo3::prelude::*;
 use pyo3::types::PyDict;
 
 #[derive(Debug)]
 pub struct BuildArgs<'a> {
     pub args: &'a PyDict,
-    pub scripts: &'a n3_program::PythonScripts,
+    pub scripts: &'a n3_program::ExternScripts,
+    /// The scripts which are already defined in REPL
+    pub defined: RefCell<BTreeSet<&'a str>>,
 }
 
 pub trait BuildCode<'a> {
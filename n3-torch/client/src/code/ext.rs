@@ -21,8 +21,10 @@ impl<'a> BuildCode<'a> for n3_program::ExternCode {
         // Step 2. Get the source
         let main = &args.scripts[&self.data.name];
 
-        // Step 3. Define the node in REPL
-        py.run(&main.source, None, None)?;
+        // Step 3. Define the node in REPL, once for each class
+        if args.defined.borrow_mut().insert(&self.data.name) {
+            py.run(&main.source, None, None)?;
+        }
 
         // Step 4. Instantiate extern node
         let node = py.eval(
@@ -19,6 +19,7 @@ impl<'a> BuildCode<'a> for n3_program::Program {
         let build_args = BuildArgs {
             args: &args,
             scripts: &self.scripts,
+            defined: Default::default(),
         };
 
         // Step 2. Build the nodes
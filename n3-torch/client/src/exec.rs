@@ -1,5 +1,6 @@
 use std::mem::ManuallyDrop;
 
+use pyo3::exceptions::PyValueError;
 use pyo3::prelude::*;
 
 use n3_machine::PORT;
@@ -16,7 +17,17 @@ pub fn n3_execute(py: Python, program: &Program, handler: PyHandler) -> PyResult
     let mut machine_token = program.machine.split(':');
 
     // Step 1. Load the program text
-    let mut text = n3_program::Program::load(&*program.text).unwrap();
+    let text = n3_program::compact::Program::load(&*program.text).unwrap();
+    let hash = text.hash().unwrap();
+    handler.set_program_hash(hash);
+    let mut text = text.decompact();
+    if text.backend != n3_program::Backend::Torch {
+        return Err(PyValueError::new_err(format!(
+            "mismatched backend: expected {}, but the program is built for {}",
+            n3_program::Backend::Torch,
+            text.backend,
+        )));
+    }
 
     // Step 2. Attach variables
     if text.env.is_none() {
@@ -31,6 +42,10 @@ pub fn n3_execute(py: Python, program: &Program, handler: PyHandler) -> PyResult
 
     env.insert("is root".to_string(), Some(is_root.into()));
     env.insert("is distributed".to_string(), Some(is_distributed.into()));
+    env.insert(
+        "program hash".to_string(),
+        Some(n3_program::compact::to_hex(&hash).into()),
+    );
 
     let device_id = machine_token.next().unwrap_or("0").to_string();
 
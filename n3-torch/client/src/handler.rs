@@ -1,6 +1,6 @@
 use pyo3::prelude::*;
 
-use n3_machine_ffi::WorkHandler;
+use n3_machine_ffi::{ProgramHash, WorkHandler};
 
 #[pyclass]
 pub struct PyHandler {
@@ -13,6 +13,10 @@ impl PyHandler {
             inner: inner.clone(),
         }
     }
+
+    pub fn set_program_hash(&self, hash: ProgramHash) {
+        self.inner.set_program_hash(hash).unwrap()
+    }
 }
 
 #[pymethods]
@@ -1,6 +1,30 @@
+from typing import Dict, List, Optional
+
 from n3 import ExternNode
 
 
 class AssertShape(ExternNode):
+    # note: given only if the shapes have the dynamic dims
+    output_shapes: Dict[str, Optional[List[Optional[int]]]] = {}
+
     def forward(self, **kwargs):
+        # the dynamic dims may differ on every call, e.g. the sequence length
+        for name, x in kwargs.items():
+            self._assert_shape(name, x)
         return kwargs
+
+    def _assert_shape(self, name, x):
+        dims = (self.output_shapes or {}).get(name)
+        if dims is None:
+            return
+
+        # skip the batch dim
+        given = list(x.shape[1:])
+        if len(dims) != len(given):
+            raise ValueError(
+                f'mismatched shape of {name!r}: expected {dims}, given {given}')
+        for expected, dim in zip(dims, given):
+            # None: dynamic dim
+            if expected is not None and expected != dim:
+                raise ValueError(
+                    f'mismatched shape of {name!r}: expected {dims}, given {given}')
@@ -11,5 +11,8 @@ class Concat(ExternNode):
         if self.axis >= 0:
             self.axis += 1
 
-    def forward(self, x):
+    def forward(self, **x):
+        # the inputs are keyed by their indices (`0`, `1`, ...),
+        # as the outputs of the other nodes may share the same names
+        x = [x[str(i)] for i in range(len(x))]
         return torch.cat(x, dim=self.axis)
@@ -5,10 +5,11 @@ from n3 import ExternNode
 
 class CrossEntropy(ExternNode):
     number_of_classes: int
+    reduction: str
 
     def __init__(self, **kwargs):
         super().__init__(**kwargs)
-        self._inner = nn.CrossEntropyLoss()
+        self._inner = nn.CrossEntropyLoss(reduction=self.reduction)
 
     def forward(self, x, y):
         return self._inner(x, y)
@@ -21,6 +21,8 @@ class ExecWriter:
                                   self._exec_name, self._model_name)
             logdir = _increment_dir(logdir)
             self._writer = tensorboardX.SummaryWriter(logdir)
+            # record the program, to tell whether the runs share the model and code
+            self._writer.add_text('program/hash', args['env']['program hash'])
         else:
             self._writer = None
 
@@ -0,0 +1,66 @@
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+use clap::ArgMatches;
+
+use n3_builder::{parse_backend, Backend, ProgramFormat, Result};
+
+use crate::Command;
+
+pub struct BuildOptions {
+    format: ProgramFormat,
+    output: Option<PathBuf>,
+    disabled_passes: Vec<String>,
+    backend: Backend,
+}
+
+impl BuildOptions {
+    pub fn new(matches: &ArgMatches) -> Result<Self> {
+        let output = matches.value_of("output").map(PathBuf::from);
+
+        // note: the format is guessed by the output path if not given
+        let format = match matches.value_of("emit") {
+            Some(format) => format.parse().unwrap(),
+            None => output
+                .as_deref()
+                .map(ProgramFormat::from_path)
+                .unwrap_or(ProgramFormat::Binary),
+        };
+        let disabled_passes = matches
+            .values_of("disable-pass")
+            .into_iter()
+            .flatten()
+            .map(|x| x.to_string())
+            .collect();
+        let backend = match matches.value_of("backend") {
+            Some(name) => parse_backend(name)?,
+            None => Backend::default(),
+        };
+
+        Ok(Self {
+            format,
+            output,
+            disabled_passes,
+            backend,
+        })
+    }
+}
+
+pub fn f(command: Command, options: BuildOptions) -> Result<()> {
+    let mut args = command.args.unwrap();
+    for name in &options.disabled_passes {
+        args.pipeline_mut().set_enabled(name, false)?;
+    }
+    args.set_backend(options.backend);
+    if options.backend == Backend::NdArray {
+        n3_ndarray::externs::add_externs(args.root());
+    }
+
+    let program = args.build_uncompacted_with_env()?;
+    match options.output {
+        Some(path) => program.save_as(fs::File::create(path)?, options.format)?,
+        None => program.save_as(io::stdout(), options.format)?,
+    }
+    Ok(())
+}
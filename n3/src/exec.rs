@@ -4,7 +4,7 @@ use std::time::Duration;
 use chrono::prelude::*;
 use indicatif::{ProgressBar, ProgressStyle};
 
-use n3_builder::{dirs, Result};
+use n3_builder::{dirs, to_hex, Result};
 use n3_net_client::Work;
 
 use crate::Command;
@@ -41,6 +41,9 @@ pub fn execute(command: Command) -> Result<()> {
                 }
 
                 if !status.is_running {
+                    if let Some(hash) = &status.program_hash {
+                        pb.println(format!("program hash: {}", to_hex(hash)));
+                    }
                     break 'pb;
                 }
             } else {
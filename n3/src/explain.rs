@@ -0,0 +1,59 @@
+use std::process;
+
+use clap::{App, Arg};
+
+use n3_builder::{error, Result};
+
+pub fn f(args: &[String]) -> Result<()> {
+    let matches = App::new("n3 explain")
+        .bin_name("n3 explain")
+        .about("Print the detailed explanation of the error code")
+        .arg(
+            Arg::with_name("list")
+                .long("list")
+                .help("List the error codes"),
+        )
+        .arg(
+            Arg::with_name("code")
+                .required_unless("list")
+                .help("The error code to be explained (e.g. N3-E0201)"),
+        )
+        .get_matches_from(args);
+
+    let mut explanations = error::explanations().chain(n3_machine_ffi::EXPLANATIONS);
+
+    if matches.is_present("list") {
+        let mut explanations: Vec<_> = explanations.collect();
+        explanations.sort_unstable();
+        for (code, explanation) in explanations {
+            println!(
+                "{}  {}",
+                code,
+                explanation.lines().next().unwrap_or_default()
+            );
+        }
+        return Ok(());
+    }
+
+    let code = normalize(matches.value_of("code").unwrap());
+    match explanations.find(|(k, _)| *k == code) {
+        Some((_, explanation)) => {
+            println!("{}\n", &code);
+            print!("{}", explanation);
+            Ok(())
+        }
+        None => {
+            eprintln!("error: no such error code: `{}`", &code);
+            eprintln!("\nRun `n3 explain --list` to see the error codes.");
+            process::exit(1)
+        }
+    }
+}
+
+/// Accept the codes without the prefix, like `E0201` and `0201`.
+fn normalize(code: &str) -> String {
+    let code = code.trim().to_uppercase();
+    let code = code.strip_prefix("N3-").unwrap_or(&code);
+    let code = code.strip_prefix('E').unwrap_or(code);
+    format!("N3-E{}", code)
+}
@@ -0,0 +1,125 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process;
+
+use clap::{App, Arg};
+
+use n3_builder::{FormatConfig, Parser, Result};
+
+const EXTENSION: &str = "n3";
+const IGNORED_DIRS: &[&str] = &["target", "node_modules", "__pycache__"];
+
+pub fn f(args: &[String]) -> Result<()> {
+    let matches = App::new("n3 fmt")
+        .bin_name("n3 fmt")
+        .about("Format the n3 source files")
+        .arg(
+            Arg::with_name("check")
+                .long("check")
+                .help("Check whether the files are formatted, without modifying them"),
+        )
+        .arg(
+            Arg::with_name("indent-width")
+                .long("indent-width")
+                .takes_value(true)
+                .value_name("WIDTH")
+                .default_value("4")
+                .validator(validate_usize)
+                .help("The number of spaces per indentation level"),
+        )
+        .arg(
+            Arg::with_name("max-blank-lines")
+                .long("max-blank-lines")
+                .takes_value(true)
+                .value_name("LINES")
+                .default_value("1")
+                .validator(validate_usize)
+                .help("The maximum number of the adjacent blank lines"),
+        )
+        .arg(
+            Arg::with_name("no-align-graph")
+                .long("no-align-graph")
+                .help("Do not align the '=' columns of the graph nodes"),
+        )
+        .arg(
+            Arg::with_name("files")
+                .multiple(true)
+                .help("The files or directories to be formatted (default: current directory)"),
+        )
+        .get_matches_from(args);
+
+    let paths = match matches.values_of("files") {
+        Some(files) => files.map(PathBuf::from).collect(),
+        None => vec![PathBuf::from(".")],
+    };
+    let mut files = vec![];
+    for path in paths {
+        collect_files(path, &mut files)?;
+    }
+
+    let parser = Parser::default();
+    let config = FormatConfig {
+        indent_width: matches.value_of("indent-width").unwrap().parse().unwrap(),
+        align_graph: !matches.is_present("no-align-graph"),
+        max_blank_lines: matches
+            .value_of("max-blank-lines")
+            .unwrap()
+            .parse()
+            .unwrap(),
+    };
+    let check = matches.is_present("check");
+
+    let mut unformatted = false;
+    for file in files {
+        let source = fs::read_to_string(&file)?;
+        let formatted = parser.format_file(&source, &config)?;
+
+        if source != formatted {
+            if check {
+                println!("Unformatted: {}", file.display());
+                unformatted = true;
+            } else {
+                fs::write(&file, formatted)?;
+                println!("Formatted: {}", file.display());
+            }
+        }
+    }
+
+    if unformatted {
+        process::exit(1);
+    }
+    Ok(())
+}
+
+pub fn collect_files(path: PathBuf, files: &mut Vec<PathBuf>) -> Result<()> {
+    if path.is_dir() {
+        for entry in fs::read_dir(&path)? {
+            let path = entry?.path();
+            if (path.is_dir() && !is_ignored(&path)) || is_source(&path) {
+                collect_files(path, files)?;
+            }
+        }
+    } else {
+        files.push(path);
+    }
+    Ok(())
+}
+
+fn is_source(path: &Path) -> bool {
+    path.extension().and_then(|x| x.to_str()) == Some(EXTENSION)
+}
+
+/// Skip the hidden directories (e.g. `.git`) and the build outputs.
+fn is_ignored(path: &Path) -> bool {
+    match path.file_name().and_then(|x| x.to_str()) {
+        Some(name) => name.starts_with('.') || IGNORED_DIRS.contains(&name),
+        None => false,
+    }
+}
+
+fn validate_usize(value: String) -> std::result::Result<(), String> {
+    value
+        .parse::<usize>()
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
@@ -0,0 +1,116 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+use clap::{App, Arg};
+
+use n3_builder::{
+    ast, dirs, parse_backend, Backend, GlobalVars, GraphFormat, NodeRoot, Parser, RenderGraph,
+    Result, BACKENDS, GRAPH_FORMATS,
+};
+
+use crate::fmt::collect_files;
+use crate::summary::parse_input;
+
+pub fn f(args: &[String]) -> Result<()> {
+    let matches = App::new("n3 graph")
+        .bin_name("n3 graph")
+        .about("Render the graph of the model or the exec node")
+        .arg(
+            Arg::with_name("format")
+                .short("f")
+                .long("format")
+                .takes_value(true)
+                .possible_values(GRAPH_FORMATS)
+                .default_value(GRAPH_FORMATS[0])
+                .help("The format of the graph"),
+        )
+        .arg(
+            Arg::with_name("input")
+                .short("i")
+                .long("input")
+                .takes_value(true)
+                .multiple(true)
+                .number_of_values(1)
+                .value_name("[NAME=]DIMS")
+                .validator(|x| parse_input(&x).map(|_| ()))
+                .help("The input shape of the model (e.g. 1,28,28)"),
+        )
+        .arg(
+            Arg::with_name("arg")
+                .short("a")
+                .long("arg")
+                .takes_value(true)
+                .multiple(true)
+                .number_of_values(1)
+                .value_name("NAME=VALUE")
+                .validator(|x| parse_arg(&x).map(|_| ()))
+                .help("The argument of the exec node (e.g. model=LeNet5)"),
+        )
+        .arg(
+            Arg::with_name("backend")
+                .long("backend")
+                .takes_value(true)
+                .possible_values(BACKENDS)
+                .default_value(BACKENDS[0])
+                .help("The backend which implements the extern nodes of the exec node"),
+        )
+        .arg(
+            Arg::with_name("node")
+                .required(true)
+                .help("The name of the node to be rendered"),
+        )
+        .arg(
+            Arg::with_name("files")
+                .multiple(true)
+                .help("The files or directories which define the user nodes"),
+        )
+        .get_matches_from(args);
+
+    let mut files = vec![];
+    for path in matches.values_of("files").into_iter().flatten() {
+        collect_files(PathBuf::from(path), &mut files)?;
+    }
+
+    let n3_source_root = GlobalVars::default().get_string(dirs::N3_SOURCE_ROOT).ok();
+    let root = NodeRoot::new(n3_source_root.as_deref());
+    let parser = Parser::default();
+    for file in files {
+        let source = fs::read_to_string(&file)?;
+        let name = parser.parse_file(&source)?.node.name;
+        root.add_source(name, source);
+    }
+
+    let format: GraphFormat = matches.value_of("format").unwrap().parse().unwrap();
+    let name = matches.value_of("node").unwrap();
+
+    let graph = if root.is_exec(name)? {
+        let args: Vec<_> = matches
+            .values_of("arg")
+            .into_iter()
+            .flatten()
+            .map(|x| parse_arg(x).unwrap())
+            .collect();
+        let backend = parse_backend(matches.value_of("backend").unwrap())?;
+        if backend == Backend::NdArray {
+            n3_ndarray::externs::add_externs(&root);
+        }
+        root.build_exec(name, &args, backend)?.render_graph(format)
+    } else {
+        let input = matches.values_of("input").map(|values| {
+            let shapes: BTreeMap<_, _> = values.map(|x| parse_input(x).unwrap()).collect();
+            ast::Shapes::new(shapes)
+        });
+        root.build_code(name, input.as_ref())?.render_graph(format)
+    };
+    print!("{}", graph);
+    Ok(())
+}
+
+/// Parse `NAME=VALUE`, like `model=LeNet5`.
+fn parse_arg(arg: &str) -> std::result::Result<(&str, &str), String> {
+    match arg.find('=') {
+        Some(at) => Ok((arg[..at].trim(), arg[at + 1..].trim())),
+        None => Err(format!("the argument should be NAME=VALUE: {:?}", arg)),
+    }
+}
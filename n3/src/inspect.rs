@@ -0,0 +1,36 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use clap::{App, Arg};
+
+use n3_builder::{CompactedProgram, ProgramFormat, Result, PROGRAM_FORMATS};
+
+pub fn f(args: &[String]) -> Result<()> {
+    let matches = App::new("n3 inspect")
+        .bin_name("n3 inspect")
+        .about("Print the built program in the human-readable format")
+        .arg(
+            Arg::with_name("format")
+                .short("f")
+                .long("format")
+                .takes_value(true)
+                .possible_values(&PROGRAM_FORMATS[1..])
+                .default_value("json")
+                .help("The format to be printed"),
+        )
+        .arg(
+            Arg::with_name("file")
+                .required(true)
+                .help("The built program, whose format is guessed by its extension"),
+        )
+        .get_matches_from(args);
+
+    let path = Path::new(matches.value_of("file").unwrap());
+    let format: ProgramFormat = matches.value_of("format").unwrap().parse().unwrap();
+
+    // note: the program is not decompacted, to show what the machines receive
+    let program = CompactedProgram::load_as(fs::File::open(path)?, ProgramFormat::from_path(path))?;
+    program.save_as(io::stdout(), format)?;
+    Ok(())
+}
@@ -0,0 +1,159 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process;
+
+use clap::{App, Arg, ArgMatches};
+
+use n3_builder::lint::{Level, LintMessage, Linter, LINTS};
+use n3_builder::{dirs, GlobalVars, NodeRoot, Parser, Result};
+
+use crate::fmt::collect_files;
+
+pub fn f(args: &[String]) -> Result<()> {
+    let level_arg = |name, short, help| {
+        Arg::with_name(name)
+            .short(short)
+            .long(name)
+            .takes_value(true)
+            .multiple(true)
+            .number_of_values(1)
+            .value_name("LINT")
+            .help(help)
+    };
+
+    let matches = App::new("n3 lint")
+        .bin_name("n3 lint")
+        .about("Check the n3 source files for the suspicious code")
+        .arg(level_arg("allow", "A", "Ignore the lint"))
+        .arg(level_arg("warn", "W", "Report the lint as a warning"))
+        .arg(level_arg("deny", "D", "Report the lint as an error"))
+        .arg(Arg::with_name("list").long("list").help("List the lints"))
+        .arg(
+            Arg::with_name("files")
+                .multiple(true)
+                .help("The files or directories to be checked (default: current directory)"),
+        )
+        .get_matches_from(args);
+
+    if matches.is_present("list") {
+        for lint in LINTS {
+            println!(
+                "{:<20} {:<6} {}",
+                lint.name,
+                fmt_level(lint.level),
+                lint.description
+            );
+        }
+        return Ok(());
+    }
+
+    let linter = new_linter(&matches)?;
+
+    let paths = match matches.values_of("files") {
+        Some(files) => files.map(PathBuf::from).collect(),
+        None => vec![PathBuf::from(".")],
+    };
+    let mut files = vec![];
+    for path in paths {
+        collect_files(path, &mut files)?;
+    }
+
+    // the files may use each other
+    let n3_source_root = GlobalVars::default().get_string(dirs::N3_SOURCE_ROOT).ok();
+    let root = NodeRoot::new(n3_source_root.as_deref());
+    let parser = Parser::default();
+    let mut sources = vec![];
+    for file in files {
+        let source = fs::read_to_string(&file)?;
+        if let Ok(parsed) = parser.parse_file(&source) {
+            root.add_source(parsed.node.name, source.clone());
+        }
+        sources.push((file, source));
+    }
+
+    let mut failed = false;
+    for (file, source) in &sources {
+        match linter.check(&root, source) {
+            Ok(messages) => {
+                for message in messages {
+                    failed |= message.level == Level::Deny;
+                    eprintln!("{}", render(file, source, &message));
+                }
+            }
+            Err(e) => {
+                failed = true;
+                eprintln!("error[{}]: {:?}\n  --> {}\n", e.code(), e, file.display());
+            }
+        }
+    }
+
+    if failed {
+        process::exit(1);
+    }
+    Ok(())
+}
+
+fn new_linter(matches: &ArgMatches) -> Result<Linter> {
+    let mut linter = Linter::default();
+    for (arg, level) in &[
+        ("allow", Level::Allow),
+        ("warn", Level::Warn),
+        ("deny", Level::Deny),
+    ] {
+        for name in matches.values_of(arg).into_iter().flatten() {
+            linter.set_level(name, *level)?;
+        }
+    }
+    Ok(linter)
+}
+
+fn fmt_level(level: Level) -> &'static str {
+    match level {
+        Level::Allow => "allow",
+        Level::Warn => "warn",
+        Level::Deny => "deny",
+    }
+}
+
+/// Render the message with the source line, like:
+///
+/// ```text
+/// warning[unused-let]: the variable `padding` is never used
+///  --> my_node.n3:4:12
+///   |
+/// 4 |     let P: padding = int 1
+///   |            ^^^^^^^
+/// ```
+fn render(path: &Path, source: &str, message: &LintMessage) -> String {
+    let severity = match message.level {
+        Level::Deny => "error",
+        _ => "warning",
+    };
+    let mut buf = format!("{}[{}]: {}\n", severity, message.lint, &message.message);
+
+    match message.span {
+        Some(span) => {
+            let line = source.lines().nth(span.line).unwrap_or_default();
+            let number = (span.line + 1).to_string();
+            let margin = " ".repeat(number.len());
+
+            buf.push_str(&format!(
+                "{}--> {}:{}:{}\n",
+                margin,
+                path.display(),
+                span.line + 1,
+                span.start + 1,
+            ));
+            buf.push_str(&format!("{} |\n", margin));
+            buf.push_str(&format!("{} | {}\n", number, line));
+            buf.push_str(&format!(
+                "{} | {}{}\n",
+                margin,
+                " ".repeat(span.start),
+                "^".repeat((span.end - span.start).max(1)),
+            ));
+        }
+        None => buf.push_str(&format!(" --> {}\n", path.display())),
+    }
+    buf
+}
@@ -1,20 +1,56 @@
 mod args;
+mod build;
 mod exec;
+mod explain;
+mod fmt;
+mod graph;
+mod inspect;
+mod lint;
 mod monitor;
+mod parse;
+mod summary;
+
+use std::collections::BTreeMap;
+use std::process;
 
 use clap::{crate_authors, crate_version, App, AppSettings, Arg, ArgMatches, Result as ClapResult};
 use inflector::Inflector;
 
-use n3_builder::{ast, dirs, inflector, ExecRoot, GlobalVars, Result, Vars, QUERY_SPLIT_1};
+use n3_builder::optimize::PASSES;
+use n3_builder::{
+    ast, did_you_mean, dirs, inflector, suggest, ExecRoot, GlobalVars, Result, Vars, BACKENDS,
+    PROGRAM_FORMATS, QUERY_SPLIT_1,
+};
 
 use crate::args::Command;
 
+pub const SWITCH_FN_0: &[(&str, FnParse)] = &[
+    ("explain", self::explain::f),
+    ("fmt", self::fmt::f),
+    ("graph", self::graph::f),
+    ("inspect", self::inspect::f),
+    ("lint", self::lint::f),
+    ("parse", self::parse::f),
+    ("summary", self::summary::f),
+];
 pub const SWITCH_FN_1: &[(&str, FnExec)] = &[("monitor", self::monitor::f)];
-pub const SWITCH_FN_2: &[&str] = &["train", "eval", "publish"];
+pub const SWITCH_FN_2: &[&str] = &["build", "train", "eval", "publish"];
 
+pub type FnParse = fn(&[String]) -> Result<()>;
 pub type FnExec = fn(Command) -> Result<()>;
 
-fn main() -> Result<()> {
+fn main() {
+    if let Err(e) = try_main() {
+        eprintln!("error[{}]: {:?}", e.code(), e);
+        eprintln!(
+            "\nFor more information about this error, try `n3 explain {}`.",
+            e.code()
+        );
+        process::exit(1);
+    }
+}
+
+fn try_main() -> Result<()> {
     // Step 1. parse envs
     let env = GlobalVars::default();
     let env_vars = env.to_variables();
@@ -24,13 +60,18 @@ fn main() -> Result<()> {
     let command = cmd_args.get(0);
     let exec = cmd_args.get(1);
 
+    // Step 3-0. execute commands that parse their own args
+    if let Some((_, f)) = command.and_then(|x| SWITCH_FN_0.iter().find(|(k, _)| k == x)) {
+        f(&cmd_args)
+    }
     // Step 3-1. execute commands that don't need a root
-    if let Some((_, f)) = command
+    else if let Some((_, f)) = command
         .map(|x| SWITCH_FN_1.iter().find(|(k, _)| k == x))
         .flatten()
     {
         let args_set = [&env_vars];
-        match unsafe { parse_args(&args_set) } {
+        let helps = range_helps(&args_set);
+        match unsafe { parse_args(&args_set, &helps, None) } {
             Ok(_) => {
                 f(Command {
                     command: command.unwrap(),
@@ -47,19 +88,39 @@ fn main() -> Result<()> {
         drop(env);
         Ok(())
     }
-    // Step 3-2. execute commands with a root
+    // Step 3-2. reject unknown commands
+    else if let Some(command) = command.filter(|x| !x.starts_with('-') && !is_command(x)) {
+        let commands = SWITCH_FN_0
+            .iter()
+            .map(|(k, _)| k)
+            .chain(SWITCH_FN_1.iter().map(|(k, _)| k))
+            .chain(SWITCH_FN_2);
+        eprintln!("error: no such command: `{}`", command);
+        if let Some(hint) = did_you_mean(&suggest(command, commands)) {
+            eprintln!("\n{}", hint);
+        }
+        process::exit(1)
+    }
+    // Step 3-3. execute commands with a root
     else if let Some(exec) = exec {
         let mut root = ExecRoot::try_new(env.clone(), Default::default())?;
         let args = root.get(&exec.to_pascal_case())?;
 
         let args_set = [&env_vars, &args.to_variables()];
-        match unsafe { parse_args(&args_set) } {
-            Ok(_) => {
-                crate::exec::execute(Command {
+        let helps = range_helps(&args_set);
+        match unsafe { parse_args(&args_set, &helps, args.description()) } {
+            Ok(matches) => {
+                let options = matches.and_then(|x| crate::build::BuildOptions::new(&x));
+                let command = Command {
                     command: command.unwrap(),
                     env: &env,
                     args: Some(args),
-                })?;
+                };
+                if command.command == "build" {
+                    crate::build::f(command, options?)?;
+                } else {
+                    crate::exec::execute(command)?;
+                }
             }
             Err(e) => {
                 println!("{}", e);
@@ -71,9 +132,10 @@ fn main() -> Result<()> {
         drop(env);
         Ok(())
     }
-    // Step 3-3. show help message
+    // Step 3-4. show help message
     else {
-        let app = unsafe { subcommand_args(&env_vars, app()) };
+        let helps = range_helps(&[&env_vars]);
+        let app = unsafe { subcommand_args(&env_vars, &helps, app()) };
         let matches = app.get_matches_from(&["--help"]);
 
         // drop order: app (=matches) -> args
@@ -83,14 +145,27 @@ fn main() -> Result<()> {
     }
 }
 
-unsafe fn parse_args<'a, 'b, 'c>(args: &[&'a Vars]) -> ClapResult<Result<ArgMatches<'b>>>
+fn is_command(command: &str) -> bool {
+    SWITCH_FN_0.iter().any(|(k, _)| k == &command)
+        || SWITCH_FN_1.iter().any(|(k, _)| k == &command)
+        || SWITCH_FN_2.contains(&command)
+}
+
+unsafe fn parse_args<'a, 'b, 'c>(
+    args: &[&'a Vars],
+    helps: &'a Helps,
+    about: Option<&'a str>,
+) -> ClapResult<Result<ArgMatches<'b>>>
 where
     'a: 'b,
     'b: 'c,
 {
     let mut app = app();
+    if let Some(about) = about {
+        app = app.about(about);
+    }
     for args in args {
-        app = subcommand_args(args, app);
+        app = subcommand_args(args, helps, app);
     }
 
     let matches = app.get_matches_safe()?;
@@ -112,7 +187,11 @@ fn apply(matches: &ArgMatches, args: &Vars) -> Result<()> {
     Ok(())
 }
 
-unsafe fn subcommand_args<'a, 'b, 'c>(args: &'a Vars, mut app: App<'b, 'c>) -> App<'b, 'c>
+unsafe fn subcommand_args<'a, 'b, 'c>(
+    args: &'a Vars,
+    helps: &'a Helps,
+    mut app: App<'b, 'c>,
+) -> App<'b, 'c>
 where
     'a: 'b,
     'b: 'c,
@@ -130,12 +209,18 @@ where
         if let Some(shortcut) = &var.shortcut {
             arg = arg.short(shortcut.to_lowercase());
         }
-        if let Some(desc) = &var.description {
+        if let Some(help) = helps.get(name) {
+            arg = arg.help(help);
+        } else if let Some(desc) = &var.description {
             arg = arg.help(desc);
         }
         if let Some(ast::LetType::List(_)) = var.ty {
             arg = arg.multiple(true);
         }
+        if let Some(variants) = var.ty.as_ref().and_then(unwrap_enum_variants) {
+            let variants: Vec<_> = variants.iter().map(|x| x.as_str()).collect();
+            arg = arg.possible_values(&variants);
+        }
         if var.value.is_none() {
             arg = arg.required(true);
         }
@@ -145,12 +230,38 @@ where
     app
 }
 
+/// The help messages of the args with ranges, which should live as long as the app.
+type Helps = BTreeMap<String, String>;
+
+fn range_helps(args: &[&Vars]) -> Helps {
+    args.iter()
+        .flat_map(|args| args.inner.iter())
+        .filter_map(|(name, var)| {
+            let var = var.borrow();
+            let range = var.range.as_ref()?;
+            let help = match &var.description {
+                Some(desc) => format!("{} {:?}", desc, range),
+                None => format!("{:?}", range),
+            };
+            Some((name.clone(), help))
+        })
+        .collect()
+}
+
+fn unwrap_enum_variants(ty: &ast::LetType) -> Option<&[String]> {
+    match ty {
+        ast::LetType::List(ty) => unwrap_enum_variants(ty),
+        _ => ty.unwrap_enum(),
+    }
+}
+
 fn app<'a, 'b, 'c>() -> App<'b, 'c>
 where
     'a: 'b,
     'b: 'c,
 {
     let exec_commands: Vec<_> = SWITCH_FN_2.iter().map(|x| ("command", *x)).collect();
+    let passes: Vec<_> = PASSES.iter().map(|x| x.name).collect();
 
     App::new("n3")
         .version(crate_version!())
@@ -163,9 +274,41 @@ where
                 .takes_value(true),
         )
         .setting(AppSettings::ArgRequiredElseHelp)
+        .setting(AppSettings::DeriveDisplayOrder)
         .setting(AppSettings::ColoredHelp)
         .setting(AppSettings::ColorAuto)
         .setting(AppSettings::GlobalVersion)
         .arg(Arg::with_name("command").required(true))
         .arg(Arg::with_name("exec").required_ifs(&exec_commands))
+        .arg(
+            Arg::with_name("emit")
+                .long("emit")
+                .takes_value(true)
+                .possible_values(PROGRAM_FORMATS)
+                .help("The format of the built program, for `n3 build`"),
+        )
+        .arg(
+            Arg::with_name("output")
+                .long("output")
+                .takes_value(true)
+                .value_name("PATH")
+                .help("The file to save the built program, for `n3 build`"),
+        )
+        .arg(
+            Arg::with_name("disable-pass")
+                .long("disable-pass")
+                .takes_value(true)
+                .multiple(true)
+                .number_of_values(1)
+                .possible_values(&passes)
+                .value_name("PASS")
+                .help("The optimization pass to be skipped, for `n3 build`"),
+        )
+        .arg(
+            Arg::with_name("backend")
+                .long("backend")
+                .takes_value(true)
+                .possible_values(BACKENDS)
+                .help("The backend which implements the extern nodes, for `n3 build`"),
+        )
 }
@@ -0,0 +1,34 @@
+use std::fs;
+
+use clap::{App, Arg};
+
+use n3_builder::{ast, Parser, Result};
+
+pub fn f(args: &[String]) -> Result<()> {
+    let matches = App::new("n3 parse")
+        .bin_name("n3 parse")
+        .about("Parse the n3 source file and print the AST")
+        .arg(
+            Arg::with_name("json")
+                .long("json")
+                .help("Print the AST as JSON"),
+        )
+        .arg(
+            Arg::with_name("file")
+                .required(true)
+                .help("The n3 source file to be parsed"),
+        )
+        .get_matches_from(args);
+
+    let source = fs::read_to_string(matches.value_of("file").unwrap())?;
+    let file = Parser::default().parse_file(&source)?;
+
+    if matches.is_present("json") {
+        let json = ast::with_shared_variables(|| serde_json::to_string_pretty(&file))
+            .map_err(std::io::Error::from)?;
+        println!("{}", json);
+    } else {
+        print!("{:?}", &file);
+    }
+    Ok(())
+}
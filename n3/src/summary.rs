@@ -0,0 +1,229 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+use clap::{App, Arg};
+
+use n3_builder::{ast, dirs, GlobalVars, NodeRoot, NodeSummary, Parser, Result};
+
+use crate::fmt::collect_files;
+
+const HEADER: [&str; 7] = [
+    "Node",
+    "Input",
+    "Output",
+    "Params",
+    "FLOPs",
+    "Memory",
+    "Variables",
+];
+
+/// The size of an element, assuming `float32`.
+const ELEMENT_SIZE: u64 = 4;
+
+pub fn f(args: &[String]) -> Result<()> {
+    let matches = App::new("n3 summary")
+        .bin_name("n3 summary")
+        .about("Print the shapes and the estimated costs of each node in the model")
+        .arg(
+            Arg::with_name("input")
+                .short("i")
+                .long("input")
+                .takes_value(true)
+                .multiple(true)
+                .number_of_values(1)
+                .value_name("[NAME=]DIMS")
+                .validator(|x| parse_input(&x).map(|_| ()))
+                .help("The input shape of the model (e.g. 1,28,28)"),
+        )
+        .arg(
+            Arg::with_name("batch-size")
+                .short("b")
+                .long("batch-size")
+                .takes_value(true)
+                .value_name("SIZE")
+                .default_value("1")
+                .validator(|x| x.parse::<u64>().map(|_| ()).map_err(|e| e.to_string()))
+                .help("The batch size to estimate the memory usage"),
+        )
+        .arg(
+            Arg::with_name("node")
+                .required(true)
+                .help("The name of the node to be summarized"),
+        )
+        .arg(
+            Arg::with_name("files")
+                .multiple(true)
+                .help("The files or directories which define the user nodes"),
+        )
+        .get_matches_from(args);
+
+    let mut files = vec![];
+    for path in matches.values_of("files").into_iter().flatten() {
+        collect_files(PathBuf::from(path), &mut files)?;
+    }
+
+    let n3_source_root = GlobalVars::default().get_string(dirs::N3_SOURCE_ROOT).ok();
+    let root = NodeRoot::new(n3_source_root.as_deref());
+    let parser = Parser::default();
+    for file in files {
+        let source = fs::read_to_string(&file)?;
+        let name = parser.parse_file(&source)?.node.name;
+        root.add_source(name, source);
+    }
+
+    let input = matches.values_of("input").map(|values| {
+        let shapes: BTreeMap<_, _> = values.map(|x| parse_input(x).unwrap()).collect();
+        ast::Shapes::new(shapes)
+    });
+
+    let summary = root.summarize(matches.value_of("node").unwrap(), input.as_ref())?;
+
+    let mut rows = vec![HEADER.iter().map(|x| x.to_string()).collect()];
+    push_rows(&summary, 0, &mut rows);
+    print_table(&rows);
+
+    let cost = summary.cost;
+    let batch_size: u64 = matches.value_of("batch-size").unwrap().parse().unwrap();
+    println!();
+    println!("Total params: {}", fmt_count(cost.params));
+    println!("Total FLOPs per sample: {}", fmt_count(cost.flops));
+    println!("Total memory per sample: {}", fmt_count(cost.memory));
+    println!(
+        "Estimated memory (batch size {}): {} for params, {} for activations",
+        batch_size,
+        fmt_bytes(cost.params),
+        fmt_bytes(cost.memory.map(|x| x * batch_size)),
+    );
+    Ok(())
+}
+
+/// Parse `[NAME=]DIMS`, like `1,28,28` or `y=10`.
+pub(crate) fn parse_input(
+    input: &str,
+) -> std::result::Result<(String, Option<ast::Shape>), String> {
+    let (name, dims) = match input.find('=') {
+        Some(at) => (input[..at].trim(), &input[at + 1..]),
+        None => ("x", input),
+    };
+
+    let dims = dims
+        .split(',')
+        .map(|x| x.trim().parse::<u64>().map(Into::into))
+        .collect::<std::result::Result<_, _>>()
+        .map_err(|_| format!("the shape should be the comma-separated sizes: {:?}", dims))?;
+    Ok((name.to_string(), Some(ast::Shape(dims))))
+}
+
+fn push_rows(node: &NodeSummary, depth: usize, rows: &mut Vec<Vec<String>>) {
+    let mut name = if depth == 0 {
+        node.name.clone()
+    } else {
+        format!("{}{}. {}", "  ".repeat(depth), node.id, &node.name)
+    };
+    if let Some(repeat) = node.repeat {
+        name = format!("{} * {}", name, repeat);
+    }
+
+    let variables: Vec<_> = node
+        .variables
+        .iter()
+        .map(|(name, value)| format!("{}={:?}", name, value))
+        .collect();
+
+    rows.push(vec![
+        name,
+        fmt_shapes(node.input.as_ref()),
+        fmt_shapes(node.output.as_ref()),
+        fmt_count(node.cost.params),
+        fmt_count(node.cost.flops),
+        fmt_count(node.cost.memory),
+        variables.join(", "),
+    ]);
+
+    for child in &node.children {
+        push_rows(child, depth + 1, rows);
+    }
+}
+
+fn print_table(rows: &[Vec<String>]) {
+    let widths: Vec<_> = (0..HEADER.len())
+        .map(|col| rows.iter().map(|x| x[col].chars().count()).max().unwrap())
+        .collect();
+
+    for row in rows {
+        let mut line = String::new();
+        for (col, (cell, &width)) in row.iter().zip(&widths).enumerate() {
+            if col > 0 {
+                line.push_str("  ");
+            }
+            // note: the counts are aligned to the right
+            if (3..6).contains(&col) {
+                line.push_str(&format!("{:>width$}", cell, width = width));
+            } else {
+                line.push_str(&format!("{:<width$}", cell, width = width));
+            }
+        }
+        println!("{}", line.trim_end());
+    }
+}
+
+/// Format the shapes, like `[1, 28, 28]` or `x=[1, 28, 28] y=[10]`.
+fn fmt_shapes(shapes: Option<&ast::Shapes>) -> String {
+    let shapes = match shapes {
+        Some(shapes) => shapes.0.borrow(),
+        None => return "-".to_string(),
+    };
+
+    let fmt_shape = |shape: &Option<ast::Shape>| match shape {
+        Some(shape) => {
+            let dims: Vec<_> = shape.0.iter().map(|x| format!("{:?}", x)).collect();
+            format!("[{}]", dims.join(", "))
+        }
+        None => "*".to_string(),
+    };
+
+    match shapes.iter().collect::<Vec<_>>().as_slice() {
+        [(name, shape)] if name.as_str() == "x" => fmt_shape(shape),
+        shapes => {
+            let shapes: Vec<_> = shapes
+                .iter()
+                .map(|(name, shape)| format!("{}={}", name, fmt_shape(shape)))
+                .collect();
+            shapes.join(" ")
+        }
+    }
+}
+
+/// Format the count with the thousands separators, like `3,274,634`.
+fn fmt_count(count: Option<u64>) -> String {
+    let count = match count {
+        Some(count) => count.to_string(),
+        None => return "?".to_string(),
+    };
+
+    let mut formatted = String::new();
+    for (index, digit) in count.chars().enumerate() {
+        if index > 0 && (count.len() - index) % 3 == 0 {
+            formatted.push(',');
+        }
+        formatted.push(digit);
+    }
+    formatted
+}
+
+/// Format the size of the elements, like `12.49 MiB`.
+fn fmt_bytes(count: Option<u64>) -> String {
+    let bytes = match count {
+        Some(count) => (count * ELEMENT_SIZE) as f64,
+        None => return "?".to_string(),
+    };
+
+    let units = ["B", "KiB", "MiB", "GiB", "TiB"];
+    let exp = ((bytes.max(1.0).log2() / 10.0) as usize).min(units.len() - 1);
+    if exp == 0 {
+        format!("{} B", bytes)
+    } else {
+        format!("{:.2} {}", bytes / 1024f64.powi(exp as i32), units[exp])
+    }
+}